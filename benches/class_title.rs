@@ -0,0 +1,36 @@
+//! 比较每次分配新`String`与复用`String`获取窗口标题、类名的性能。
+//! 需要在有桌面的Windows上运行，使用桌面窗口（`Progman`）作为目标。
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use window_inspector::class_title::get_window_class;
+use window_inspector::class_title::get_window_class_into;
+use window_inspector::class_title::get_window_title;
+use window_inspector::class_title::get_window_title_into;
+use window_inspector::find::get_hwnd;
+
+fn bench_class_title(c: &mut Criterion) {
+    let hwnd = get_hwnd("Progman", "").unwrap();
+    let mut group = c.benchmark_group("class_title");
+
+    group.bench_function("get_window_title", |b| {
+        b.iter(|| get_window_title(hwnd).unwrap())
+    });
+    let mut title = String::new();
+    group.bench_function("get_window_title_into", |b| {
+        b.iter(|| get_window_title_into(hwnd, &mut title).unwrap())
+    });
+    group.bench_function("get_window_class", |b| {
+        b.iter(|| get_window_class(hwnd).unwrap())
+    });
+    let mut class = String::new();
+    group.bench_function("get_window_class_into", |b| {
+        b.iter(|| get_window_class_into(hwnd, &mut class).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_class_title);
+criterion_main!(benches);
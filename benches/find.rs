@@ -0,0 +1,80 @@
+//! 比较分片缓存与原先的全局`Mutex`缓存在多线程轮询时的性能。
+//! 需要在有桌面的Windows上运行，使用任务栏窗口（`Shell_TrayWnd`）作为查找目标。
+
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::thread;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use lru::LruCache;
+use window_inspector::exist::is_window_exist;
+use window_inspector::find::get_hwnd;
+use window_inspector::find::get_hwnd_ref_cache;
+
+const WINDOW_CLASS: &str = "Shell_TrayWnd";
+const THREAD_COUNT: usize = 8;
+const CALLS_PER_THREAD: usize = 1000;
+
+/// 原先的实现：一个全局`Mutex`，命中时加锁一次，未命中时加锁三次。
+fn get_hwnd_global_mutex(
+    cache: &Mutex<LruCache<(String, String), usize>>,
+    window_class: &str,
+    window_title: &str,
+) -> usize {
+    let key = (window_class.to_string(), window_title.to_string());
+    let hwnd = cache.lock().unwrap().get(&key).copied();
+    if hwnd.is_some_and(is_window_exist) {
+        hwnd.unwrap()
+    } else {
+        cache.lock().unwrap().pop(&key);
+        let hwnd = get_hwnd(window_class, window_title).unwrap();
+        cache.lock().unwrap().put(key, hwnd);
+        hwnd
+    }
+}
+
+fn bench_find(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find");
+
+    group.bench_function("get_hwnd", |b| {
+        b.iter(|| get_hwnd(WINDOW_CLASS, "").unwrap())
+    });
+    group.bench_function("get_hwnd_ref_cache", |b| {
+        b.iter(|| get_hwnd_ref_cache(WINDOW_CLASS, "").unwrap())
+    });
+
+    let global_mutex_cache = Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap()));
+    group.bench_function("contended/global_mutex", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for _ in 0..THREAD_COUNT {
+                    s.spawn(|| {
+                        for _ in 0..CALLS_PER_THREAD {
+                            get_hwnd_global_mutex(&global_mutex_cache, WINDOW_CLASS, "");
+                        }
+                    });
+                }
+            })
+        })
+    });
+    group.bench_function("contended/sharded", |b| {
+        b.iter(|| {
+            thread::scope(|s| {
+                for _ in 0..THREAD_COUNT {
+                    s.spawn(|| {
+                        for _ in 0..CALLS_PER_THREAD {
+                            get_hwnd_ref_cache(WINDOW_CLASS, "").unwrap();
+                        }
+                    });
+                }
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_find);
+criterion_main!(benches);
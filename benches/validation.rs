@@ -0,0 +1,34 @@
+//! 比较[`Validation::Eager`]与[`Validation::Lazy`]下轮询窗口信息的性能。
+//! 需要在有桌面的Windows上运行，使用桌面窗口（`Progman`）作为目标。
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use window_inspector::class_title::get_window_title_into;
+use window_inspector::find::get_hwnd;
+use window_inspector::mode::set_validation;
+use window_inspector::mode::Validation;
+use window_inspector::position_size::get_window_xywh_include_shadow;
+
+fn bench_validation(c: &mut Criterion) {
+    let hwnd = get_hwnd("Progman", "").unwrap();
+    let mut group = c.benchmark_group("validation");
+
+    let mut title = String::new();
+    for validation in [Validation::Eager, Validation::Lazy] {
+        set_validation(validation);
+        group.bench_function(
+            format!("get_window_xywh_include_shadow/{:?}", validation),
+            |b| b.iter(|| get_window_xywh_include_shadow(hwnd).unwrap()),
+        );
+        group.bench_function(format!("get_window_title_into/{:?}", validation), |b| {
+            b.iter(|| get_window_title_into(hwnd, &mut title).unwrap())
+        });
+    }
+    set_validation(Validation::Eager);
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_validation);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+/* window_inspector的C接口，对应src/ffi.rs。编译动态库：cargo rustc --release --crate-type cdylib --features ffi */
+#ifndef WINDOW_INSPECTOR_H
+#define WINDOW_INSPECTOR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define WI_OK 0
+#define WI_ERROR_NOT_FOUND 1
+#define WI_ERROR_ACCESS_DENIED 2
+#define WI_ERROR_INVALID_HANDLE 3
+#define WI_ERROR_TIMEOUT 4
+#define WI_ERROR_INVALID_ARGUMENT 5
+#define WI_ERROR_PANIC 6
+#define WI_ERROR_OTHER 99
+
+typedef struct WiRect {
+    int32_t x;
+    int32_t y;
+    uint32_t width;
+    uint32_t height;
+} WiRect;
+
+int32_t wi_find_window(const uint16_t *window_class, const uint16_t *window_title, uintptr_t *out_hwnd);
+int32_t wi_get_rect(uintptr_t hwnd, int32_t exclude_shadow, WiRect *out_rect);
+int32_t wi_set_topmost(uintptr_t hwnd, int32_t top_most);
+size_t wi_last_error_message(uint16_t *buffer, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
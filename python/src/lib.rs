@@ -0,0 +1,134 @@
+//! window_inspector的Python绑定。窗口句柄在Python中是`int`。
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use window_inspector::class_title;
+use window_inspector::enumerate;
+use window_inspector::error::WindowInspectorError;
+use window_inspector::find;
+use window_inspector::foreground;
+use window_inspector::position_size;
+use window_inspector::process;
+use window_inspector::top_most;
+
+create_exception!(window_inspector, WindowInspectorException, PyException);
+
+/// 将库的错误转换为Python异常，异常的`args`为(错误信息, HRESULT或None)。
+fn to_py_err(e: WindowInspectorError) -> PyErr {
+    WindowInspectorException::new_err((e.to_string(), e.os_code()))
+}
+
+/// 按窗口类名和标题查找顶层窗口，空字符串表示不限制。
+#[pyfunction]
+#[pyo3(signature = (class_name = "", title = ""))]
+fn find_window(class_name: &str, title: &str) -> PyResult<usize> {
+    find::get_hwnd(class_name, title).map_err(to_py_err)
+}
+
+/// 获取所有顶层窗口，按Z序从上到下排列。
+#[pyfunction]
+fn list_windows() -> PyResult<Vec<usize>> {
+    enumerate::get_top_level_windows().map_err(to_py_err)
+}
+
+/// 获取进程的所有顶层窗口。
+#[pyfunction]
+fn list_process_windows(process_id: u32) -> PyResult<Vec<usize>> {
+    enumerate::get_process_windows(process_id).map_err(to_py_err)
+}
+
+/// 获取窗口标题。
+#[pyfunction]
+fn get_window_title(hwnd: usize) -> PyResult<String> {
+    class_title::get_window_title(hwnd).map_err(to_py_err)
+}
+
+/// 获取窗口类名。
+#[pyfunction]
+fn get_window_class(hwnd: usize) -> PyResult<String> {
+    class_title::get_window_class(hwnd).map_err(to_py_err)
+}
+
+/// 获取窗口所属进程ID。
+#[pyfunction]
+fn get_window_process(hwnd: usize) -> PyResult<u32> {
+    process::get_window_process(hwnd as isize).map_err(to_py_err)
+}
+
+/// 获取窗口矩形(x, y, width, height)，相对于屏幕。
+#[pyfunction]
+#[pyo3(signature = (hwnd, exclude_shadow = false))]
+fn get_window_rect(hwnd: usize, exclude_shadow: bool) -> PyResult<(i32, i32, u32, u32)> {
+    if exclude_shadow {
+        position_size::get_window_xywh_exclude_shadow(hwnd)
+    } else {
+        position_size::get_window_xywh_include_shadow(hwnd)
+    }
+    .map_err(to_py_err)
+}
+
+/// 获取客户区矩形(x, y, width, height)，相对于屏幕。
+#[pyfunction]
+fn get_client_rect(hwnd: usize) -> PyResult<(i32, i32, u32, u32)> {
+    position_size::get_client_xywh(hwnd).map_err(to_py_err)
+}
+
+/// 移动窗口。
+#[pyfunction]
+fn move_window(hwnd: usize, x: i32, y: i32, width: u32, height: u32) -> PyResult<()> {
+    position_size::move_window_to_xywh(hwnd, x, y, width, height).map_err(to_py_err)
+}
+
+/// 获取前台窗口，没有时为0。
+#[pyfunction]
+fn get_foreground_window() -> usize {
+    foreground::get_foreground_hwnd()
+}
+
+/// 设置前台窗口。
+#[pyfunction]
+fn set_foreground_window(hwnd: usize) -> PyResult<()> {
+    foreground::set_foreground_window(hwnd).map_err(to_py_err)
+}
+
+/// 获取窗口是否置顶。
+#[pyfunction]
+fn is_top_most(hwnd: usize) -> PyResult<bool> {
+    top_most::get_window_top_most(hwnd).map_err(to_py_err)
+}
+
+/// 设置或取消窗口置顶。
+#[pyfunction]
+#[pyo3(signature = (hwnd, top_most = true))]
+fn set_top_most(hwnd: usize, top_most: bool) -> PyResult<()> {
+    if top_most {
+        top_most::set_window_top_most(hwnd)
+    } else {
+        top_most::cancel_window_top_most(hwnd)
+    }
+    .map_err(to_py_err)
+}
+
+#[pymodule]
+#[pyo3(name = "window_inspector")]
+fn window_inspector_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add(
+        "WindowInspectorException",
+        m.py().get_type::<WindowInspectorException>(),
+    )?;
+    m.add_function(wrap_pyfunction!(find_window, m)?)?;
+    m.add_function(wrap_pyfunction!(list_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(list_process_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_title, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_class, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_process, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_rect, m)?)?;
+    m.add_function(wrap_pyfunction!(get_client_rect, m)?)?;
+    m.add_function(wrap_pyfunction!(move_window, m)?)?;
+    m.add_function(wrap_pyfunction!(get_foreground_window, m)?)?;
+    m.add_function(wrap_pyfunction!(set_foreground_window, m)?)?;
+    m.add_function(wrap_pyfunction!(is_top_most, m)?)?;
+    m.add_function(wrap_pyfunction!(set_top_most, m)?)?;
+    Ok(())
+}
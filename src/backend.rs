@@ -0,0 +1,475 @@
+//! 底层Win32调用的抽象。
+//!
+//! 库中基础的查询和操作（窗口是否存在、类名、标题、查找窗口、位置尺寸、所属进程、前台窗口、移动窗口、置顶）都通过[`Backend`]完成。
+//! 默认使用调用Win32 API的[`Win32Backend`]；测试时可以用[`with_backend`]在当前线程临时换成[`MockBackend`]，
+//! 在没有桌面会话的CI机器上测试窗口处理逻辑。
+//!
+//! # 示例
+//! ```
+//! use std::sync::Arc;
+//!
+//! use window_inspector::backend::with_backend;
+//! use window_inspector::backend::MockBackend;
+//! use window_inspector::backend::MockWindow;
+//! use window_inspector::class_title::get_window_title;
+//! use window_inspector::find::get_hwnd;
+//!
+//! let backend = Arc::new(MockBackend::new());
+//! let hwnd = backend.add_window(MockWindow::new("Notepad", "无标题 - 记事本"));
+//! with_backend(backend.clone(), || {
+//!     assert_eq!(get_hwnd("Notepad", "").unwrap(), hwnd);
+//!     assert_eq!(get_window_title(hwnd).unwrap(), "无标题 - 记事本");
+//! });
+//! ```
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::c_void;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use widestring::U16CStr;
+use windows::core::HRESULT;
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::SetLastError;
+use windows::Win32::Foundation::ERROR_INVALID_WINDOW_HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::WIN32_ERROR;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use windows::Win32::UI::WindowsAndMessaging::MoveWindow;
+use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::HWND_NOTOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+use crate::wide::to_pcwstr_or_null;
+
+/// 底层的窗口查询和操作。
+/// 各方法不检查窗口是否存在，由调用者通过[`Backend::is_window`]检查。
+pub trait Backend {
+    /// 窗口是否存在。对应`IsWindow`。
+    fn is_window(&self, hwnd: usize) -> bool;
+    /// 获取窗口类名，写入`class`。对应`GetClassNameW`。
+    fn window_class(&self, hwnd: usize, class: &mut String) -> Result<()>;
+    /// 获取窗口标题，写入`title`。对应`GetWindowTextW`。
+    fn window_title(&self, hwnd: usize, title: &mut String) -> Result<()>;
+    /// 按类名和标题查找顶层窗口，空字符串表示不限制。对应`FindWindowExW`。
+    fn find_window(&self, window_class: &U16CStr, window_title: &U16CStr) -> Result<usize>;
+    /// 获取窗口位置尺寸（包括阴影），(x, y, width, height)。对应`GetWindowRect`。
+    fn window_xywh(&self, hwnd: usize) -> Result<(i32, i32, u32, u32)>;
+    /// 获取窗口所属进程ID。对应`GetWindowThreadProcessId`。
+    fn window_process(&self, hwnd: usize) -> Result<u32>;
+    /// 获取前台窗口，没有时为0。对应`GetForegroundWindow`。
+    fn foreground_window(&self) -> usize;
+    /// 设置前台窗口。对应`SetForegroundWindow`。
+    fn set_foreground_window(&self, hwnd: usize) -> Result<()>;
+    /// 移动窗口。对应`MoveWindow`。
+    fn move_window(&self, hwnd: usize, x: i32, y: i32, width: u32, height: u32) -> Result<()>;
+    /// 获取窗口是否置顶。对应`GetWindowLongW(GWL_EXSTYLE)`。
+    fn is_top_most(&self, hwnd: usize) -> Result<bool>;
+    /// 设置窗口置顶状态。对应`SetWindowPos(HWND_TOPMOST/HWND_NOTOPMOST)`。
+    fn set_top_most(&self, hwnd: usize, is_top_most: bool) -> Result<()>;
+}
+
+thread_local! {
+    /// 每个线程复用的UTF-16缓冲区。
+    static WIDE_BUFFER: RefCell<[u16; 1024]> = const { RefCell::new([0; 1024]) };
+    /// 当前线程临时使用的后端，为`None`时使用[`Win32Backend`]。
+    static CURRENT_BACKEND: RefCell<Option<Arc<dyn Backend>>> = const { RefCell::new(None) };
+}
+
+/// 用线程的UTF-16缓冲区调用`f`。
+/// `GetWindowTextW`等待目标窗口处理`WM_GETTEXT`期间会处理发送到本线程的消息，
+/// 其中的处理函数可能再次获取标题，此时缓冲区已被借用，改用栈上的缓冲区。
+fn with_wide_buffer<R>(f: impl FnOnce(&mut [u16]) -> R) -> R {
+    WIDE_BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut buffer) => f(&mut buffer[..]),
+        Err(_) => f(&mut [0; 1024]),
+    })
+}
+
+/// 将UTF-16解码到`s`中，复用`s`已有的容量。
+fn decode_utf16_into(wide: &[u16], s: &mut String) {
+    s.clear();
+    s.extend(
+        char::decode_utf16(wide.iter().copied()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+    );
+}
+
+/// 调用Win32 API的后端，是默认的后端。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Win32Backend;
+
+impl Backend for Win32Backend {
+    fn is_window(&self, hwnd: usize) -> bool {
+        unsafe { IsWindow(HWND(hwnd as *mut c_void)) }.as_bool()
+    }
+
+    fn window_class(&self, hwnd: usize, class: &mut String) -> Result<()> {
+        with_wide_buffer(|buffer| {
+            match unsafe { GetClassNameW(HWND(hwnd as *mut c_void), buffer) } {
+                0 => Err(WindowInspectorError::GetClassNameWFailed {
+                    error: windows::core::Error::from_win32(),
+                }),
+                n => {
+                    decode_utf16_into(&buffer[..n as usize], class);
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn window_title(&self, hwnd: usize, title: &mut String) -> Result<()> {
+        with_wide_buffer(|buffer| {
+            match unsafe { GetWindowTextW(HWND(hwnd as *mut c_void), buffer) } {
+                0 => Err(WindowInspectorError::GetWindowTextWFailed {
+                    error: windows::core::Error::from_win32(),
+                }),
+                n => {
+                    decode_utf16_into(&buffer[..n as usize], title);
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn find_window(&self, window_class: &U16CStr, window_title: &U16CStr) -> Result<usize> {
+        match unsafe {
+            FindWindowExW(
+                None,
+                None,
+                to_pcwstr_or_null(window_class),
+                to_pcwstr_or_null(window_title),
+            )
+        } {
+            Ok(hwnd) => Ok(hwnd.0 as usize),
+            Err(e) => Err(WindowInspectorError::FindWindowExWFailed {
+                window_class: window_class.to_string_lossy(),
+                window_title: window_title.to_string_lossy(),
+                error: e,
+            }),
+        }
+    }
+
+    fn window_xywh(&self, hwnd: usize) -> Result<(i32, i32, u32, u32)> {
+        let mut rect = RECT::default();
+        match unsafe { GetWindowRect(HWND(hwnd as *mut c_void), &mut rect) } {
+            Ok(_) => Ok((
+                rect.left,
+                rect.top,
+                (rect.right - rect.left) as u32,
+                (rect.bottom - rect.top) as u32,
+            )),
+            Err(e) => Err(WindowInspectorError::GetWindowRectFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: e,
+            }),
+        }
+    }
+
+    fn window_process(&self, hwnd: usize) -> Result<u32> {
+        let mut process_id = 0;
+        if unsafe { GetWindowThreadProcessId(HWND(hwnd as *mut c_void), Some(&mut process_id)) }
+            == 0
+        {
+            return Err(WindowInspectorError::GetWindowThreadProcessIdFailed {
+                error: windows::core::Error::from_win32(),
+            });
+        }
+        Ok(process_id)
+    }
+
+    fn foreground_window(&self) -> usize {
+        unsafe { GetForegroundWindow() }.0 as usize
+    }
+
+    fn set_foreground_window(&self, hwnd: usize) -> Result<()> {
+        if !unsafe { SetForegroundWindow(HWND(hwnd as *mut c_void)) }.as_bool() {
+            return Err(WindowInspectorError::SetForegroundWindowFailed);
+        }
+        Ok(())
+    }
+
+    fn move_window(&self, hwnd: usize, x: i32, y: i32, width: u32, height: u32) -> Result<()> {
+        unsafe {
+            MoveWindow(
+                HWND(hwnd as *mut c_void),
+                x,
+                y,
+                width as i32,
+                height as i32,
+                true,
+            )
+        }
+        .map_err(|e| WindowInspectorError::MoveWindowFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        })
+    }
+
+    fn is_top_most(&self, hwnd: usize) -> Result<bool> {
+        // 扩展样式可能本来就是0，需要通过GetLastError区分失败。
+        unsafe { SetLastError(WIN32_ERROR(0)) };
+        match unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } {
+            0 => match unsafe { GetLastError() } {
+                WIN32_ERROR(0) => Ok(false),
+                error => Err(WindowInspectorError::GetWindowLongWFailed {
+                    error: windows::core::Error::from_hresult(error.to_hresult()),
+                }),
+            },
+            n => Ok((n as u32 & WS_EX_TOPMOST.0) != 0),
+        }
+    }
+
+    fn set_top_most(&self, hwnd: usize, is_top_most: bool) -> Result<()> {
+        unsafe {
+            SetWindowPos(
+                HWND(hwnd as *mut c_void),
+                if is_top_most {
+                    HWND_TOPMOST
+                } else {
+                    HWND_NOTOPMOST
+                },
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE,
+            )
+        }
+        .map_err(|e| WindowInspectorError::SetWindowPosFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        })
+    }
+}
+
+/// 在当前线程中使用`backend`执行`f`，结束后恢复原来的后端。
+/// 只影响当前线程，并行运行的测试互不干扰。
+pub fn with_backend<T>(backend: Arc<dyn Backend>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<dyn Backend>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_BACKEND.set(self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT_BACKEND.replace(Some(backend)));
+    f()
+}
+
+/// 使用当前线程的后端执行`f`。
+pub(crate) fn with_current_backend<T>(f: impl FnOnce(&dyn Backend) -> T) -> T {
+    CURRENT_BACKEND.with_borrow(|backend| match backend {
+        Some(backend) => f(backend.as_ref()),
+        None => f(&Win32Backend),
+    })
+}
+
+/// [`MockBackend`]中的窗口。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockWindow {
+    /// 窗口类名。
+    pub class: String,
+    /// 窗口标题。
+    pub title: String,
+    /// 窗口位置尺寸，(x, y, width, height)。
+    pub xywh: (i32, i32, u32, u32),
+    /// 所属进程ID。
+    pub process_id: u32,
+    /// 是否置顶。
+    pub top_most: bool,
+}
+
+impl MockWindow {
+    /// 创建指定类名和标题的窗口，其他字段为默认值。
+    pub fn new(class: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            class: class.into(),
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    windows: BTreeMap<usize, MockWindow>,
+    foreground: usize,
+    next_hwnd: usize,
+    find_window_calls: usize,
+}
+
+/// 在内存中模拟窗口的后端，用于测试。
+/// 查找窗口时按添加的顺序返回第一个匹配的窗口。
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    state: Mutex<MockState>,
+}
+
+impl MockBackend {
+    /// 创建没有任何窗口的后端。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加窗口，返回分配的窗口句柄。
+    pub fn add_window(&self, window: MockWindow) -> usize {
+        let mut state = self.state.lock().unwrap();
+        // 模拟真实句柄的对齐，且不会分配到0。
+        state.next_hwnd += 0x10;
+        let hwnd = state.next_hwnd;
+        state.windows.insert(hwnd, window);
+        hwnd
+    }
+
+    /// 移除窗口，模拟窗口被关闭。
+    pub fn remove_window(&self, hwnd: usize) -> Option<MockWindow> {
+        let mut state = self.state.lock().unwrap();
+        if state.foreground == hwnd {
+            state.foreground = 0;
+        }
+        state.windows.remove(&hwnd)
+    }
+
+    /// 获取窗口当前的状态。
+    pub fn window(&self, hwnd: usize) -> Option<MockWindow> {
+        self.state.lock().unwrap().windows.get(&hwnd).cloned()
+    }
+
+    /// 修改窗口，例如模拟标题改变。窗口不存在时不做任何事。
+    pub fn update_window(&self, hwnd: usize, f: impl FnOnce(&mut MockWindow)) {
+        if let Some(window) = self.state.lock().unwrap().windows.get_mut(&hwnd) {
+            f(window);
+        }
+    }
+
+    /// [`Backend::find_window`]被调用的次数，例如用于检查缓存是否生效。
+    pub fn find_window_calls(&self) -> usize {
+        self.state.lock().unwrap().find_window_calls
+    }
+
+    fn with_window<T>(&self, hwnd: usize, f: impl FnOnce(&mut MockWindow) -> T) -> Result<T> {
+        match self.state.lock().unwrap().windows.get_mut(&hwnd) {
+            Some(window) => Ok(f(window)),
+            None => Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            }),
+        }
+    }
+}
+
+impl Backend for MockBackend {
+    fn is_window(&self, hwnd: usize) -> bool {
+        self.state.lock().unwrap().windows.contains_key(&hwnd)
+    }
+
+    fn window_class(&self, hwnd: usize, class: &mut String) -> Result<()> {
+        self.with_window(hwnd, |window| {
+            class.clear();
+            class.push_str(&window.class);
+        })
+    }
+
+    fn window_title(&self, hwnd: usize, title: &mut String) -> Result<()> {
+        self.with_window(hwnd, |window| {
+            title.clear();
+            title.push_str(&window.title);
+        })
+    }
+
+    fn find_window(&self, window_class: &U16CStr, window_title: &U16CStr) -> Result<usize> {
+        let class = window_class.to_string_lossy();
+        let title = window_title.to_string_lossy();
+        let mut state = self.state.lock().unwrap();
+        state.find_window_calls += 1;
+        state
+            .windows
+            .iter()
+            .find(|(_, window)| {
+                (class.is_empty() || window.class == class)
+                    && (title.is_empty() || window.title == title)
+            })
+            .map(|(&hwnd, _)| hwnd)
+            .ok_or_else(|| WindowInspectorError::FindWindowExWFailed {
+                window_class: class.clone(),
+                window_title: title.clone(),
+                error: windows::core::Error::from_hresult(HRESULT::from_win32(
+                    ERROR_INVALID_WINDOW_HANDLE.0,
+                )),
+            })
+    }
+
+    fn window_xywh(&self, hwnd: usize) -> Result<(i32, i32, u32, u32)> {
+        self.with_window(hwnd, |window| window.xywh)
+    }
+
+    fn window_process(&self, hwnd: usize) -> Result<u32> {
+        self.with_window(hwnd, |window| window.process_id)
+    }
+
+    fn foreground_window(&self) -> usize {
+        self.state.lock().unwrap().foreground
+    }
+
+    fn set_foreground_window(&self, hwnd: usize) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.windows.contains_key(&hwnd) {
+            return Err(WindowInspectorError::SetForegroundWindowFailed);
+        }
+        state.foreground = hwnd;
+        Ok(())
+    }
+
+    fn move_window(&self, hwnd: usize, x: i32, y: i32, width: u32, height: u32) -> Result<()> {
+        self.with_window(hwnd, |window| window.xywh = (x, y, width, height))
+    }
+
+    fn is_top_most(&self, hwnd: usize) -> Result<bool> {
+        self.with_window(hwnd, |window| window.top_most)
+    }
+
+    fn set_top_most(&self, hwnd: usize, is_top_most: bool) -> Result<()> {
+        self.with_window(hwnd, |window| window.top_most = is_top_most)
+    }
+}
+
+#[test]
+fn test_mock_backend() {
+    use crate::find::get_hwnd;
+    use crate::position_size::get_window_xywh_include_shadow;
+    use crate::position_size::move_window_to_xywh;
+    use crate::top_most::get_window_top_most;
+    use crate::top_most::set_window_top_most;
+
+    let backend = Arc::new(MockBackend::new());
+    let hwnd = backend.add_window(MockWindow::new("Mock", "mock window"));
+    with_backend(backend.clone(), || {
+        assert_eq!(get_hwnd("", "mock window").unwrap(), hwnd);
+        move_window_to_xywh(hwnd, 10, 20, 300, 400).unwrap();
+        assert_eq!(
+            get_window_xywh_include_shadow(hwnd).unwrap(),
+            (10, 20, 300, 400)
+        );
+        set_window_top_most(hwnd).unwrap();
+        assert!(get_window_top_most(hwnd).unwrap());
+        backend.remove_window(hwnd);
+        assert!(matches!(
+            get_window_xywh_include_shadow(hwnd),
+            Err(WindowInspectorError::WindowNotExist { .. })
+        ));
+    });
+}
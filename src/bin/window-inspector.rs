@@ -0,0 +1,238 @@
+//! 命令行工具，输出JSON。需要`cli`特性。
+//!
+//! ```text
+//! window-inspector list [--all]
+//! window-inspector find [--class <类名>] [--title <标题>]
+//! window-inspector info <hwnd>
+//! window-inspector move <hwnd> <x> <y> <width> <height>
+//! window-inspector topmost <hwnd> [on|off|toggle]
+//! window-inspector foreground [<hwnd>]
+//! window-inspector watch [--interval <毫秒>]
+//! ```
+//! 窗口句柄可以是十进制或`0x`开头的十六进制。
+
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::Duration;
+
+use serde_json::json;
+use serde_json::Value;
+use window_inspector::enumerate::get_top_level_windows;
+use window_inspector::error::WindowInspectorError;
+use window_inspector::find::get_hwnd;
+use window_inspector::foreground::force_foreground;
+use window_inspector::foreground::get_foreground_info;
+use window_inspector::foreground::ForegroundInfo;
+use window_inspector::foreground::Strategy;
+use window_inspector::information::query;
+use window_inspector::information::QueryFlags;
+use window_inspector::position_size::move_window_to_xywh;
+use window_inspector::process::get_process_path;
+use window_inspector::top_most::cancel_window_top_most;
+use window_inspector::top_most::set_window_top_most;
+use window_inspector::top_most::toggle_window_top_most;
+
+const USAGE: &str = "用法：
+  window-inspector list [--all]
+  window-inspector find [--class <类名>] [--title <标题>]
+  window-inspector info <hwnd>
+  window-inspector move <hwnd> <x> <y> <width> <height>
+  window-inspector topmost <hwnd> [on|off|toggle]
+  window-inspector foreground [<hwnd>]
+  window-inspector watch [--interval <毫秒>]";
+
+/// 命令行错误。
+enum CliError {
+    /// 参数错误，输出用法。
+    Usage(String),
+    /// 库返回的错误。
+    Library(WindowInspectorError),
+}
+
+impl From<WindowInspectorError> for CliError {
+    fn from(e: WindowInspectorError) -> Self {
+        CliError::Library(e)
+    }
+}
+
+type CliResult<T> = std::result::Result<T, CliError>;
+
+fn parse_hwnd(s: Option<&String>) -> CliResult<usize> {
+    let s = s.ok_or_else(|| CliError::Usage("缺少窗口句柄".to_string()))?;
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| CliError::Usage(format!("无效的窗口句柄：{}", s)))
+}
+
+fn parse_number<T: std::str::FromStr>(s: Option<&String>, name: &str) -> CliResult<T> {
+    let s = s.ok_or_else(|| CliError::Usage(format!("缺少{}", name)))?;
+    s.parse()
+        .map_err(|_| CliError::Usage(format!("无效的{}：{}", name, s)))
+}
+
+/// 获取`--name value`形式的选项。
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a String> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+}
+
+fn xywh_json(xywh: Option<(i32, i32, u32, u32)>) -> Value {
+    match xywh {
+        Some((x, y, width, height)) => {
+            json!({ "x": x, "y": y, "width": width, "height": height })
+        }
+        None => Value::Null,
+    }
+}
+
+fn window_json(hwnd: usize) -> CliResult<Value> {
+    let info = query(hwnd, QueryFlags::all().difference(QueryFlags::PROCESS_PATH))?;
+    // 没有权限访问以管理员身份运行的进程时获取不到路径，不影响其他信息。
+    let process_path = info
+        .process_id
+        .and_then(|process_id| get_process_path(process_id).ok());
+    Ok(json!({
+        "hwnd": hwnd,
+        "class": info.class,
+        "title": info.title,
+        "process_id": info.process_id,
+        "process_path": process_path,
+        "window_rect": xywh_json(info.window_xywh_include_shadow),
+        "window_rect_exclude_shadow": xywh_json(info.window_xywh_exclude_shadow),
+        "client_rect": xywh_json(info.client_xywh),
+        "top_most": info.top_most,
+        "visible": info.visible,
+        "minimized": info.minimized,
+    }))
+}
+
+fn foreground_json(info: &ForegroundInfo) -> Value {
+    json!({
+        "hwnd": info.hwnd,
+        "title": info.title,
+        "class": info.class,
+        "process_id": info.process_id,
+        "exe_path": info.exe_path,
+    })
+}
+
+fn list(args: &[String]) -> CliResult<Value> {
+    let all = args.iter().any(|arg| arg == "--all");
+    let mut windows = Vec::new();
+    for hwnd in get_top_level_windows()? {
+        // 枚举期间窗口可能已经关闭，跳过即可。
+        let Ok(info) = query(
+            hwnd,
+            QueryFlags::CLASS | QueryFlags::TITLE | QueryFlags::PROCESS_ID | QueryFlags::VISIBLE,
+        ) else {
+            continue;
+        };
+        if !all && info.visible != Some(true) {
+            continue;
+        }
+        windows.push(json!({
+            "hwnd": hwnd,
+            "class": info.class,
+            "title": info.title,
+            "process_id": info.process_id,
+            "visible": info.visible,
+        }));
+    }
+    Ok(Value::Array(windows))
+}
+
+fn find(args: &[String]) -> CliResult<Value> {
+    let class = option(args, "--class").map_or("", String::as_str);
+    let title = option(args, "--title").map_or("", String::as_str);
+    let hwnd = get_hwnd(class, title)?;
+    window_json(hwnd)
+}
+
+fn move_window(args: &[String]) -> CliResult<Value> {
+    let hwnd = parse_hwnd(args.first())?;
+    move_window_to_xywh(
+        hwnd,
+        parse_number(args.get(1), "x")?,
+        parse_number(args.get(2), "y")?,
+        parse_number(args.get(3), "width")?,
+        parse_number(args.get(4), "height")?,
+    )?;
+    window_json(hwnd)
+}
+
+fn topmost(args: &[String]) -> CliResult<Value> {
+    let hwnd = parse_hwnd(args.first())?;
+    match args.get(1).map_or("on", String::as_str) {
+        "on" => set_window_top_most(hwnd)?,
+        "off" => cancel_window_top_most(hwnd)?,
+        "toggle" => toggle_window_top_most(hwnd)?,
+        other => return Err(CliError::Usage(format!("无效的置顶操作：{}", other))),
+    }
+    window_json(hwnd)
+}
+
+fn foreground(args: &[String]) -> CliResult<Value> {
+    if let Some(arg) = args.first() {
+        force_foreground(parse_hwnd(Some(arg))?, Strategy::Auto)?;
+    }
+    Ok(foreground_json(&get_foreground_info()?))
+}
+
+/// 前台窗口改变时输出一行JSON，直到进程被结束。
+fn watch(args: &[String]) -> CliResult<Value> {
+    let interval = match option(args, "--interval") {
+        Some(interval) => parse_number(Some(interval), "interval")?,
+        None => 200,
+    };
+    let mut last: Option<ForegroundInfo> = None;
+    loop {
+        // 锁屏等情况下没有前台窗口，等待下一次轮询。
+        if let Ok(info) = get_foreground_info() {
+            if last.as_ref() != Some(&info) {
+                println!("{}", foreground_json(&info));
+                last = Some(info);
+            }
+        }
+        sleep(Duration::from_millis(interval));
+    }
+}
+
+fn run(args: &[String]) -> CliResult<Value> {
+    let (command, rest) = args
+        .split_first()
+        .ok_or_else(|| CliError::Usage("缺少子命令".to_string()))?;
+    match command.as_str() {
+        "list" => list(rest),
+        "find" => find(rest),
+        "info" => window_json(parse_hwnd(rest.first())?),
+        "move" => move_window(rest),
+        "topmost" => topmost(rest),
+        "foreground" => foreground(rest),
+        "watch" => watch(rest),
+        other => Err(CliError::Usage(format!("未知的子命令：{}", other))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(value) => {
+            println!("{}", serde_json::to_string_pretty(&value).unwrap());
+            ExitCode::SUCCESS
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(CliError::Library(e)) => {
+            println!(
+                "{}",
+                json!({ "error": e.to_string(), "os_code": e.os_code() })
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,555 @@
+//! 窗口截图。
+
+use std::ffi::c_void;
+#[cfg(feature = "windows-ocr")]
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+#[cfg(feature = "windows-ocr")]
+use windows::Graphics::Imaging::BitmapAlphaMode;
+#[cfg(feature = "windows-ocr")]
+use windows::Graphics::Imaging::BitmapPixelFormat;
+#[cfg(feature = "windows-ocr")]
+use windows::Graphics::Imaging::SoftwareBitmap;
+#[cfg(feature = "windows-ocr")]
+use windows::Media::Ocr::OcrEngine;
+#[cfg(feature = "windows-ocr")]
+use windows::Storage::Streams::DataWriter;
+use windows::Win32::Foundation::ERROR_INVALID_PARAMETER;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Graphics::Gdi::BitBlt;
+use windows::Win32::Graphics::Gdi::CreateCompatibleDC;
+use windows::Win32::Graphics::Gdi::CreateDIBSection;
+use windows::Win32::Graphics::Gdi::DeleteDC;
+use windows::Win32::Graphics::Gdi::DeleteObject;
+use windows::Win32::Graphics::Gdi::GetDC;
+use windows::Win32::Graphics::Gdi::ReleaseDC;
+use windows::Win32::Graphics::Gdi::SelectObject;
+use windows::Win32::Graphics::Gdi::BITMAPINFO;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::CAPTUREBLT;
+use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+use windows::Win32::Graphics::Gdi::HBITMAP;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::HGDIOBJ;
+use windows::Win32::Graphics::Gdi::SRCCOPY;
+use windows::Win32::Storage::Xps::PrintWindow;
+use windows::Win32::Storage::Xps::PRINT_WINDOW_FLAGS;
+use windows::Win32::UI::HiDpi::SetThreadDpiAwarenessContext;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::PW_RENDERFULLCONTENT;
+
+#[cfg(feature = "windows-ocr")]
+use crate::com::ComGuard;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::geometry::Rect;
+use crate::position_size::get_client_size;
+use crate::position_size::get_window_rect_include_shadow;
+use crate::result::Result;
+
+/// 截图得到的图像，像素按行从上到下排列，每个像素为BGRA各一个字节，不透明。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// 获取`(x, y)`处像素的`(r, g, b)`，超出范围时返回`None`。
+    pub fn pixel(&self, x: u32, y: u32) -> Option<(u8, u8, u8)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        Some((self.pixels[i + 2], self.pixels[i + 1], self.pixels[i]))
+    }
+
+    /// 编码为BMP文件（32位，不压缩）。
+    pub fn to_bmp(&self) -> Vec<u8> {
+        const HEADER_SIZE: usize = 14 + 40;
+        let mut bmp = Vec::with_capacity(HEADER_SIZE + self.pixels.len());
+        // BITMAPFILEHEADER
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((HEADER_SIZE + self.pixels.len()) as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        // BITMAPINFOHEADER，高度为负表示从上到下。
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(self.width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(-(self.height as i32)).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&32u16.to_le_bytes());
+        bmp.extend_from_slice(&[0; 24]);
+        bmp.extend_from_slice(&self.pixels);
+        bmp
+    }
+
+    /// 编码为PNG文件（RGB，不压缩）。
+    /// PNG不允许宽或高为0，此时结果没有图像数据，多数解码器会拒绝。
+    pub fn to_png(&self) -> Vec<u8> {
+        // 每行以过滤类型0开头。
+        let mut raw = Vec::with_capacity((self.width as usize * 3 + 1) * self.height as usize);
+        let row_len = (self.width as usize * 4).max(1);
+        for row in self.pixels.chunks_exact(row_len).take(self.height as usize) {
+            raw.push(0);
+            for bgra in row.chunks_exact(4) {
+                raw.extend_from_slice(&[bgra[2], bgra[1], bgra[0]]);
+            }
+        }
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 位深8，颜色类型2（RGB），压缩、过滤、隔行扫描方式均为0。
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_png_chunk(&mut png, b"IHDR", &header);
+        write_png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// 图像文件格式，见[`save_window_image`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Bmp,
+}
+
+fn write_png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 用不压缩的deflate块包装为zlib数据，不需要引入压缩库。
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 0xFFFF;
+    let mut zlib = Vec::with_capacity(data.len() + data.len() / BLOCK_SIZE * 5 + 11);
+    zlib.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(BLOCK_SIZE).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    zlib
+}
+
+/// 内存中的32位位图，离开作用域时释放。
+struct Canvas {
+    hdc: HDC,
+    bitmap: HBITMAP,
+    old: HGDIOBJ,
+    bits: *mut c_void,
+    width: u32,
+    height: u32,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Result<Self> {
+        let info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width as i32,
+                // 高度为负表示从上到下。
+                biHeight: -(height as i32),
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let hdc = unsafe { CreateCompatibleDC(None) };
+        let mut bits = std::ptr::null_mut();
+        let bitmap =
+            match unsafe { CreateDIBSection(hdc, &info, DIB_RGB_COLORS, &mut bits, None, 0) } {
+                Ok(bitmap) => bitmap,
+                Err(e) => {
+                    let _ = unsafe { DeleteDC(hdc) };
+                    return Err(WindowInspectorError::CreateBitmapFailed { error: e });
+                }
+            };
+        let old = unsafe { SelectObject(hdc, bitmap) };
+        Ok(Self {
+            hdc,
+            bitmap,
+            old,
+            bits,
+            width,
+            height,
+        })
+    }
+
+    fn hdc(&self) -> HDC {
+        self.hdc
+    }
+
+    fn to_image(&self) -> Image {
+        let len = self.width as usize * self.height as usize * 4;
+        let mut pixels =
+            unsafe { std::slice::from_raw_parts(self.bits as *const u8, len) }.to_vec();
+        // GDI不写入alpha通道。
+        for alpha in pixels.iter_mut().skip(3).step_by(4) {
+            *alpha = 0xFF;
+        }
+        Image {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+impl Drop for Canvas {
+    fn drop(&mut self) {
+        unsafe {
+            SelectObject(self.hdc, self.old);
+            let _ = DeleteObject(self.bitmap);
+            let _ = DeleteDC(self.hdc);
+        }
+    }
+}
+
+/// 截取整个窗口（包括阴影所在的区域），窗口被遮挡或在屏幕外时也能截取。
+/// 使用`PrintWindow`的`PW_RENDERFULLCONTENT`，可以截取使用DirectX、Chromium等硬件加速绘制的窗口。最小化的窗口无法截取。
+pub fn capture_window(hwnd: usize) -> Result<Image> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    // DPI感知程度较低的调用者在缩放的显示器上得到的矩形是缩放后的，而PrintWindow按物理像素绘制。
+    let _physical = PhysicalCoordinates::new();
+    let rect = get_window_rect_include_shadow(hwnd)?;
+    if rect.is_empty() {
+        return Err(WindowInspectorError::CaptureFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_hresult(ERROR_INVALID_PARAMETER.to_hresult()),
+        });
+    }
+    let canvas = Canvas::new(rect.w, rect.h)?;
+    let ok = unsafe {
+        PrintWindow(
+            HWND(hwnd as *mut c_void),
+            canvas.hdc(),
+            PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT),
+        )
+    };
+    if !ok.as_bool() {
+        return Err(WindowInspectorError::CaptureFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(canvas.to_image())
+}
+
+/// 截取整个窗口（见[`capture_window`]）并保存为图像文件。
+pub fn save_window_image(hwnd: usize, path: impl AsRef<Path>, format: ImageFormat) -> Result<()> {
+    let image = capture_window(hwnd)?;
+    let data = match format {
+        ImageFormat::Png => image.to_png(),
+        ImageFormat::Bmp => image.to_bmp(),
+    };
+    std::fs::write(path.as_ref(), data).map_err(|e| WindowInspectorError::WriteFileFailed {
+        path: path.as_ref().display().to_string(),
+        error: e,
+    })
+}
+
+/// 在作用域内把当前线程切换为按显示器感知DPI，使坐标都是物理像素，离开作用域时恢复。
+struct PhysicalCoordinates(DPI_AWARENESS_CONTEXT);
+
+impl PhysicalCoordinates {
+    fn new() -> Self {
+        // 系统不支持时返回空值，坐标可能被缩放，但仍然可以截图。
+        Self(unsafe { SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) })
+    }
+}
+
+impl Drop for PhysicalCoordinates {
+    fn drop(&mut self) {
+        if !self.0.is_invalid() {
+            unsafe { SetThreadDpiAwarenessContext(self.0) };
+        }
+    }
+}
+
+/// 截取客户区中的矩形区域，`(x, y)`是相对于客户区左上角的物理像素坐标，不受调用进程DPI感知的影响。超出客户区的部分会被裁剪。
+/// 直接从屏幕复制像素，比[`capture_window`]快得多，适合高频采样小区域（例如OCR、像素检测）；但区域必须在屏幕上可见，被遮挡的部分会截到遮挡的窗口。
+pub fn capture_client_region(hwnd: usize, x: i32, y: i32, w: u32, h: u32) -> Result<Image> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let _physical = PhysicalCoordinates::new();
+    let client = Rect::from_point_size(Default::default(), get_client_size(hwnd)?);
+    let region = Rect::new(x, y, w, h).intersect(&client).ok_or_else(|| {
+        WindowInspectorError::CaptureFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_hresult(ERROR_INVALID_PARAMETER.to_hresult()),
+        }
+    })?;
+    let canvas = Canvas::new(region.w, region.h)?;
+    let hdc = unsafe { GetDC(HWND(hwnd as *mut c_void)) };
+    if hdc.is_invalid() {
+        return Err(WindowInspectorError::CaptureFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    let result = unsafe {
+        BitBlt(
+            canvas.hdc(),
+            0,
+            0,
+            region.w as i32,
+            region.h as i32,
+            hdc,
+            region.x,
+            region.y,
+            SRCCOPY | CAPTUREBLT,
+        )
+    };
+    unsafe { ReleaseDC(HWND(hwnd as *mut c_void), hdc) };
+    result.map_err(|e| WindowInspectorError::CaptureFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })?;
+    Ok(canvas.to_image())
+}
+
+/// 按固定帧率持续截取窗口（见[`capture_window`]），drop或[`stop`](CaptureLoop::stop)时停止。
+/// 窗口最小化时暂停，还原后继续；窗口尺寸变化时图像尺寸随之变化；单帧截取失败（例如显卡驱动重置）时跳过该帧；窗口关闭后自动停止。
+/// 回调处理得比帧间隔慢时会丢弃落后的帧，而不是累积。
+#[must_use = "CaptureLoop被drop时会立即停止"]
+#[derive(Debug)]
+pub struct CaptureLoop {
+    hwnd: usize,
+    stop: Option<mpsc::Sender<()>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CaptureLoop {
+    /// 开始以每秒`fps`帧截取窗口，在后台线程中对每一帧调用`callback`。
+    pub fn new(
+        hwnd: usize,
+        fps: f64,
+        mut callback: impl FnMut(Image) + Send + 'static,
+    ) -> Result<Self> {
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+        let interval = Duration::from_secs_f64(1.0 / fps.max(0.001));
+        let (sender, receiver) = mpsc::channel::<()>();
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        let thread = thread::spawn(move || {
+            // 回调panic时线程也会结束，在drop中清除标志。
+            struct ClearRunning(Arc<AtomicBool>);
+            impl Drop for ClearRunning {
+                fn drop(&mut self) {
+                    self.0.store(false, Ordering::Relaxed);
+                }
+            }
+            let _running = ClearRunning(thread_running);
+            let mut next = Instant::now();
+            // 收到停止信号或发送端断开时结束。
+            while let Err(RecvTimeoutError::Timeout) =
+                receiver.recv_timeout(next.saturating_duration_since(Instant::now()))
+            {
+                next += interval;
+                // 处理太慢时从现在重新计时，丢弃落后的帧。
+                let now = Instant::now();
+                if next < now {
+                    next = now;
+                }
+                if !is_window_exist(hwnd) {
+                    break;
+                }
+                if unsafe { IsIconic(HWND(hwnd as *mut c_void)) }.as_bool() {
+                    continue;
+                }
+                if let Ok(image) = capture_window(hwnd) {
+                    callback(image);
+                }
+            }
+        });
+        Ok(Self {
+            hwnd,
+            stop: Some(sender),
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// 截取的窗口。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 是否仍在截取，窗口关闭后返回`false`。
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// 停止截取，等待正在进行的回调结束。
+    pub fn stop(self) {}
+}
+
+impl Drop for CaptureLoop {
+    fn drop(&mut self) {
+        // 发送端断开后后台线程随之结束。
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 对截取的图像进行处理，例如文字识别、模板匹配。见[`process_client_region`]。
+pub trait FrameProcessor {
+    type Output;
+
+    fn process(&self, image: &Image) -> Result<Self::Output>;
+}
+
+/// 截取客户区中的区域（见[`capture_client_region`]）并交给`processor`处理。
+/// `region`是相对于客户区左上角的物理像素坐标。
+pub fn process_client_region<P: FrameProcessor>(
+    hwnd: usize,
+    region: Rect,
+    processor: &P,
+) -> Result<P::Output> {
+    let image = capture_client_region(hwnd, region.x, region.y, region.w, region.h)?;
+    processor.process(&image)
+}
+
+/// 用Windows自带的OCR（`Windows.Media.Ocr`）识别文字，语言为用户配置的首选语言，需要安装对应的OCR语言包。
+/// 创建时在当前线程初始化COM并保持到drop，因此只能在创建它的线程中使用。
+#[cfg(feature = "windows-ocr")]
+#[derive(Debug)]
+pub struct OcrProcessor {
+    // 字段按声明顺序drop，引擎必须在反初始化COM之前释放。
+    engine: OcrEngine,
+    _com: ComGuard,
+    /// COM按线程初始化，不能跨线程移动。
+    _not_send: PhantomData<*const ()>,
+}
+
+#[cfg(feature = "windows-ocr")]
+impl OcrProcessor {
+    pub fn new() -> Result<Self> {
+        let com = ComGuard::new()?;
+        let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+            .map_err(|e| WindowInspectorError::OcrFailed { error: e })?;
+        Ok(Self {
+            engine,
+            _com: com,
+            _not_send: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "windows-ocr")]
+impl FrameProcessor for OcrProcessor {
+    /// 识别出的文字，各行以换行分隔。
+    type Output = String;
+
+    fn process(&self, image: &Image) -> Result<String> {
+        let recognize = || -> windows::core::Result<String> {
+            let bitmap = SoftwareBitmap::CreateWithAlpha(
+                BitmapPixelFormat::Bgra8,
+                image.width as i32,
+                image.height as i32,
+                BitmapAlphaMode::Premultiplied,
+            )?;
+            let writer = DataWriter::new()?;
+            writer.WriteBytes(&image.pixels)?;
+            bitmap.CopyFromBuffer(&writer.DetachBuffer()?)?;
+            let result = self.engine.RecognizeAsync(&bitmap)?.get()?;
+            let lines = result
+                .Lines()?
+                .into_iter()
+                .map(|line| line.Text().map(|text| text.to_string()))
+                .collect::<windows::core::Result<Vec<_>>>()?;
+            Ok(lines.join("\n"))
+        };
+        recognize().map_err(|e| WindowInspectorError::OcrFailed { error: e })
+    }
+}
+
+/// 识别客户区中区域`region`内的文字，见[`OcrProcessor`]。
+/// `region`是相对于客户区左上角的物理像素坐标，宽高不能超过`OcrEngine::MaxImageDimension`。
+/// 需要多次识别时应复用[`OcrProcessor`]并调用[`process_client_region`]。
+#[cfg(feature = "windows-ocr")]
+pub fn read_text(hwnd: usize, region: Rect) -> Result<String> {
+    process_client_region(hwnd, region, &OcrProcessor::new()?)
+}
+
+#[test]
+fn test_encode() {
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    let image = Image {
+        width: 2,
+        height: 1,
+        pixels: vec![0, 0, 0xFF, 0xFF, 0xFF, 0, 0, 0xFF],
+    };
+    assert_eq!(image.pixel(0, 0), Some((0xFF, 0, 0)));
+    assert_eq!(image.pixel(2, 0), None);
+    let png = image.to_png();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    assert!(png.ends_with(&[0xAE, 0x42, 0x60, 0x82]));
+    assert_eq!(image.to_bmp().len(), 14 + 40 + 8);
+    assert!(Image::default().to_png().starts_with(b"\x89PNG\r\n\x1a\n"));
+}
@@ -1,42 +1,49 @@
-use std::ffi::c_void;
-
-use windows::Win32::Foundation::GetLastError;
-use windows::Win32::Foundation::HWND;
-use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
-use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
-
-use crate::error::WindowInspectorError;
-use crate::exist::is_window_exist;
+use crate::backend::with_current_backend;
+use crate::exist::with_validation;
 use crate::result::Result;
 
 /// 获取窗口类名。
 pub fn get_window_class(hwnd: usize) -> Result<String> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
-    let mut buffer = [0u16; 1024];
-    match unsafe { GetClassNameW(HWND(hwnd as *mut c_void), &mut buffer) } {
-        0 => Err(WindowInspectorError::GetClassNameWFailed {
-            error_code: unsafe { GetLastError() }.0,
-        }),
-        n => Ok(String::from_utf16_lossy(&buffer[..n as usize])),
-    }
+    with_validation(hwnd, get_window_class_unchecked)
+}
+
+/// 获取窗口类名，不检查窗口是否存在。
+pub(crate) fn get_window_class_unchecked(hwnd: usize) -> Result<String> {
+    let mut class = String::new();
+    get_window_class_into_unchecked(hwnd, &mut class)?;
+    Ok(class)
+}
+
+/// 获取窗口类名，写入`class`。
+/// 与[`get_window_class`]相同，但复用`class`的内存，适合频繁轮询多个窗口时使用。
+pub fn get_window_class_into(hwnd: usize, class: &mut String) -> Result<()> {
+    with_validation(hwnd, |hwnd| get_window_class_into_unchecked(hwnd, class))
+}
+
+/// 获取窗口类名，写入`class`，不检查窗口是否存在。
+pub(crate) fn get_window_class_into_unchecked(hwnd: usize, class: &mut String) -> Result<()> {
+    with_current_backend(|backend| backend.window_class(hwnd, class))
 }
 
 /// 获取窗口标题。
 pub fn get_window_title(hwnd: usize) -> Result<String> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
-    let mut buffer = [0u16; 1024];
-    match unsafe { GetWindowTextW(HWND(hwnd as *mut c_void), &mut buffer) } {
-        0 => Err(WindowInspectorError::GetClassNameWFailed {
-            error_code: unsafe { GetLastError() }.0,
-        }),
-        n => Ok(String::from_utf16_lossy(&buffer[..n as usize])),
-    }
+    with_validation(hwnd, get_window_title_unchecked)
+}
+
+/// 获取窗口标题，不检查窗口是否存在。
+pub(crate) fn get_window_title_unchecked(hwnd: usize) -> Result<String> {
+    let mut title = String::new();
+    get_window_title_into_unchecked(hwnd, &mut title)?;
+    Ok(title)
+}
+
+/// 获取窗口标题，写入`title`。
+/// 与[`get_window_title`]相同，但复用`title`的内存，适合频繁轮询多个窗口时使用。
+pub fn get_window_title_into(hwnd: usize, title: &mut String) -> Result<()> {
+    with_validation(hwnd, |hwnd| get_window_title_into_unchecked(hwnd, title))
+}
+
+/// 获取窗口标题，写入`title`，不检查窗口是否存在。
+pub(crate) fn get_window_title_into_unchecked(hwnd: usize, title: &mut String) -> Result<()> {
+    with_current_backend(|backend| backend.window_title(hwnd, title))
 }
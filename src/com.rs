@@ -0,0 +1,37 @@
+//! COM初始化。
+
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::CoInitializeEx;
+use windows::Win32::System::Com::CoUninitialize;
+use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+
+/// 在当前线程初始化COM，drop时反初始化。
+/// 线程已经以其他模式初始化过COM时（`RPC_E_CHANGED_MODE`）直接使用已有的初始化，drop时不反初始化。
+#[derive(Debug)]
+pub(crate) struct ComGuard {
+    initialized: bool,
+}
+
+impl ComGuard {
+    pub(crate) fn new() -> Result<ComGuard> {
+        let result = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+        if result == RPC_E_CHANGED_MODE {
+            return Ok(ComGuard { initialized: false });
+        }
+        result
+            .ok()
+            .map_err(|e| WindowInspectorError::CoInitializeExFailed { error: e })?;
+        Ok(ComGuard { initialized: true })
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
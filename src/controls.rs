@@ -0,0 +1,552 @@
+//! 读取其他窗口中标准控件的状态。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use windows::core::PCWSTR;
+#[cfg(feature = "remote-controls")]
+use windows::core::PWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::System::Diagnostics::Debug::WriteProcessMemory;
+use windows::Win32::System::Memory::VirtualAllocEx;
+use windows::Win32::System::Memory::VirtualFreeEx;
+use windows::Win32::System::Memory::MEM_COMMIT;
+use windows::Win32::System::Memory::MEM_RELEASE;
+use windows::Win32::System::Memory::MEM_RESERVE;
+use windows::Win32::System::Memory::PAGE_READWRITE;
+use windows::Win32::System::Threading::OpenProcess;
+use windows::Win32::System::Threading::PROCESS_VM_OPERATION;
+use windows::Win32::System::Threading::PROCESS_VM_READ;
+use windows::Win32::System::Threading::PROCESS_VM_WRITE;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::HDM_GETITEMCOUNT;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::HTREEITEM;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::LVITEMW;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::LVM_GETHEADER;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::LVM_GETITEMCOUNT;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::LVM_GETITEMTEXTW;
+use windows::Win32::UI::Controls::SB_GETPARTS;
+use windows::Win32::UI::Controls::SB_GETTEXTLENGTHW;
+use windows::Win32::UI::Controls::SB_GETTEXTW;
+use windows::Win32::UI::Controls::STATE_SYSTEM_INVISIBLE;
+use windows::Win32::UI::Controls::STATE_SYSTEM_UNAVAILABLE;
+use windows::Win32::UI::Controls::STATUSCLASSNAMEW;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVGN_CHILD;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVGN_NEXT;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVGN_ROOT;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVIF_TEXT;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVITEMW;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVM_GETITEMW;
+#[cfg(feature = "remote-controls")]
+use windows::Win32::UI::Controls::TVM_GETNEXTITEM;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::GetScrollBarInfo;
+use windows::Win32::UI::WindowsAndMessaging::GetScrollInfo;
+use windows::Win32::UI::WindowsAndMessaging::SendMessageTimeoutW;
+use windows::Win32::UI::WindowsAndMessaging::OBJID_HSCROLL;
+use windows::Win32::UI::WindowsAndMessaging::OBJID_VSCROLL;
+use windows::Win32::UI::WindowsAndMessaging::SB_HORZ;
+use windows::Win32::UI::WindowsAndMessaging::SB_VERT;
+use windows::Win32::UI::WindowsAndMessaging::SCROLLBARINFO;
+use windows::Win32::UI::WindowsAndMessaging::SCROLLINFO;
+use windows::Win32::UI::WindowsAndMessaging::SIF_ALL;
+use windows::Win32::UI::WindowsAndMessaging::SMTO_ABORTIFHUNG;
+
+use crate::class_title::get_window_class;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::geometry::Rect;
+#[cfg(feature = "remote-controls")]
+use crate::process::get_process_architecture;
+use crate::process::get_window_process;
+#[cfg(feature = "remote-controls")]
+use crate::process::ProcessArchitecture;
+use crate::result::Result;
+
+/// 向其他进程的控件发送消息的超时时间（毫秒）。
+const SEND_MESSAGE_TIMEOUT: u32 = 1000;
+
+/// 滚动条。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bar {
+    Vertical,
+    Horizontal,
+}
+
+/// 滚动条状态，见[`get_scroll_info`]。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScrollInfo {
+    /// 滚动范围的最小值。
+    pub min: i32,
+    /// 滚动范围的最大值。
+    pub max: i32,
+    /// 一页的大小，位置的最大值为`max - page + 1`。
+    pub page: u32,
+    /// 当前位置。
+    pub pos: i32,
+    /// 拖动滑块时的位置，未拖动时与`pos`相同。
+    pub track_pos: i32,
+    /// 滚动条是否可见。
+    pub visible: bool,
+    /// 滚动条是否可用。
+    pub enabled: bool,
+    /// 滚动条的矩形，相对于屏幕。
+    pub rect: Rect,
+}
+
+impl ScrollInfo {
+    /// 是否已经滚动到底（或最右）。
+    pub fn is_at_end(&self) -> bool {
+        self.pos as i64 + (self.page.max(1) as i64) > self.max as i64
+    }
+}
+
+/// 获取窗口自身的标准滚动条（`WS_VSCROLL`、`WS_HSCROLL`）的状态，可用于其他进程的窗口。
+/// 不适用于滚动条控件（`ScrollBar`类的子窗口），也不适用于自绘滚动条的程序（例如浏览器）。
+/// 窗口没有该滚动条时返回错误。
+pub fn get_scroll_info(hwnd: usize, bar: Bar) -> Result<ScrollInfo> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let (object_id, scroll_bar) = match bar {
+        Bar::Vertical => (OBJID_VSCROLL, SB_VERT),
+        Bar::Horizontal => (OBJID_HSCROLL, SB_HORZ),
+    };
+    let mut bar_info = SCROLLBARINFO {
+        cbSize: size_of::<SCROLLBARINFO>() as u32,
+        ..Default::default()
+    };
+    unsafe { GetScrollBarInfo(HWND(hwnd as *mut c_void), object_id, &mut bar_info) }.map_err(
+        |e| WindowInspectorError::GetScrollInfoFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        },
+    )?;
+    let mut info = SCROLLINFO {
+        cbSize: size_of::<SCROLLINFO>() as u32,
+        fMask: SIF_ALL,
+        ..Default::default()
+    };
+    unsafe { GetScrollInfo(HWND(hwnd as *mut c_void), scroll_bar, &mut info) }.map_err(|e| {
+        WindowInspectorError::GetScrollInfoFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        }
+    })?;
+    let state = bar_info.rgstate[0];
+    Ok(ScrollInfo {
+        min: info.nMin,
+        max: info.nMax,
+        page: info.nPage,
+        pos: info.nPos,
+        track_pos: info.nTrackPos,
+        visible: state & STATE_SYSTEM_INVISIBLE.0 == 0,
+        enabled: state & STATE_SYSTEM_UNAVAILABLE.0 == 0,
+        rect: bar_info.rcScrollBar.into(),
+    })
+}
+
+/// 发送消息并返回结果，窗口未响应或超时时返回错误。
+fn send_message(hwnd: usize, message: u32, wparam: usize, lparam: isize) -> Result<usize> {
+    let mut result = 0usize;
+    let ok = unsafe {
+        SendMessageTimeoutW(
+            HWND(hwnd as *mut c_void),
+            message,
+            WPARAM(wparam),
+            LPARAM(lparam),
+            SMTO_ABORTIFHUNG,
+            SEND_MESSAGE_TIMEOUT,
+            Some(&mut result),
+        )
+    };
+    if ok.0 == 0 {
+        return Err(WindowInspectorError::SendMessageTimeoutWFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(result)
+}
+
+/// 在其他进程中分配的内存，离开作用域时释放。
+/// 控件消息中的指针参数必须指向控件所在进程的内存。
+struct RemoteBuffer {
+    process_id: u32,
+    process: HANDLE,
+    address: *mut c_void,
+    size: usize,
+}
+
+impl RemoteBuffer {
+    fn new(process_id: u32, size: usize) -> Result<Self> {
+        let process = unsafe {
+            OpenProcess(
+                PROCESS_VM_OPERATION | PROCESS_VM_READ | PROCESS_VM_WRITE,
+                false,
+                process_id,
+            )
+        }
+        .map_err(|e| WindowInspectorError::OpenProcessFailed {
+            process_id,
+            error: e,
+        })?;
+        let address = unsafe {
+            VirtualAllocEx(
+                process,
+                None,
+                size,
+                MEM_COMMIT | MEM_RESERVE,
+                PAGE_READWRITE,
+            )
+        };
+        if address.is_null() {
+            let error = windows::core::Error::from_win32();
+            let _ = unsafe { CloseHandle(process) };
+            return Err(WindowInspectorError::VirtualAllocExFailed { process_id, error });
+        }
+        Ok(Self {
+            process_id,
+            process,
+            address,
+            size,
+        })
+    }
+
+    /// 缓冲区中偏移`offset`字节处的地址。
+    fn address_at(&self, offset: usize) -> *mut c_void {
+        (self.address as usize + offset) as *mut c_void
+    }
+
+    /// 将`value`写入缓冲区开头。
+    #[cfg(feature = "remote-controls")]
+    fn write<T: Copy>(&self, value: &T) -> Result<()> {
+        unsafe {
+            WriteProcessMemory(
+                self.process,
+                self.address,
+                value as *const T as *const c_void,
+                size_of::<T>().min(self.size),
+                None,
+            )
+        }
+        .map_err(|e| WindowInspectorError::WriteProcessMemoryFailed {
+            process_id: self.process_id,
+            error: e,
+        })
+    }
+
+    /// 从偏移`offset`字节处读取最多`length`个UTF-16字符，遇到`\0`截止。
+    fn read_wide(&self, offset: usize, length: usize) -> Result<String> {
+        let mut buffer = vec![0u16; length.min(self.size.saturating_sub(offset) / 2)];
+        unsafe {
+            ReadProcessMemory(
+                self.process,
+                self.address_at(offset),
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len() * 2,
+                None,
+            )
+        }
+        .map_err(|e| WindowInspectorError::ReadProcessMemoryFailed {
+            process_id: self.process_id,
+            error: e,
+        })?;
+        let length = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Ok(String::from_utf16_lossy(&buffer[..length]))
+    }
+}
+
+impl Drop for RemoteBuffer {
+    fn drop(&mut self) {
+        let _ = unsafe { VirtualFreeEx(self.process, self.address, 0, MEM_RELEASE) };
+        let _ = unsafe { CloseHandle(self.process) };
+    }
+}
+
+/// 状态栏文本缓冲区的容量（UTF-16字符数），`length`是刚刚查询到的文本长度。
+/// `SB_GETTEXTW`不接受缓冲区大小，查询长度之后文本变长时会写入预留的空间，而不是越过缓冲区。
+fn status_text_capacity(length: usize) -> usize {
+    length * 2 + 256
+}
+
+/// 读取状态栏控件每个分栏的文本。
+fn read_status_bar(status_bar: usize) -> Result<Vec<String>> {
+    let parts = send_message(status_bar, SB_GETPARTS, 0, 0)?;
+    if parts == 0 {
+        return Ok(Vec::new());
+    }
+    let process_id = get_window_process(status_bar as isize)?;
+    (0..parts)
+        .map(|part| {
+            // 紧接着读取之前查询长度，低16位是文本长度，高16位是绘制方式。
+            let length = send_message(status_bar, SB_GETTEXTLENGTHW, part, 0)? & 0xFFFF;
+            let capacity = status_text_capacity(length);
+            let buffer = RemoteBuffer::new(process_id, capacity * size_of::<u16>())?;
+            send_message(status_bar, SB_GETTEXTW, part, buffer.address as isize)?;
+            buffer.read_wide(0, capacity)
+        })
+        .collect()
+}
+
+/// 读取窗口中状态栏（`msctls_statusbar32`）的文本，返回每个分栏的文本。
+/// `hwnd`可以是状态栏本身，也可以是包含状态栏的窗口（只查找直接子窗口）；有多个状态栏时按Z序依次拼接。没有状态栏时返回空。
+/// 需要在控件所在进程中分配内存，权限不足（例如目标进程以管理员身份运行）时返回错误。
+pub fn get_status_bar_text(hwnd: usize) -> Result<Vec<String>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if get_window_class(hwnd)?.eq_ignore_ascii_case("msctls_statusbar32") {
+        return read_status_bar(hwnd);
+    }
+    let mut texts = Vec::new();
+    let mut previous = HWND::default();
+    // 找不到更多窗口时FindWindowExW返回空句柄，windows-rs将其转换为错误。
+    while let Ok(status_bar) = unsafe {
+        FindWindowExW(
+            HWND(hwnd as *mut c_void),
+            previous,
+            STATUSCLASSNAMEW,
+            PCWSTR::null(),
+        )
+    } {
+        if status_bar.is_invalid() {
+            break;
+        }
+        texts.extend(read_status_bar(status_bar.0 as usize)?);
+        previous = status_bar;
+    }
+    Ok(texts)
+}
+
+/// 列表视图、树视图每项文本的最大长度（UTF-16字符数）。
+#[cfg(feature = "remote-controls")]
+const ITEM_TEXT_LENGTH: usize = 1024;
+
+/// 控件消息中的结构体包含指针，布局与进程位数有关，只能读取与当前进程位数相同的进程中的控件。
+#[cfg(feature = "remote-controls")]
+fn check_same_pointer_width(process_id: u32) -> Result<()> {
+    let is_32_bit = matches!(
+        get_process_architecture(process_id)?,
+        ProcessArchitecture::X86 | ProcessArchitecture::Arm
+    );
+    if is_32_bit != cfg!(target_pointer_width = "32") {
+        return Err(WindowInspectorError::PointerWidthMismatch { process_id });
+    }
+    Ok(())
+}
+
+/// 为控件消息分配缓冲区：开头是结构体`T`，之后是文本。
+#[cfg(feature = "remote-controls")]
+fn new_item_buffer<T>(control: usize) -> Result<RemoteBuffer> {
+    let process_id = get_window_process(control as isize)?;
+    check_same_pointer_width(process_id)?;
+    RemoteBuffer::new(
+        process_id,
+        size_of::<T>() + ITEM_TEXT_LENGTH * size_of::<u16>(),
+    )
+}
+
+/// 读取列表视图控件（`SysListView32`）的所有项，每行是各列的文本。
+/// 没有列标题（非报表视图）时每行只有一列。只能读取与当前进程位数相同的进程中的控件。
+#[cfg(feature = "remote-controls")]
+pub fn read_list_view(hwnd: usize) -> Result<Vec<Vec<String>>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let count = send_message(hwnd, LVM_GETITEMCOUNT, 0, 0)?;
+    let header = send_message(hwnd, LVM_GETHEADER, 0, 0)?;
+    let columns = if header == 0 {
+        1
+    } else {
+        // 失败时返回-1。
+        (send_message(header, HDM_GETITEMCOUNT, 0, 0)? as isize).max(1) as usize
+    };
+    let buffer = new_item_buffer::<LVITEMW>(hwnd)?;
+    let text_offset = size_of::<LVITEMW>();
+    (0..count)
+        .map(|item| {
+            (0..columns)
+                .map(|column| {
+                    buffer.write(&LVITEMW {
+                        iSubItem: column as i32,
+                        pszText: PWSTR(buffer.address_at(text_offset) as *mut u16),
+                        cchTextMax: ITEM_TEXT_LENGTH as i32,
+                        ..Default::default()
+                    })?;
+                    let length =
+                        send_message(hwnd, LVM_GETITEMTEXTW, item, buffer.address as isize)?;
+                    buffer.read_wide(text_offset, length + 1)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 树视图的节点，见[`read_tree_view`]。
+#[cfg(feature = "remote-controls")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TreeItem {
+    pub text: String,
+    pub children: Vec<TreeItem>,
+}
+
+/// 读取树视图的最大深度，更深的节点不再读取子节点，防止极深的树或损坏的节点链导致栈溢出。
+#[cfg(feature = "remote-controls")]
+const MAX_TREE_DEPTH: usize = 256;
+
+/// 读取`first`及其之后的兄弟节点，`depth`是它们的深度（根节点为0）。
+#[cfg(feature = "remote-controls")]
+fn read_tree_items(
+    hwnd: usize,
+    buffer: &RemoteBuffer,
+    first: usize,
+    depth: usize,
+) -> Result<Vec<TreeItem>> {
+    let text_offset = size_of::<TVITEMW>();
+    let mut items = Vec::new();
+    let mut item = first;
+    while item != 0 {
+        buffer.write(&TVITEMW {
+            mask: TVIF_TEXT,
+            hItem: HTREEITEM(item as isize),
+            pszText: PWSTR(buffer.address_at(text_offset) as *mut u16),
+            cchTextMax: ITEM_TEXT_LENGTH as i32,
+            ..Default::default()
+        })?;
+        send_message(hwnd, TVM_GETITEMW, 0, buffer.address as isize)?;
+        let text = buffer.read_wide(text_offset, ITEM_TEXT_LENGTH)?;
+        let children = if depth + 1 < MAX_TREE_DEPTH {
+            let child = send_message(hwnd, TVM_GETNEXTITEM, TVGN_CHILD as usize, item as isize)?;
+            read_tree_items(hwnd, buffer, child, depth + 1)?
+        } else {
+            Vec::new()
+        };
+        items.push(TreeItem { text, children });
+        item = send_message(hwnd, TVM_GETNEXTITEM, TVGN_NEXT as usize, item as isize)?;
+    }
+    Ok(items)
+}
+
+/// 读取树视图控件（`SysTreeView32`）的所有节点。
+/// 许多程序（例如资源管理器的导航窗格）在展开节点时才创建子节点，未展开的节点可能没有子节点。
+/// 最多读取256层，更深的节点没有子节点。只能读取与当前进程位数相同的进程中的控件。
+#[cfg(feature = "remote-controls")]
+pub fn read_tree_view(hwnd: usize) -> Result<Vec<TreeItem>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let buffer = new_item_buffer::<TVITEMW>(hwnd)?;
+    let root = send_message(hwnd, TVM_GETNEXTITEM, TVGN_ROOT as usize, 0)?;
+    read_tree_items(hwnd, &buffer, root, 0)
+}
+
+#[test]
+fn test_is_at_end() {
+    let info = ScrollInfo {
+        max: 99,
+        page: 10,
+        pos: 90,
+        ..Default::default()
+    };
+    assert!(info.is_at_end());
+    assert!(!ScrollInfo { pos: 89, ..info }.is_at_end());
+}
+
+#[test]
+fn test_get_status_bar_text() {
+    use windows::core::w;
+    use windows::Win32::UI::Controls::InitCommonControlsEx;
+    use windows::Win32::UI::Controls::ICC_BAR_CLASSES;
+    use windows::Win32::UI::Controls::INITCOMMONCONTROLSEX;
+    use windows::Win32::UI::Controls::SB_SETPARTS;
+    use windows::Win32::UI::Controls::SB_SETTEXTW;
+    use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+    use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+    use windows::Win32::UI::WindowsAndMessaging::SendMessageW;
+    use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
+    use windows::Win32::UI::WindowsAndMessaging::WS_CHILD;
+    use windows::Win32::UI::WindowsAndMessaging::WS_VISIBLE;
+
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let init = INITCOMMONCONTROLSEX {
+        dwSize: size_of::<INITCOMMONCONTROLSEX>() as u32,
+        dwICC: ICC_BAR_CLASSES,
+    };
+    assert!(unsafe { InitCommonControlsEx(&init) }.as_bool());
+    // 状态栏属于当前线程，发送给它的消息直接在当前线程处理。
+    let status_bar = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            STATUSCLASSNAMEW,
+            PCWSTR::null(),
+            WS_CHILD | WS_VISIBLE,
+            0,
+            0,
+            0,
+            0,
+            HWND(window.hwnd() as *mut c_void),
+            None,
+            None,
+            None,
+        )
+    }
+    .unwrap();
+    let widths = [100i32, -1];
+    let long_text = "状态".repeat(200);
+    let long_text = widestring::U16CString::from_str(&long_text).unwrap();
+    unsafe {
+        SendMessageW(
+            status_bar,
+            SB_SETPARTS,
+            WPARAM(widths.len()),
+            LPARAM(widths.as_ptr() as isize),
+        );
+        SendMessageW(
+            status_bar,
+            SB_SETTEXTW,
+            WPARAM(0),
+            LPARAM(w!("就绪").as_ptr() as isize),
+        );
+        SendMessageW(
+            status_bar,
+            SB_SETTEXTW,
+            WPARAM(1),
+            LPARAM(long_text.as_ptr() as isize),
+        );
+    }
+    assert_eq!(
+        get_status_bar_text(window.hwnd()).unwrap(),
+        vec!["就绪".to_string(), "状态".repeat(200)]
+    );
+    assert_eq!(status_text_capacity(0), 256);
+    assert!(status_text_capacity(400) > 400);
+    let _ = unsafe { DestroyWindow(status_bar) };
+}
@@ -0,0 +1,99 @@
+//! 在屏幕上标出窗口，用于确认查找、枚举得到的是不是想要的窗口。
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
+use windows::Win32::UI::WindowsAndMessaging::SetTimer;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::message_loop::MessageThread;
+use crate::overlay::Overlay;
+use crate::position_size::get_window_xywh_exclude_shadow;
+use crate::result::Result;
+
+/// 边框闪烁的间隔。
+const FLASH_INTERVAL_MS: u32 = 250;
+/// 边框宽度。
+const BORDER_WIDTH: i32 = 3;
+
+/// 高亮过程中的状态，只在高亮线程中访问。
+struct HighlightState {
+    overlay: Overlay,
+    hwnd: usize,
+    visible: bool,
+}
+
+thread_local! {
+    static HIGHLIGHT_STATE: RefCell<Option<HighlightState>> = const { RefCell::new(None) };
+}
+
+/// 切换边框的显示和隐藏。每次显示时重新获取窗口位置，窗口移动时边框跟着移动。
+fn flash() {
+    HIGHLIGHT_STATE.with_borrow_mut(|state| {
+        let Some(state) = state else {
+            return;
+        };
+        state.visible = !state.visible;
+        match get_window_xywh_exclude_shadow(state.hwnd) {
+            Ok(xywh) if state.visible => state.overlay.show_around(xywh),
+            // 窗口已经关闭时提前结束。
+            Err(_) => unsafe { PostQuitMessage(0) },
+            _ => state.overlay.hide(),
+        }
+    });
+}
+
+unsafe extern "system" fn flash_proc(_: HWND, _: u32, _: usize, _: u32) {
+    flash();
+}
+
+unsafe extern "system" fn end_proc(_: HWND, _: u32, _: usize, _: u32) {
+    PostQuitMessage(0);
+}
+
+/// 在窗口周围绘制闪烁的边框（类似Spy++），持续`duration`后消失，阻塞直到结束。
+/// `color`为`0x00BBGGRR`格式的颜色。边框是鼠标穿透的置顶窗口，不影响对窗口的操作。
+/// 窗口在此期间关闭时提前返回。
+pub fn highlight_window(hwnd: usize, color: u32, duration: Duration) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let (sender, receiver) = mpsc::channel();
+    let thread = MessageThread::spawn(
+        move || {
+            let overlay = Overlay::new(color, BORDER_WIDTH)?;
+            HIGHLIGHT_STATE.set(Some(HighlightState {
+                overlay,
+                hwnd,
+                visible: false,
+            }));
+            flash();
+            let elapse = duration.as_millis().clamp(1, u32::MAX as u128) as u32;
+            unsafe {
+                SetTimer(None, 0, FLASH_INTERVAL_MS, Some(flash_proc));
+                SetTimer(None, 0, elapse, Some(end_proc));
+            }
+            Ok(())
+        },
+        |_| {},
+        move |()| {
+            drop(HIGHLIGHT_STATE.take());
+            let _ = sender.send(());
+        },
+    )
+    .map_err(|error| WindowInspectorError::CreateWindowExWFailed {
+        class: "window_inspector_overlay".to_string(),
+        title: String::new(),
+        error,
+    })?;
+    let _ = receiver.recv();
+    drop(thread);
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use std::ffi::c_void;
+use std::time::Duration;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::HWND_MESSAGE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+
+use crate::class_title::get_window_title;
+use crate::error::WindowInspectorError;
+use crate::geometry::Size;
+use crate::information::get_window_uptime;
+use crate::position_size::get_window_xywh_include_shadow;
+use crate::process::get_window_process;
+use crate::result::Result;
+use crate::state::is_window_cloaked;
+use crate::wide::to_pcwstr_or_null;
+use crate::wide::to_wide;
+
+unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam.0 as *mut Vec<usize>);
+    windows.push(hwnd.0 as usize);
+    true.into()
+}
+
+/// 获取所有顶层窗口，按Z序从上到下排列。
+/// 是[`EnumWindows`]的封装，包括不可见窗口。
+///
+/// [`EnumWindows`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/WindowsAndMessaging/fn.EnumWindows.html
+pub fn get_top_level_windows() -> Result<Vec<usize>> {
+    let mut windows: Vec<usize> = Vec::new();
+    unsafe {
+        EnumWindows(
+            Some(enum_windows_proc),
+            LPARAM(&mut windows as *mut Vec<usize> as isize),
+        )
+    }
+    .map_err(|e| WindowInspectorError::EnumWindowsFailed { error: e })?;
+    Ok(windows)
+}
+
+/// 窗口过滤条件，用于从枚举结果中去掉尺寸为0的辅助窗口、启动画面等临时窗口。
+/// 默认值不过滤任何窗口。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FilterOptions {
+    /// 窗口（包括阴影）的最小宽高，宽或高小于它的窗口被去掉。
+    pub min_size: Size,
+    /// 窗口的最小存在时间，用于去掉刚启动的程序的启动画面。
+    /// 存在时间由[`get_window_uptime`]以进程启动时间近似，对早已运行的程序新建的窗口不起作用。
+    pub min_age: Duration,
+    /// 是否去掉没有标题的窗口。
+    pub require_title: bool,
+    /// 是否去掉带`WS_EX_TOOLWINDOW`样式的工具窗口。
+    pub skip_tool_windows: bool,
+    /// 是否去掉被DWM隐藏（cloaked）的窗口，例如其他虚拟桌面上的窗口、挂起的UWP窗口。
+    pub skip_cloaked: bool,
+}
+
+impl FilterOptions {
+    /// 窗口是否满足条件，窗口不存在时返回`false`。
+    pub fn matches(&self, hwnd: usize) -> bool {
+        if self.min_size != Size::default() {
+            match get_window_xywh_include_shadow(hwnd) {
+                Ok((_, _, w, h)) if w >= self.min_size.w && h >= self.min_size.h => {}
+                _ => return false,
+            }
+        }
+        if self.require_title && get_window_title(hwnd).map_or(true, |title| title.is_empty()) {
+            return false;
+        }
+        if self.skip_tool_windows {
+            let ex_style = unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } as u32;
+            if ex_style & WS_EX_TOOLWINDOW.0 != 0 {
+                return false;
+            }
+        }
+        if self.skip_cloaked && is_window_cloaked(hwnd).unwrap_or(true) {
+            return false;
+        }
+        if !self.min_age.is_zero() && !get_window_uptime(hwnd).is_ok_and(|age| age >= self.min_age)
+        {
+            return false;
+        }
+        true
+    }
+
+    /// 按条件过滤窗口，保持原有顺序。
+    pub fn filter(&self, hwnds: Vec<usize>) -> Vec<usize> {
+        hwnds
+            .into_iter()
+            .filter(|&hwnd| self.matches(hwnd))
+            .collect()
+    }
+}
+
+/// 获取满足条件的顶层窗口，按Z序从上到下排列。
+pub fn get_top_level_windows_filtered(options: &FilterOptions) -> Result<Vec<usize>> {
+    Ok(options.filter(get_top_level_windows()?))
+}
+
+/// 获取进程的所有顶层窗口，按Z序从上到下排列。
+pub fn get_process_windows(process_id: u32) -> Result<Vec<usize>> {
+    Ok(get_top_level_windows()?
+        .into_iter()
+        .filter(|&hwnd| get_window_process(hwnd as isize).is_ok_and(|id| id == process_id))
+        .collect())
+}
+
+/// 获取仅消息窗口（父窗口为`HWND_MESSAGE`的窗口），`window_class`为空字符串时不限制类名。
+/// 仅消息窗口不会被[`get_top_level_windows`]、[`crate::find::get_hwnd`]找到，许多后台服务通过它们通信。
+pub fn get_message_only_windows(window_class: &str) -> Result<Vec<usize>> {
+    let window_class = to_wide(window_class.as_ref())?;
+    let mut windows = Vec::new();
+    let mut previous = HWND::default();
+    // 找不到更多窗口时FindWindowExW返回空句柄，windows-rs将其转换为错误。
+    while let Ok(hwnd) = unsafe {
+        FindWindowExW(
+            HWND_MESSAGE,
+            previous,
+            to_pcwstr_or_null(&window_class),
+            PCWSTR::null(),
+        )
+    } {
+        if hwnd.is_invalid() {
+            break;
+        }
+        windows.push(hwnd.0 as usize);
+        previous = hwnd;
+    }
+    Ok(windows)
+}
+
+#[test]
+fn test_get_message_only_windows() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().message_only(true).build().unwrap();
+    assert_eq!(
+        get_message_only_windows(window.class()).unwrap(),
+        vec![window.hwnd()]
+    );
+    assert!(!get_top_level_windows().unwrap().contains(&window.hwnd()));
+}
+
+#[test]
+fn test_filter_options() {
+    use crate::test_support::TestWindowBuilder;
+
+    let small = TestWindowBuilder::new().xywh(0, 0, 1, 1).build().unwrap();
+    let untitled = TestWindowBuilder::new().title("").build().unwrap();
+    let options = FilterOptions {
+        min_size: Size::new(50, 50),
+        require_title: true,
+        ..Default::default()
+    };
+    let hwnds = get_top_level_windows_filtered(&options).unwrap();
+    assert!(!hwnds.contains(&small.hwnd()));
+    assert!(!hwnds.contains(&untitled.hwnd()));
+    assert_eq!(
+        FilterOptions::default().filter(vec![small.hwnd(), untitled.hwnd()]),
+        vec![small.hwnd(), untitled.hwnd()]
+    );
+}
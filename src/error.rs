@@ -1,50 +1,1028 @@
 use thiserror::Error;
+use windows::core::HRESULT;
+use windows::Win32::Foundation::ERROR_ACCESS_DENIED;
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::Foundation::ERROR_INVALID_HANDLE;
+use windows::Win32::Foundation::ERROR_INVALID_PARAMETER;
+use windows::Win32::Foundation::ERROR_INVALID_WINDOW_HANDLE;
+use windows::Win32::Foundation::ERROR_NOT_FOUND;
+use windows::Win32::Foundation::ERROR_PATH_NOT_FOUND;
+use windows::Win32::Foundation::ERROR_RESOURCE_TYPE_NOT_FOUND;
+use windows::Win32::Foundation::ERROR_TIMEOUT;
 use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::STATUS_ACCESS_DENIED;
+use windows::Win32::Foundation::STATUS_INVALID_HANDLE;
+use windows::Win32::Foundation::WAIT_TIMEOUT;
+
+/// 错误的大致类别，用于在程序中区分处理错误，而不需要解析错误信息。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// 窗口、进程、文件等不存在。
+    NotFound,
+    /// 权限不足，例如访问以管理员身份运行的进程。
+    AccessDenied,
+    /// 句柄无效，通常是窗口在调用过程中被关闭了。
+    InvalidHandle,
+    /// 等待超时。
+    Timeout,
+    /// 其他错误。
+    Other,
+}
 
 #[derive(Error, Debug)]
 pub enum WindowInspectorError {
-    #[error("窗口类名和标题都为空")]
+    #[cfg_attr(not(feature = "english"), error("窗口类名和标题都为空"))]
+    #[cfg_attr(feature = "english", error("window class and title are both empty"))]
     WindowClassTitleBothEmpty,
-    #[error(
-        "FindWindowExW失败，窗口类名：{window_class}，窗口标题：{window_title}，{error_message}"
+    #[cfg_attr(
+        not(feature = "english"),
+        error("FindWindowExW失败，窗口类名：{window_class}，窗口标题：{window_title}，{error}")
     )]
+    #[cfg_attr(feature = "english", error("FindWindowExW failed, window class: {window_class}, window title: {window_title}, {error}"))]
     FindWindowExWFailed {
         window_class: String,
         window_title: String,
-        error_message: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("GetClassNameW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("GetClassNameW failed, {error}"))]
+    GetClassNameWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("GetWindowTextW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("GetWindowTextW failed, {error}"))]
+    GetWindowTextWFailed {
+        #[source]
+        error: windows::core::Error,
     },
-    #[error("GetWindowTextW失败，error_code: {error_code:#X}")]
-    GetClassNameWFailed { error_code: u32 },
-    #[error("SetForegroundWindow失败")]
+    #[cfg_attr(not(feature = "english"), error("SetForegroundWindow失败"))]
+    #[cfg_attr(feature = "english", error("SetForegroundWindow failed"))]
     SetForegroundWindowFailed,
-    #[error("GetWindowRect失败，{hwnd:?}，{error_message}")]
-    GetWindowRectFailed { hwnd: HWND, error_message: String },
-    #[error("DwmGetWindowAttribute失败，{hwnd:?}，{error_message}")]
-    DwmGetWindowAttributeFailed { hwnd: HWND, error_message: String },
-    #[error("ClientToScreen失败，{hwnd:?}")]
-    ClientToScreenFailed { hwnd: HWND },
-    #[error("GetClientRect失败，{hwnd:?}，{error_message}")]
-    GetClientRectFailed { hwnd: HWND, error_message: String },
-    #[error("MoveWindow失败，{hwnd:?}，{error_message}")]
-    MoveWindowFailed { hwnd: HWND, error_message: String },
-    #[error("GetWindowThreadProcessId失败，error_code: {error_code:#X}")]
-    GetWindowThreadProcessIdFailed { error_code: u32 },
-    #[error("OpenProcess失败，error_code: {error_message}")]
+    #[cfg_attr(not(feature = "english"), error("没有前台窗口"))]
+    #[cfg_attr(feature = "english", error("no foreground window"))]
+    NoForegroundWindow,
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetWindowRect失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("GetWindowRect failed, {hwnd:?}, {error}"))]
+    GetWindowRectFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("DwmGetWindowAttribute失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("DwmGetWindowAttribute failed, {hwnd:?}, {error}")
+    )]
+    DwmGetWindowAttributeFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("ClientToScreen失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("ClientToScreen failed, {hwnd:?}, {error}"))]
+    ClientToScreenFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("ScreenToClient失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("ScreenToClient failed, {hwnd:?}, {error}"))]
+    ScreenToClientFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetClientRect失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("GetClientRect failed, {hwnd:?}, {error}"))]
+    GetClientRectFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("MoveWindow失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(feature = "english", error("MoveWindow failed, {hwnd:?}, {error}"))]
+    MoveWindowFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetWindowThreadProcessId失败，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("GetWindowThreadProcessId failed, {error}"))]
+    GetWindowThreadProcessIdFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("OpenProcess失败，{error}"))]
+    #[cfg_attr(feature = "english", error("OpenProcess failed, {error}"))]
     OpenProcessFailed {
         process_id: u32,
-        error_message: String,
+        #[source]
+        error: windows::core::Error,
     },
-    #[error(
-        "QueryFullProcessImageNameW失败，process_id: {process_id}，error_code: {error_message}"
+    #[cfg_attr(
+        not(feature = "english"),
+        error("QueryFullProcessImageNameW失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("QueryFullProcessImageNameW failed, process_id: {process_id}, {error}")
     )]
     QueryFullProcessImageNameWFailed {
         process_id: u32,
-        error_message: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("NtQueryInformationProcess失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("NtQueryInformationProcess failed, process_id: {process_id}, {error}")
+    )]
+    NtQueryInformationProcessFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("CreateToolhelp32Snapshot失败，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("CreateToolhelp32Snapshot failed, {error}"))]
+    CreateToolhelp32SnapshotFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("进程不存在，process_id: {process_id}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("process does not exist, process_id: {process_id}")
+    )]
+    ProcessNotExist { process_id: u32 },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("OpenProcessToken失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("OpenProcessToken failed, process_id: {process_id}, {error}")
+    )]
+    OpenProcessTokenFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetTokenInformation失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetTokenInformation failed, process_id: {process_id}, {error}")
+    )]
+    GetTokenInformationFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("LookupAccountSidW失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("LookupAccountSidW failed, process_id: {process_id}, {error}")
+    )]
+    LookupAccountSidWFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
     },
-    #[error("GetWindowLongW失败，error_code: {error_code:#X}")]
-    GetWindowLongWFailed { error_code: u32 },
-    #[error("SetWindowPos失败，{hwnd:?}，{error_message}")]
-    SetWindowPosFailed { hwnd: HWND, error_message: String },
-    #[error("窗口不存在，{hwnd:?}")]
+    #[cfg_attr(
+        not(feature = "english"),
+        error("ProcessIdToSessionId失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("ProcessIdToSessionId failed, process_id: {process_id}, {error}")
+    )]
+    ProcessIdToSessionIdFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("IsWow64Process2失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("IsWow64Process2 failed, process_id: {process_id}, {error}")
+    )]
+    IsWow64Process2Failed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetFileVersionInfoW失败，文件路径：{path}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetFileVersionInfoW failed, path: {path}, {error}")
+    )]
+    GetFileVersionInfoWFailed {
+        path: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetProcessMemoryInfo失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetProcessMemoryInfo failed, process_id: {process_id}, {error}")
+    )]
+    GetProcessMemoryInfoFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetProcessTimes失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetProcessTimes failed, process_id: {process_id}, {error}")
+    )]
+    GetProcessTimesFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("NtSuspendProcess失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("NtSuspendProcess failed, process_id: {process_id}, {error}")
+    )]
+    NtSuspendProcessFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("NtResumeProcess失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("NtResumeProcess failed, process_id: {process_id}, {error}")
+    )]
+    NtResumeProcessFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetPriorityClass失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetPriorityClass failed, process_id: {process_id}, {error}")
+    )]
+    GetPriorityClassFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SetPriorityClass失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SetPriorityClass failed, process_id: {process_id}, {error}")
+    )]
+    SetPriorityClassFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("EnumWindows失败，{error}"))]
+    #[cfg_attr(feature = "english", error("EnumWindows failed, {error}"))]
+    EnumWindowsFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("启动程序失败，命令：{command}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to spawn process, command: {command}, {error}")
+    )]
+    SpawnFailed {
+        command: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("等待窗口超时，process_id: {process_id}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("timed out waiting for window, process_id: {process_id}")
+    )]
+    WaitForWindowTimeout { process_id: u32 },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("找不到进程的主窗口，process_id: {process_id}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("main window of process not found, process_id: {process_id}")
+    )]
+    MainWindowNotExist { process_id: u32 },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetWindowPlacement失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetWindowPlacement failed, {hwnd:?}, {error}")
+    )]
+    GetWindowPlacementFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SetWindowPlacement失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SetWindowPlacement failed, {hwnd:?}, {error}")
+    )]
+    SetWindowPlacementFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("PostMessageW失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(feature = "english", error("PostMessageW failed, {hwnd:?}, {error}"))]
+    PostMessageWFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("TerminateProcess失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("TerminateProcess failed, process_id: {process_id}, {error}")
+    )]
+    TerminateProcessFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("GetLastInputInfo失败，{error}"))]
+    #[cfg_attr(feature = "english", error("GetLastInputInfo failed, {error}"))]
+    GetLastInputInfoFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("字符串中含有\\0，string: {string}"))]
+    #[cfg_attr(feature = "english", error("string contains \\0, string: {string}"))]
+    StringContainsNul { string: String },
+    #[cfg_attr(not(feature = "english"), error("GetWindowLongW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("GetWindowLongW failed, {error}"))]
+    GetWindowLongWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SetWindowLongW失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("SetWindowLongW failed, {hwnd:?}, {error}"))]
+    SetWindowLongWFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SetLayeredWindowAttributes失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SetLayeredWindowAttributes failed, {hwnd:?}, {error}")
+    )]
+    SetLayeredWindowAttributesFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("SetWindowPos失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(feature = "english", error("SetWindowPos failed, {hwnd:?}, {error}"))]
+    SetWindowPosFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("RegisterClassExW失败，窗口类名：{class}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("RegisterClassExW failed, window class: {class}, {error}")
+    )]
+    RegisterClassExWFailed {
+        class: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("CreateWindowExW失败，窗口类名：{class}，窗口标题：{title}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("CreateWindowExW failed, window class: {class}, window title: {title}, {error}")
+    )]
+    CreateWindowExWFailed {
+        class: String,
+        title: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("SetWindowsHookExW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("SetWindowsHookExW failed, {error}"))]
+    SetWindowsHookExWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("RegisterHotKey失败，修饰键：{modifiers:#X}，虚拟键码：{key:#X}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("RegisterHotKey failed, modifiers: {modifiers:#X}, virtual key: {key:#X}, {error}")
+    )]
+    RegisterHotKeyFailed {
+        modifiers: u32,
+        key: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("RegisterShellHookWindow失败，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("RegisterShellHookWindow failed, {error}"))]
+    RegisterShellHookWindowFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("WTSRegisterSessionNotification失败，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("WTSRegisterSessionNotification failed, {error}")
+    )]
+    WTSRegisterSessionNotificationFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SetWinEventHook失败，事件：{event:#X}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SetWinEventHook failed, event: {event:#X}, {error}")
+    )]
+    SetWinEventHookFailed {
+        event: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("BeginDeferWindowPos失败，{error}"))]
+    #[cfg_attr(feature = "english", error("BeginDeferWindowPos failed, {error}"))]
+    BeginDeferWindowPosFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("DeferWindowPos失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("DeferWindowPos failed, {hwnd:?}, {error}"))]
+    DeferWindowPosFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("EndDeferWindowPos失败，{error}"))]
+    #[cfg_attr(feature = "english", error("EndDeferWindowPos failed, {error}"))]
+    EndDeferWindowPosFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("AnimateWindow失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("AnimateWindow failed, {hwnd:?}, {error}"))]
+    AnimateWindowFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("系统不支持{function}"))]
+    #[cfg_attr(
+        feature = "english",
+        error("{function} is not available on this system")
+    )]
+    FunctionNotAvailable { function: &'static str },
+    #[cfg_attr(not(feature = "english"), error("OpenInputDesktop失败，{error}"))]
+    #[cfg_attr(feature = "english", error("OpenInputDesktop failed, {error}"))]
+    OpenInputDesktopFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetUserObjectInformationW失败，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetUserObjectInformationW failed, {error}")
+    )]
+    GetUserObjectInformationWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("SystemParametersInfoW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("SystemParametersInfoW failed, {error}"))]
+    SystemParametersInfoWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("RegisterPowerSettingNotification失败，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("RegisterPowerSettingNotification failed, {error}")
+    )]
+    RegisterPowerSettingNotificationFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("EnumDisplayMonitors失败，{error}"))]
+    #[cfg_attr(feature = "english", error("EnumDisplayMonitors failed, {error}"))]
+    EnumDisplayMonitorsFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetMonitorInfoW失败，{monitor:#X}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("GetMonitorInfoW failed, {monitor:#X}, {error}")
+    )]
+    GetMonitorInfoWFailed {
+        monitor: usize,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("GetWindowBand失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(feature = "english", error("GetWindowBand failed, {hwnd:?}, {error}"))]
+    GetWindowBandFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("MapWindowPoints失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("MapWindowPoints failed, {hwnd:?}, {error}")
+    )]
+    MapWindowPointsFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("AttachThreadInput失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("AttachThreadInput failed, {hwnd:?}, {error}")
+    )]
+    AttachThreadInputFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("{child:?}不是{parent:?}的子窗口"))]
+    #[cfg_attr(feature = "english", error("{child:?} is not a child of {parent:?}"))]
+    NotChildWindow { parent: HWND, child: HWND },
+    #[cfg_attr(not(feature = "english"), error("SetFocus失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(feature = "english", error("SetFocus failed, {hwnd:?}, {error}"))]
+    SetFocusFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("CoInitializeEx失败，{error}"))]
+    #[cfg_attr(feature = "english", error("CoInitializeEx failed, {error}"))]
+    CoInitializeExFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("获取Shell窗口列表失败，{error}"))]
+    #[cfg_attr(feature = "english", error("failed to access shell windows, {error}"))]
+    ShellWindowsFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("获取资源管理器窗口的路径失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to get the path of the Explorer window, {hwnd:?}, {error}")
+    )]
+    GetExplorerWindowPathFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("UI Automation调用失败，{error}"))]
+    #[cfg_attr(feature = "english", error("UI Automation call failed, {error}"))]
+    UIAutomationFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("获取滚动条信息失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to get scroll bar info, {hwnd:?}, {error}")
+    )]
+    GetScrollInfoFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("找不到菜单项，{hwnd:?}，路径：{path}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("menu item not found, {hwnd:?}, path: {path}")
+    )]
+    MenuItemNotFound { hwnd: HWND, path: String },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("菜单项已禁用，{hwnd:?}，路径：{path}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("menu item is disabled, {hwnd:?}, path: {path}")
+    )]
+    MenuItemDisabled { hwnd: HWND, path: String },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SendMessageTimeoutW失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SendMessageTimeoutW failed, {hwnd:?}, {error}")
+    )]
+    SendMessageTimeoutWFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("VirtualAllocEx失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("VirtualAllocEx failed, process_id: {process_id}, {error}")
+    )]
+    VirtualAllocExFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("ReadProcessMemory失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("ReadProcessMemory failed, process_id: {process_id}, {error}")
+    )]
+    ReadProcessMemoryFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("WriteProcessMemory失败，process_id: {process_id}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("WriteProcessMemory failed, process_id: {process_id}, {error}")
+    )]
+    WriteProcessMemoryFailed {
+        process_id: u32,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("进程位数与当前进程不同，process_id: {process_id}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("process pointer width differs from the current process, process_id: {process_id}")
+    )]
+    PointerWidthMismatch { process_id: u32 },
+    #[cfg_attr(not(feature = "english"), error("EnumDesktopsW失败，{error}"))]
+    #[cfg_attr(feature = "english", error("EnumDesktopsW failed, {error}"))]
+    EnumDesktopsWFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("OpenDesktopW失败，desktop: {desktop}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("OpenDesktopW failed, desktop: {desktop}, {error}")
+    )]
+    OpenDesktopWFailed {
+        desktop: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("EnumDesktopWindows失败，desktop: {desktop}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("EnumDesktopWindows failed, desktop: {desktop}, {error}")
+    )]
+    EnumDesktopWindowsFailed {
+        desktop: String,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("RedrawWindow失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(feature = "english", error("RedrawWindow failed, {hwnd:?}, {error}"))]
+    RedrawWindowFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("创建位图失败，{error}"))]
+    #[cfg_attr(feature = "english", error("failed to create bitmap, {error}"))]
+    CreateBitmapFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("截图失败，{hwnd:?}，{error}"))]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to capture window, {hwnd:?}, {error}")
+    )]
+    CaptureFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("读取文件失败，path: {path}，{error}"))]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to read file, path: {path}, {error}")
+    )]
+    ReadFileFailed {
+        path: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("写入文件失败，path: {path}，{error}"))]
+    #[cfg_attr(
+        feature = "english",
+        error("failed to write file, path: {path}, {error}")
+    )]
+    WriteFileFailed {
+        path: String,
+        #[source]
+        error: std::io::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("文字识别失败，{error}"))]
+    #[cfg_attr(feature = "english", error("OCR failed, {error}"))]
+    OcrFailed {
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(
+        not(feature = "english"),
+        error("SHGetPropertyStoreForWindow失败，{hwnd:?}，{error}")
+    )]
+    #[cfg_attr(
+        feature = "english",
+        error("SHGetPropertyStoreForWindow failed, {hwnd:?}, {error}")
+    )]
+    SHGetPropertyStoreForWindowFailed {
+        hwnd: HWND,
+        #[source]
+        error: windows::core::Error,
+    },
+    #[cfg_attr(not(feature = "english"), error("试运行模式下无法执行{operation}"))]
+    #[cfg_attr(
+        feature = "english",
+        error("{operation} is not supported in dry run mode")
+    )]
+    DryRun { operation: &'static str },
+    #[cfg_attr(not(feature = "english"), error("窗口不存在，{hwnd:?}"))]
+    #[cfg_attr(feature = "english", error("window does not exist, {hwnd:?}"))]
     WindowNotExist { hwnd: HWND },
+    #[cfg_attr(not(feature = "english"), error("没有满足条件的窗口"))]
+    #[cfg_attr(feature = "english", error("no matching window"))]
+    NoMatchingWindow,
+}
+
+impl WindowInspectorError {
+    /// 底层系统错误的HRESULT。
+    /// Win32错误码以`HRESULT_FROM_WIN32`的形式给出（例如`ERROR_ACCESS_DENIED`为`0x80070005`），NTSTATUS以`HRESULT_FROM_NT`的形式给出。
+    /// 不是由系统调用失败引起的错误返回`None`。
+    pub fn os_code(&self) -> Option<i32> {
+        let source = std::error::Error::source(self)?;
+        if let Some(error) = source.downcast_ref::<windows::core::Error>() {
+            return Some(error.code().0);
+        }
+        let code = source.downcast_ref::<std::io::Error>()?.raw_os_error()?;
+        Some(HRESULT::from_win32(code as u32).0)
+    }
+
+    /// 错误的类别。
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            WindowInspectorError::NoForegroundWindow
+            | WindowInspectorError::ProcessNotExist { .. }
+            | WindowInspectorError::MainWindowNotExist { .. }
+            | WindowInspectorError::WindowNotExist { .. }
+            | WindowInspectorError::NoMatchingWindow => return ErrorKind::NotFound,
+            WindowInspectorError::WaitForWindowTimeout { .. } => return ErrorKind::Timeout,
+            // 进程已经退出时，OpenProcess返回ERROR_INVALID_PARAMETER。
+            WindowInspectorError::OpenProcessFailed { error, .. }
+                if error.code() == HRESULT::from_win32(ERROR_INVALID_PARAMETER.0) =>
+            {
+                return ErrorKind::NotFound
+            }
+            _ => {}
+        }
+        let Some(code) = self.os_code() else {
+            return ErrorKind::Other;
+        };
+        let code = HRESULT(code);
+        let is_any = |win32_errors: &[u32], nt_statuses: &[i32]| {
+            win32_errors
+                .iter()
+                .any(|&error| code == HRESULT::from_win32(error))
+                || nt_statuses
+                    .iter()
+                    .any(|&status| code == HRESULT::from_nt(status))
+        };
+        if is_any(
+            &[
+                ERROR_FILE_NOT_FOUND.0,
+                ERROR_PATH_NOT_FOUND.0,
+                ERROR_NOT_FOUND.0,
+                ERROR_RESOURCE_TYPE_NOT_FOUND.0,
+            ],
+            &[],
+        ) {
+            ErrorKind::NotFound
+        } else if is_any(&[ERROR_ACCESS_DENIED.0], &[STATUS_ACCESS_DENIED.0]) {
+            ErrorKind::AccessDenied
+        } else if is_any(
+            &[ERROR_INVALID_HANDLE.0, ERROR_INVALID_WINDOW_HANDLE.0],
+            &[STATUS_INVALID_HANDLE.0],
+        ) {
+            ErrorKind::InvalidHandle
+        } else if is_any(&[ERROR_TIMEOUT.0, WAIT_TIMEOUT.0], &[]) {
+            ErrorKind::Timeout
+        } else {
+            ErrorKind::Other
+        }
+    }
+
+    /// 是否是暂时性的错误，即重试可能成功。
+    /// 包括：前台锁导致的[`WindowInspectorError::SetForegroundWindowFailed`]，
+    /// 以及对窗口操作时被UIPI拒绝（例如目标窗口刚好处于提升权限的对话框下）。
+    /// 窗口已经关闭（句柄无效、窗口不存在）不是暂时性的错误，关闭的窗口不会再出现。
+    /// 可以配合[`crate::retry::with_retry`]使用。
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WindowInspectorError::SetForegroundWindowFailed => true,
+            WindowInspectorError::SetWindowPosFailed { .. }
+            | WindowInspectorError::MoveWindowFailed { .. }
+            | WindowInspectorError::DeferWindowPosFailed { .. }
+            | WindowInspectorError::PostMessageWFailed { .. }
+            | WindowInspectorError::SetWindowPlacementFailed { .. } => {
+                self.kind() == ErrorKind::AccessDenied
+            }
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_error_kind() {
+    let error = WindowInspectorError::GetWindowRectFailed {
+        hwnd: HWND::default(),
+        error: windows::core::Error::from_hresult(HRESULT::from_win32(
+            ERROR_INVALID_WINDOW_HANDLE.0,
+        )),
+    };
+    assert_eq!(error.os_code(), Some(0x80070578_u32 as i32));
+    assert_eq!(error.kind(), ErrorKind::InvalidHandle);
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error = WindowInspectorError::SpawnFailed {
+        command: "a.exe".to_string(),
+        error: std::io::Error::from_raw_os_error(ERROR_ACCESS_DENIED.0 as i32),
+    };
+    assert_eq!(error.kind(), ErrorKind::AccessDenied);
+
+    let error = WindowInspectorError::WindowClassTitleBothEmpty;
+    assert_eq!(error.os_code(), None);
+    assert_eq!(error.kind(), ErrorKind::Other);
 }
@@ -0,0 +1,1194 @@
+//! 系统事件通知。每个订阅在后台线程中接收事件，通过[`EventReceiver`]取出。
+//!
+//! 事件送达时窗口可能已经关闭。[`owned_win_events`]在钩子线程收到事件时就把窗口信息复制出来，
+//! 取出的[`OwnedWindowEvent`]不依赖窗口仍然存在。
+//!
+//! # 示例
+//! ```no_run
+//! use window_inspector::events::shell_hook;
+//!
+//! let events = shell_hook().unwrap();
+//! for event in events.iter() {
+//!     println!("{:?}", event);
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::mem::size_of;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+use std::time::Instant;
+
+use bitflags::bitflags;
+use windows::core::w;
+use windows::core::HRESULT;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::ERROR_CLASS_ALREADY_EXISTS;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Power::RegisterPowerSettingNotification;
+use windows::Win32::System::Power::UnregisterPowerSettingNotification;
+use windows::Win32::System::Power::POWERBROADCAST_SETTING;
+use windows::Win32::System::RemoteDesktop::WTSRegisterSessionNotification;
+use windows::Win32::System::RemoteDesktop::WTSUnRegisterSessionNotification;
+use windows::Win32::System::RemoteDesktop::NOTIFY_FOR_THIS_SESSION;
+use windows::Win32::System::SystemServices::GUID_CONSOLE_DISPLAY_STATE;
+use windows::Win32::UI::Accessibility::SetWinEventHook;
+use windows::Win32::UI::Accessibility::UnhookWinEvent;
+use windows::Win32::UI::Accessibility::HWINEVENTHOOK;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::DeregisterShellHookWindow;
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::RegisterShellHookWindow;
+use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+use windows::Win32::UI::WindowsAndMessaging::SetTimer;
+use windows::Win32::UI::WindowsAndMessaging::CHILDID_SELF;
+use windows::Win32::UI::WindowsAndMessaging::DBT_DEVNODES_CHANGED;
+use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_WINDOW_HANDLE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_CREATE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_DESTROY;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_LOCATIONCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_NAMECHANGE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_SHOW;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_FOREGROUND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZEEND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZESTART;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MOVESIZEEND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MOVESIZESTART;
+use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::HSHELL_HIGHBIT;
+use windows::Win32::UI::WindowsAndMessaging::HSHELL_REDRAW;
+use windows::Win32::UI::WindowsAndMessaging::HSHELL_WINDOWACTIVATED;
+use windows::Win32::UI::WindowsAndMessaging::HSHELL_WINDOWCREATED;
+use windows::Win32::UI::WindowsAndMessaging::HSHELL_WINDOWDESTROYED;
+use windows::Win32::UI::WindowsAndMessaging::OBJID_WINDOW;
+use windows::Win32::UI::WindowsAndMessaging::PBT_POWERSETTINGCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::SPI_SETWORKAREA;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WINEVENT_OUTOFCONTEXT;
+use windows::Win32::UI::WindowsAndMessaging::WM_DEVICECHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_POWERBROADCAST;
+use windows::Win32::UI::WindowsAndMessaging::WM_SETTINGCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_WTSSESSION_CHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WS_CHILD;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WTS_CONSOLE_CONNECT;
+use windows::Win32::UI::WindowsAndMessaging::WTS_CONSOLE_DISCONNECT;
+use windows::Win32::UI::WindowsAndMessaging::WTS_REMOTE_CONNECT;
+use windows::Win32::UI::WindowsAndMessaging::WTS_REMOTE_DISCONNECT;
+use windows::Win32::UI::WindowsAndMessaging::WTS_SESSION_LOCK;
+use windows::Win32::UI::WindowsAndMessaging::WTS_SESSION_LOGOFF;
+use windows::Win32::UI::WindowsAndMessaging::WTS_SESSION_LOGON;
+use windows::Win32::UI::WindowsAndMessaging::WTS_SESSION_UNLOCK;
+
+use crate::class_title::get_window_class;
+use crate::class_title::get_window_title;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::find::WindowMatcher;
+use crate::geometry::Rect;
+use crate::message_loop::MessageThread;
+use crate::position_size::get_window_rect_include_shadow;
+use crate::process::get_window_process;
+use crate::result::Result;
+
+/// 已安装的钩子（或其他订阅）及处理它的后台线程。
+/// drop时卸载钩子（`UnhookWinEvent`等）并结束后台线程，钩子不会泄漏。
+#[must_use = "guard被drop时会立即卸载钩子"]
+#[derive(Debug)]
+pub struct HookGuard {
+    thread: MessageThread,
+}
+
+impl HookGuard {
+    /// 卸载钩子并等待后台线程结束。与drop相同，只是更明确。
+    pub fn shutdown(self) {
+        drop(self.thread);
+    }
+}
+
+/// 事件订阅，drop时取消订阅并结束后台线程。
+#[derive(Debug)]
+pub struct EventReceiver<T> {
+    receiver: Receiver<T>,
+    guard: HookGuard,
+}
+
+impl<T> EventReceiver<T> {
+    /// 取消订阅并等待后台线程结束。与drop相同，只是更明确。
+    pub fn shutdown(self) {
+        self.guard.shutdown();
+    }
+
+    /// 拆分为接收端和[`HookGuard`]，例如将接收端交给其他线程，由当前线程控制订阅的生命周期。
+    /// guard被drop后接收端仍可以取出已经到达的事件。
+    pub fn into_parts(self) -> (Receiver<T>, HookGuard) {
+        (self.receiver, self.guard)
+    }
+
+    /// 阻塞等待下一个事件。
+    pub fn recv(&self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+
+    /// 取出一个已经到达的事件，没有时立即返回`None`。
+    pub fn try_recv(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// 最多等待`timeout`，超时返回`None`。
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// 阻塞地逐个取出事件。
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.receiver.iter()
+    }
+}
+
+/// 隐藏窗口收到的消息的处理函数，返回`true`表示已处理。
+type WindowHandler = Box<dyn FnMut(u32, WPARAM, LPARAM) -> bool>;
+
+thread_local! {
+    /// 当前线程的隐藏窗口的消息处理函数。每个订阅有自己的线程。
+    static WINDOW_HANDLER: RefCell<Option<WindowHandler>> = const { RefCell::new(None) };
+}
+
+unsafe extern "system" fn event_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let handled = WINDOW_HANDLER.with_borrow_mut(|handler| {
+        handler
+            .as_mut()
+            .is_some_and(|handler| handler(msg, wparam, lparam))
+    });
+    if handled {
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// 隐藏窗口订阅失败的原因。
+enum ListenError {
+    Window(windows::core::Error),
+    Register(windows::core::Error),
+}
+
+/// 在后台线程中创建隐藏的顶层窗口并用`register`订阅通知，窗口收到的消息由`handler`转换为事件。
+/// 许多广播消息不会发送给仅消息窗口，所以使用隐藏的顶层窗口。
+/// `register`的返回值在取消订阅时传给`unregister`，失败时的错误由`register_error`转换。
+fn listen_window<T: Send + 'static, R: 'static>(
+    register: impl FnOnce(HWND) -> windows::core::Result<R> + Send + 'static,
+    unregister: impl FnOnce(HWND, R) + Send + 'static,
+    mut handler: impl FnMut(u32, WPARAM, LPARAM) -> Option<T> + Send + 'static,
+    register_error: impl FnOnce(windows::core::Error) -> WindowInspectorError,
+) -> Result<EventReceiver<T>> {
+    let (sender, receiver) = mpsc::channel();
+    let thread = MessageThread::spawn(
+        move || {
+            WINDOW_HANDLER.set(Some(Box::new(move |msg, wparam, lparam| {
+                match handler(msg, wparam, lparam) {
+                    Some(event) => {
+                        let _ = sender.send(event);
+                        true
+                    }
+                    None => false,
+                }
+            })));
+            let hwnd = create_event_window().map_err(ListenError::Window)?;
+            match register(hwnd) {
+                Ok(registration) => Ok((hwnd, registration)),
+                Err(e) => {
+                    let _ = unsafe { DestroyWindow(hwnd) };
+                    Err(ListenError::Register(e))
+                }
+            }
+        },
+        |_| {},
+        move |(hwnd, registration)| {
+            unregister(hwnd, registration);
+            let _ = unsafe { DestroyWindow(hwnd) };
+            WINDOW_HANDLER.take();
+        },
+    )
+    .map_err(|e| match e {
+        ListenError::Window(error) => WindowInspectorError::CreateWindowExWFailed {
+            class: "window_inspector_events".to_string(),
+            title: String::new(),
+            error,
+        },
+        ListenError::Register(error) => register_error(error),
+    })?;
+    Ok(EventReceiver {
+        receiver,
+        guard: HookGuard { thread },
+    })
+}
+
+/// 创建不可见的顶层工具窗口。
+fn create_event_window() -> windows::core::Result<HWND> {
+    let instance = unsafe { GetModuleHandleW(None) }?;
+    let window_class = WNDCLASSEXW {
+        cbSize: size_of::<WNDCLASSEXW>() as u32,
+        lpfnWndProc: Some(event_window_proc),
+        hInstance: instance.into(),
+        lpszClassName: w!("window_inspector_events"),
+        ..Default::default()
+    };
+    if unsafe { RegisterClassExW(&window_class) } == 0 {
+        let error = windows::core::Error::from_win32();
+        if error.code() != HRESULT::from_win32(ERROR_CLASS_ALREADY_EXISTS.0) {
+            return Err(error);
+        }
+    }
+    unsafe {
+        CreateWindowExW(
+            WS_EX_TOOLWINDOW,
+            w!("window_inspector_events"),
+            PCWSTR::null(),
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            instance,
+            None,
+        )
+    }
+}
+
+/// Shell钩子事件，见[`shell_hook`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShellEvent {
+    /// 创建了顶层、无所有者的窗口（会显示在任务栏上的窗口）。
+    WindowCreated { hwnd: usize },
+    /// 上面这类窗口被销毁。
+    WindowDestroyed { hwnd: usize },
+    /// 激活了顶层窗口。`full_screen`为`true`表示是全屏窗口。
+    WindowActivated { hwnd: usize, full_screen: bool },
+    /// 窗口的标题或图标改变，任务栏需要重绘。
+    Redraw { hwnd: usize },
+    /// 窗口请求闪烁任务栏按钮。
+    Flash { hwnd: usize },
+}
+
+/// 订阅Shell钩子（`RegisterShellHookWindow`）事件。
+/// 能收到一些WinEvent钩子收不到的任务栏级别事件，例如任务栏按钮闪烁。
+pub fn shell_hook() -> Result<EventReceiver<ShellEvent>> {
+    let shell_hook_message = unsafe { RegisterWindowMessageW(w!("SHELLHOOK")) };
+    listen_window(
+        |hwnd| {
+            if unsafe { RegisterShellHookWindow(hwnd) }.as_bool() {
+                Ok(())
+            } else {
+                Err(windows::core::Error::from_win32())
+            }
+        },
+        |hwnd, ()| {
+            let _ = unsafe { DeregisterShellHookWindow(hwnd) };
+        },
+        move |msg, wparam, lparam| {
+            if msg != shell_hook_message {
+                return None;
+            }
+            let hwnd = lparam.0 as usize;
+            let code = wparam.0 as u32;
+            match code {
+                HSHELL_WINDOWCREATED => Some(ShellEvent::WindowCreated { hwnd }),
+                HSHELL_WINDOWDESTROYED => Some(ShellEvent::WindowDestroyed { hwnd }),
+                HSHELL_WINDOWACTIVATED => Some(ShellEvent::WindowActivated {
+                    hwnd,
+                    full_screen: false,
+                }),
+                // HSHELL_RUDEAPPACTIVATED，即HSHELL_WINDOWACTIVATED | HSHELL_HIGHBIT。
+                _ if code == HSHELL_WINDOWACTIVATED | HSHELL_HIGHBIT => {
+                    Some(ShellEvent::WindowActivated {
+                        hwnd,
+                        full_screen: true,
+                    })
+                }
+                HSHELL_REDRAW => Some(ShellEvent::Redraw { hwnd }),
+                // HSHELL_FLASH，即HSHELL_REDRAW | HSHELL_HIGHBIT。
+                _ if code == HSHELL_REDRAW | HSHELL_HIGHBIT => Some(ShellEvent::Flash { hwnd }),
+                _ => None,
+            }
+        },
+        |error| WindowInspectorError::RegisterShellHookWindowFailed { error },
+    )
+}
+
+/// 会话事件，见[`session_events`]。`session_id`是发生变化的会话。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionEvent {
+    /// 会话被锁定（锁屏）。
+    Lock { session_id: u32 },
+    /// 会话被解锁。
+    Unlock { session_id: u32 },
+    /// 用户登录。
+    Logon { session_id: u32 },
+    /// 用户注销。
+    Logoff { session_id: u32 },
+    /// 会话连接到本地控制台。
+    ConsoleConnect { session_id: u32 },
+    /// 会话与本地控制台断开。
+    ConsoleDisconnect { session_id: u32 },
+    /// 会话通过远程桌面连接。
+    RemoteConnect { session_id: u32 },
+    /// 会话的远程桌面连接断开。
+    RemoteDisconnect { session_id: u32 },
+}
+
+/// 订阅当前会话的锁定、解锁、登录注销、远程连接断开事件（`WTSRegisterSessionNotification`）。
+/// 锁屏期间大部分窗口操作会失败，使用时长统计、布局管理等可以在这些事件前后暂停或重新应用。
+pub fn session_events() -> Result<EventReceiver<SessionEvent>> {
+    listen_window(
+        |hwnd| unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) },
+        |hwnd, ()| {
+            let _ = unsafe { WTSUnRegisterSessionNotification(hwnd) };
+        },
+        |msg, wparam, lparam| {
+            if msg != WM_WTSSESSION_CHANGE {
+                return None;
+            }
+            let session_id = lparam.0 as u32;
+            match wparam.0 as u32 {
+                WTS_SESSION_LOCK => Some(SessionEvent::Lock { session_id }),
+                WTS_SESSION_UNLOCK => Some(SessionEvent::Unlock { session_id }),
+                WTS_SESSION_LOGON => Some(SessionEvent::Logon { session_id }),
+                WTS_SESSION_LOGOFF => Some(SessionEvent::Logoff { session_id }),
+                WTS_CONSOLE_CONNECT => Some(SessionEvent::ConsoleConnect { session_id }),
+                WTS_CONSOLE_DISCONNECT => Some(SessionEvent::ConsoleDisconnect { session_id }),
+                WTS_REMOTE_CONNECT => Some(SessionEvent::RemoteConnect { session_id }),
+                WTS_REMOTE_DISCONNECT => Some(SessionEvent::RemoteDisconnect { session_id }),
+                _ => None,
+            }
+        },
+        |error| WindowInspectorError::WTSRegisterSessionNotificationFailed { error },
+    )
+}
+
+/// 显示设置事件，见[`display_changes`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayEvent {
+    /// 显示器分辨率或颜色深度改变，也会在增减显示器时发生。`width`、`height`是主显示器的新分辨率。
+    DisplayChanged {
+        width: u32,
+        height: u32,
+        bits_per_pixel: u32,
+    },
+    /// 设备增减，例如插拔显示器。可能有其他设备引起的误报，收到后应重新获取显示器信息。
+    DevicesChanged,
+    /// 工作区（去掉任务栏等的区域）改变。
+    WorkAreaChanged,
+}
+
+/// 订阅显示器增减、分辨率改变等事件（`WM_DISPLAYCHANGE`、`WM_DEVICECHANGE`、`WM_SETTINGCHANGE`）。
+/// 布局工具可以在收到事件后重新计算窗口位置。
+pub fn display_changes() -> Result<EventReceiver<DisplayEvent>> {
+    listen_window(
+        |_| Ok(()),
+        |_, ()| {},
+        |msg, wparam, lparam| match msg {
+            WM_DISPLAYCHANGE => Some(DisplayEvent::DisplayChanged {
+                width: (lparam.0 & 0xFFFF) as u32,
+                height: ((lparam.0 >> 16) & 0xFFFF) as u32,
+                bits_per_pixel: wparam.0 as u32,
+            }),
+            WM_DEVICECHANGE if wparam.0 as u32 == DBT_DEVNODES_CHANGED => {
+                Some(DisplayEvent::DevicesChanged)
+            }
+            WM_SETTINGCHANGE if wparam.0 as u32 == SPI_SETWORKAREA.0 => {
+                Some(DisplayEvent::WorkAreaChanged)
+            }
+            _ => None,
+        },
+        // 不需要订阅，不会失败。
+        |_| unreachable!(),
+    )
+}
+
+/// 显示器电源状态，见[`display_power_events`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayPower {
+    /// 关闭。
+    Off,
+    /// 打开。
+    On,
+    /// 变暗（即将关闭）。
+    Dimmed,
+}
+
+/// 订阅显示器电源状态（`GUID_CONSOLE_DISPLAY_STATE`）。订阅后会立即收到一次当前状态。
+pub fn display_power_events() -> Result<EventReceiver<DisplayPower>> {
+    listen_window(
+        |hwnd| unsafe {
+            RegisterPowerSettingNotification(
+                HANDLE(hwnd.0),
+                &GUID_CONSOLE_DISPLAY_STATE,
+                DEVICE_NOTIFY_WINDOW_HANDLE,
+            )
+        },
+        |_, notify| {
+            let _ = unsafe { UnregisterPowerSettingNotification(notify) };
+        },
+        |msg, wparam, lparam| {
+            if msg != WM_POWERBROADCAST || wparam.0 as u32 != PBT_POWERSETTINGCHANGE {
+                return None;
+            }
+            let setting = unsafe { &*(lparam.0 as *const POWERBROADCAST_SETTING) };
+            if setting.PowerSetting != GUID_CONSOLE_DISPLAY_STATE {
+                return None;
+            }
+            match setting.Data[0] {
+                0 => Some(DisplayPower::Off),
+                1 => Some(DisplayPower::On),
+                2 => Some(DisplayPower::Dimmed),
+                _ => None,
+            }
+        },
+        |error| WindowInspectorError::RegisterPowerSettingNotificationFailed { error },
+    )
+}
+
+bitflags! {
+    /// 要订阅的WinEvent事件类型，见[`EventFilter`]。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EventKinds: u32 {
+        /// 前台窗口改变（`EVENT_SYSTEM_FOREGROUND`）。
+        const FOREGROUND = 1 << 0;
+        /// 窗口创建（`EVENT_OBJECT_CREATE`）。
+        const CREATE = 1 << 1;
+        /// 窗口销毁（`EVENT_OBJECT_DESTROY`）。
+        const DESTROY = 1 << 2;
+        /// 窗口显示（`EVENT_OBJECT_SHOW`）。
+        const SHOW = 1 << 3;
+        /// 窗口隐藏（`EVENT_OBJECT_HIDE`）。
+        const HIDE = 1 << 4;
+        /// 窗口位置尺寸改变（`EVENT_OBJECT_LOCATIONCHANGE`）。拖动窗口时每秒可能有上百次。
+        const LOCATION_CHANGE = 1 << 5;
+        /// 窗口标题改变（`EVENT_OBJECT_NAMECHANGE`）。
+        const NAME_CHANGE = 1 << 6;
+        /// 窗口最小化（`EVENT_SYSTEM_MINIMIZESTART`）。
+        const MINIMIZE = 1 << 7;
+        /// 窗口从最小化恢复（`EVENT_SYSTEM_MINIMIZEEND`）。
+        const RESTORE = 1 << 8;
+        /// 开始拖动或调整窗口大小（`EVENT_SYSTEM_MOVESIZESTART`）。
+        const MOVE_SIZE_START = 1 << 9;
+        /// 结束拖动或调整窗口大小（`EVENT_SYSTEM_MOVESIZEEND`）。
+        const MOVE_SIZE_END = 1 << 10;
+    }
+}
+
+/// 事件类型与WinEvent事件常量的对应关系。
+const WIN_EVENTS: [(EventKinds, u32); 11] = [
+    (EventKinds::FOREGROUND, EVENT_SYSTEM_FOREGROUND),
+    (EventKinds::CREATE, EVENT_OBJECT_CREATE),
+    (EventKinds::DESTROY, EVENT_OBJECT_DESTROY),
+    (EventKinds::SHOW, EVENT_OBJECT_SHOW),
+    (EventKinds::HIDE, EVENT_OBJECT_HIDE),
+    (EventKinds::LOCATION_CHANGE, EVENT_OBJECT_LOCATIONCHANGE),
+    (EventKinds::NAME_CHANGE, EVENT_OBJECT_NAMECHANGE),
+    (EventKinds::MINIMIZE, EVENT_SYSTEM_MINIMIZESTART),
+    (EventKinds::RESTORE, EVENT_SYSTEM_MINIMIZEEND),
+    (EventKinds::MOVE_SIZE_START, EVENT_SYSTEM_MOVESIZESTART),
+    (EventKinds::MOVE_SIZE_END, EVENT_SYSTEM_MOVESIZEEND),
+];
+
+/// 窗口事件，见[`win_events`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WinEvent {
+    /// 事件类型，只包含一种。
+    pub kind: EventKinds,
+    /// 发生事件的窗口。
+    pub hwnd: usize,
+    /// 事件发生的时间，即`GetTickCount`的值（毫秒）。
+    pub time: u32,
+}
+
+/// 带窗口信息的窗口事件，见[`owned_win_events`]。
+/// 窗口信息在钩子线程收到事件时立即读取并复制，之后窗口关闭、句柄被复用都不影响这些字段。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedWindowEvent {
+    /// 事件类型，只包含一种。
+    pub kind: EventKinds,
+    /// 发生事件的窗口。仅用于标识，读取时窗口可能已经关闭。
+    pub hwnd: usize,
+    /// 事件发生的时间，即`GetTickCount`的值（毫秒）。
+    pub time: u32,
+    /// 窗口类名，收到事件时窗口已经销毁则为`None`，下同。
+    pub class: Option<String>,
+    /// 窗口标题。
+    pub title: Option<String>,
+    /// 窗口矩形（包括阴影）。
+    pub rect: Option<Rect>,
+    /// 所属进程ID。
+    pub process_id: Option<u32>,
+}
+
+impl OwnedWindowEvent {
+    /// 读取窗口信息。
+    fn capture(event: WinEvent) -> Self {
+        Self {
+            kind: event.kind,
+            hwnd: event.hwnd,
+            time: event.time,
+            class: get_window_class(event.hwnd).ok(),
+            // 标题为空时获取标题也会失败，只要窗口存在就视为空标题。
+            title: is_window_exist(event.hwnd)
+                .then(|| get_window_title(event.hwnd).unwrap_or_default()),
+            rect: get_window_rect_include_shadow(event.hwnd).ok(),
+            process_id: get_window_process(event.hwnd as isize).ok(),
+        }
+    }
+}
+
+/// 钩子线程发送的事件，[`RateLimiter`]按其中的[`WinEvent`]合并。
+trait HookEvent: Clone {
+    fn win_event(&self) -> WinEvent;
+}
+
+impl HookEvent for WinEvent {
+    fn win_event(&self) -> WinEvent {
+        *self
+    }
+}
+
+impl HookEvent for OwnedWindowEvent {
+    fn win_event(&self) -> WinEvent {
+        WinEvent {
+            kind: self.kind,
+            hwnd: self.hwnd,
+            time: self.time,
+        }
+    }
+}
+
+/// 事件过滤条件，见[`win_events`]。
+/// 指定了进程或窗口时只在对应的进程或线程中安装钩子，系统不会把其他程序的事件发送过来，
+/// 比在回调中过滤省CPU。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventFilter {
+    /// 只接收这个进程的窗口的事件。
+    pub pid: Option<u32>,
+    /// 只接收这个窗口的事件。指定后忽略`pid`。
+    pub hwnd: Option<usize>,
+    /// 要接收的事件类型。
+    pub event_kinds: EventKinds,
+    /// 限流方式，对位置改变、标题改变这类频繁的事件很有用。
+    pub rate_limit: RateLimit,
+}
+
+impl Default for EventFilter {
+    /// 所有进程、所有窗口、所有事件类型，不限流。
+    fn default() -> Self {
+        Self {
+            pid: None,
+            hwnd: None,
+            event_kinds: EventKinds::all(),
+            rate_limit: RateLimit::None,
+        }
+    }
+}
+
+/// 同一窗口同一类型的事件的限流方式，见[`EventFilter::rate_limit`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RateLimit {
+    /// 不限流，每个事件都会收到。
+    #[default]
+    None,
+    /// 事件停止`Duration`后才发送最后一个事件，例如拖动窗口结束后只收到最终位置。
+    Debounce(Duration),
+    /// 每`Duration`最多发送一个事件：先立即发送，之后期间内的事件合并为最后一个，在期满时发送。
+    Throttle(Duration),
+}
+
+/// 按(事件类型, 窗口)合并事件。
+struct RateLimiter<T = WinEvent> {
+    rate_limit: RateLimit,
+    /// 等待发送的事件及其发送时间。
+    pending: HashMap<(EventKinds, usize), (T, Instant)>,
+    /// 节流时上一次发送的时间。
+    last_sent: HashMap<(EventKinds, usize), Instant>,
+}
+
+impl<T> Default for RateLimiter<T> {
+    fn default() -> Self {
+        Self {
+            rate_limit: RateLimit::default(),
+            pending: HashMap::new(),
+            last_sent: HashMap::new(),
+        }
+    }
+}
+
+impl<T: HookEvent> RateLimiter<T> {
+    /// 处理收到的事件，需要立即发送时返回`true`。
+    fn push(&mut self, event: &T) -> bool {
+        let WinEvent { kind, hwnd, .. } = event.win_event();
+        let key = (kind, hwnd);
+        let now = Instant::now();
+        match self.rate_limit {
+            RateLimit::None => true,
+            RateLimit::Debounce(duration) => {
+                self.pending.insert(key, (event.clone(), now + duration));
+                false
+            }
+            RateLimit::Throttle(duration) => match self.last_sent.get(&key) {
+                Some(&last) if now < last + duration => {
+                    self.pending.insert(key, (event.clone(), last + duration));
+                    false
+                }
+                _ => {
+                    self.last_sent.insert(key, now);
+                    true
+                }
+            },
+        }
+    }
+
+    /// 取出到期的事件。
+    fn flush(&mut self) -> Vec<T> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        self.pending.retain(|_, (event, at)| {
+            if *at <= now {
+                due.push(event.clone());
+                false
+            } else {
+                true
+            }
+        });
+        due.sort_by_key(|event| event.win_event().time);
+        if let RateLimit::Throttle(duration) = self.rate_limit {
+            for event in &due {
+                let WinEvent { kind, hwnd, .. } = event.win_event();
+                self.last_sent.insert((kind, hwnd), now);
+            }
+            self.last_sent.retain(|_, &mut last| now < last + duration);
+        }
+        due
+    }
+}
+
+/// 事件的限流和发送。
+struct EventSink<T> {
+    limiter: RateLimiter<T>,
+    sender: Sender<T>,
+}
+
+impl<T: HookEvent> EventSink<T> {
+    fn push(&mut self, event: T) {
+        if self.limiter.push(&event) {
+            let _ = self.sender.send(event);
+        }
+    }
+
+    fn flush(&mut self) {
+        for event in self.limiter.flush() {
+            let _ = self.sender.send(event);
+        }
+    }
+}
+
+/// 钩子线程发送的事件类型。
+enum WinEventSink {
+    Raw(EventSink<WinEvent>),
+    /// 收到事件时立即读取窗口信息，见[`owned_win_events`]。
+    Owned(EventSink<OwnedWindowEvent>),
+}
+
+/// 钩子线程的状态。
+struct WinEventState {
+    /// 只接收这个窗口的事件。
+    hwnd: Option<usize>,
+    sink: WinEventSink,
+}
+
+thread_local! {
+    /// 当前线程的WinEvent钩子的状态。每个订阅有自己的线程。
+    static WIN_EVENT_STATE: RefCell<Option<WinEventState>> = const { RefCell::new(None) };
+}
+
+unsafe extern "system" fn flush_proc(_: HWND, _: u32, _: usize, _: u32) {
+    WIN_EVENT_STATE.with_borrow_mut(|state| match state {
+        Some(WinEventState {
+            sink: WinEventSink::Raw(sink),
+            ..
+        }) => sink.flush(),
+        Some(WinEventState {
+            sink: WinEventSink::Owned(sink),
+            ..
+        }) => sink.flush(),
+        None => {}
+    });
+}
+
+unsafe extern "system" fn win_event_proc(
+    _: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _: u32,
+    time: u32,
+) {
+    // 只关心窗口本身，不关心窗口中的控件、光标等对象。
+    if hwnd.is_invalid() || id_object != OBJID_WINDOW.0 || id_child != CHILDID_SELF as i32 {
+        return;
+    }
+    let Some(&(kind, _)) = WIN_EVENTS.iter().find(|(_, e)| *e == event) else {
+        return;
+    };
+    let hwnd = hwnd.0 as usize;
+    let wanted = WIN_EVENT_STATE.with_borrow(|state| {
+        state.as_ref().and_then(|state| {
+            state
+                .hwnd
+                .is_none_or(|filter_hwnd| filter_hwnd == hwnd)
+                .then_some(matches!(state.sink, WinEventSink::Owned(_)))
+        })
+    });
+    let Some(owned) = wanted else {
+        return;
+    };
+    let event = WinEvent { kind, hwnd, time };
+    // 读取本进程中窗口的标题时会发送WM_GETTEXT并等待窗口所在的线程处理，期间钩子可能被重入，
+    // 因此在借用状态之前读取。
+    let owned_event = owned.then(|| OwnedWindowEvent::capture(event));
+    WIN_EVENT_STATE.with_borrow_mut(|state| match (state, owned_event) {
+        (
+            Some(WinEventState {
+                sink: WinEventSink::Raw(sink),
+                ..
+            }),
+            _,
+        ) => sink.push(event),
+        (
+            Some(WinEventState {
+                sink: WinEventSink::Owned(sink),
+                ..
+            }),
+            Some(owned_event),
+        ) => sink.push(owned_event),
+        _ => {}
+    });
+}
+
+/// 订阅窗口事件（`SetWinEventHook`）。
+/// 指定窗口时，窗口不存在会返回错误；窗口关闭后不会再收到事件。
+///
+/// 事件是异步送达的，取出事件时窗口可能已经关闭，句柄甚至可能已被新窗口复用。
+/// 需要窗口的标题、位置等信息时应使用[`owned_win_events`]。
+pub fn win_events(filter: &EventFilter) -> Result<EventReceiver<WinEvent>> {
+    let (sender, receiver) = mpsc::channel();
+    let guard = install_win_event_hooks(
+        filter,
+        WinEventSink::Raw(EventSink {
+            limiter: RateLimiter {
+                rate_limit: filter.rate_limit,
+                ..Default::default()
+            },
+            sender,
+        }),
+    )?;
+    Ok(EventReceiver { receiver, guard })
+}
+
+/// 订阅窗口事件，同[`win_events`]，但事件中带有窗口信息。
+///
+/// 保证：窗口的类名、标题、矩形、进程ID在钩子线程收到事件时立即读取，复制为自有的数据，
+/// 取出事件时不需要再访问窗口，不会因窗口已经关闭而失败或读到复用句柄的新窗口。
+/// 限流时合并后的事件带有最后一个事件收到时的信息。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::events::owned_win_events;
+/// use window_inspector::events::EventFilter;
+/// use window_inspector::events::EventKinds;
+///
+/// let events = owned_win_events(&EventFilter {
+///     event_kinds: EventKinds::NAME_CHANGE,
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// for event in events.iter() {
+///     println!("{:#X} {:?}", event.hwnd, event.title);
+/// }
+/// ```
+pub fn owned_win_events(filter: &EventFilter) -> Result<EventReceiver<OwnedWindowEvent>> {
+    let (sender, receiver) = mpsc::channel();
+    let guard = install_win_event_hooks(
+        filter,
+        WinEventSink::Owned(EventSink {
+            limiter: RateLimiter {
+                rate_limit: filter.rate_limit,
+                ..Default::default()
+            },
+            sender,
+        }),
+    )?;
+    Ok(EventReceiver { receiver, guard })
+}
+
+/// 在后台线程中安装WinEvent钩子，事件交给`sink`。
+fn install_win_event_hooks(filter: &EventFilter, sink: WinEventSink) -> Result<HookGuard> {
+    let filter = *filter;
+    let (process_id, thread_id) = match filter.hwnd {
+        Some(hwnd) => {
+            if !is_window_exist(hwnd) {
+                return Err(WindowInspectorError::WindowNotExist {
+                    hwnd: HWND(hwnd as *mut c_void),
+                });
+            }
+            let mut process_id = 0;
+            let thread_id = unsafe {
+                GetWindowThreadProcessId(HWND(hwnd as *mut c_void), Some(&mut process_id))
+            };
+            (process_id, thread_id)
+        }
+        None => (filter.pid.unwrap_or(0), 0),
+    };
+    let thread = MessageThread::spawn(
+        move || {
+            WIN_EVENT_STATE.set(Some(WinEventState {
+                hwnd: filter.hwnd,
+                sink,
+            }));
+            let mut hooks = Vec::new();
+            for (kind, event) in WIN_EVENTS {
+                if !filter.event_kinds.contains(kind) {
+                    continue;
+                }
+                let hook = unsafe {
+                    SetWinEventHook(
+                        event,
+                        event,
+                        None,
+                        Some(win_event_proc),
+                        process_id,
+                        thread_id,
+                        WINEVENT_OUTOFCONTEXT,
+                    )
+                };
+                if hook.is_invalid() {
+                    // 卸载已经安装的钩子前保存错误码。
+                    let code = windows::core::Error::from_win32().code();
+                    for hook in hooks {
+                        let _ = unsafe { UnhookWinEvent(hook) };
+                    }
+                    return Err((event, code));
+                }
+                hooks.push(hook);
+            }
+            if let RateLimit::Debounce(duration) | RateLimit::Throttle(duration) = filter.rate_limit
+            {
+                // 定时检查到期的事件，间隔越短发送越准时。
+                let elapse = (duration.as_millis() / 4).clamp(10, u32::MAX as u128) as u32;
+                unsafe { SetTimer(None, 0, elapse, Some(flush_proc)) };
+            }
+            Ok(hooks)
+        },
+        |_| {},
+        |hooks| {
+            for hook in hooks {
+                let _ = unsafe { UnhookWinEvent(hook) };
+            }
+            WIN_EVENT_STATE.take();
+        },
+    )
+    .map_err(
+        |(event, code)| WindowInspectorError::SetWinEventHookFailed {
+            event,
+            error: windows::core::Error::from_hresult(code),
+        },
+    )?;
+    Ok(HookGuard { thread })
+}
+
+/// [`on_window_created`]的钩子线程的状态。
+struct WindowCreatedState {
+    matcher: WindowMatcher,
+    action: Box<dyn FnMut(usize) + Send>,
+    /// 已经执行过操作的窗口，窗口关闭后移除。
+    handled: HashSet<usize>,
+}
+
+thread_local! {
+    /// 当前线程的[`on_window_created`]的状态。每个订阅有自己的线程。
+    static WINDOW_CREATED_STATE: RefCell<Option<WindowCreatedState>> = const { RefCell::new(None) };
+    /// 等待处理的(事件, 窗口)。`action`执行期间重入的事件先放在这里，`action`返回后依次处理。
+    static WINDOW_CREATED_PENDING: RefCell<VecDeque<(u32, usize)>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// 检查窗口的事件。
+const WINDOW_CREATED_EVENTS: [u32; 4] = [
+    EVENT_OBJECT_CREATE,
+    EVENT_OBJECT_SHOW,
+    EVENT_OBJECT_NAMECHANGE,
+    EVENT_OBJECT_DESTROY,
+];
+
+unsafe extern "system" fn window_created_proc(
+    _: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _: u32,
+    _: u32,
+) {
+    if hwnd.is_invalid() || id_object != OBJID_WINDOW.0 || id_child != CHILDID_SELF as i32 {
+        return;
+    }
+    WINDOW_CREATED_PENDING.with_borrow_mut(|pending| pending.push_back((event, hwnd.0 as usize)));
+    // 匹配和执行操作时会跨线程调用（读取标题、移动窗口等），等待期间钩子可能被重入。
+    // 先把状态取出，不在借用期间调用；重入时状态为空，事件留在队列中，由外层调用处理。
+    let Some(mut state) = WINDOW_CREATED_STATE.take() else {
+        return;
+    };
+    while let Some((event, hwnd)) = WINDOW_CREATED_PENDING.with_borrow_mut(VecDeque::pop_front) {
+        if event == EVENT_OBJECT_DESTROY {
+            state.handled.remove(&hwnd);
+        } else if !state.handled.contains(&hwnd)
+            && GetWindowLongW(HWND(hwnd as *mut c_void), GWL_STYLE) as u32 & WS_CHILD.0 == 0
+            && state.matcher.matches(hwnd)
+        {
+            state.handled.insert(hwnd);
+            (state.action)(hwnd);
+        }
+    }
+    WINDOW_CREATED_STATE.set(Some(state));
+}
+
+/// 新的顶层窗口出现并匹配`matcher`时，对它执行一次`action`（例如移动、置顶、修改样式）。返回的guard被drop时停止。
+/// `action`直接在钩子线程中同步执行，不经过通道转发，窗口以错误的位置、样式显示的时间尽可能短。
+/// 窗口创建、显示和标题变化时都会检查，因此创建时还没有标题的窗口也能在设置标题后匹配；每个窗口只执行一次。
+/// `action`执行期间到达的事件在它返回后依次处理，应尽快返回。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::events::on_window_created;
+/// use window_inspector::find::WindowMatcher;
+/// use window_inspector::position_size::move_window_to_xywh;
+///
+/// let _guard = on_window_created(WindowMatcher::new().class("Notepad"), |hwnd| {
+///     let _ = move_window_to_xywh(hwnd, 0, 0, 800, 600);
+/// })
+/// .unwrap();
+/// ```
+pub fn on_window_created(
+    matcher: WindowMatcher,
+    action: impl FnMut(usize) + Send + 'static,
+) -> Result<HookGuard> {
+    let thread = MessageThread::spawn(
+        move || {
+            WINDOW_CREATED_STATE.set(Some(WindowCreatedState {
+                matcher,
+                action: Box::new(action),
+                handled: HashSet::new(),
+            }));
+            let mut hooks = Vec::new();
+            for event in WINDOW_CREATED_EVENTS {
+                let hook = unsafe {
+                    SetWinEventHook(
+                        event,
+                        event,
+                        None,
+                        Some(window_created_proc),
+                        0,
+                        0,
+                        WINEVENT_OUTOFCONTEXT,
+                    )
+                };
+                if hook.is_invalid() {
+                    // 卸载已经安装的钩子前保存错误码。
+                    let code = windows::core::Error::from_win32().code();
+                    for hook in hooks {
+                        let _ = unsafe { UnhookWinEvent(hook) };
+                    }
+                    WINDOW_CREATED_STATE.take();
+                    return Err((event, code));
+                }
+                hooks.push(hook);
+            }
+            Ok(hooks)
+        },
+        |_| {},
+        |hooks| {
+            for hook in hooks {
+                let _ = unsafe { UnhookWinEvent(hook) };
+            }
+            WINDOW_CREATED_STATE.take();
+            WINDOW_CREATED_PENDING.take();
+        },
+    )
+    .map_err(
+        |(event, code)| WindowInspectorError::SetWinEventHookFailed {
+            event,
+            error: windows::core::Error::from_hresult(code),
+        },
+    )?;
+    Ok(HookGuard { thread })
+}
+
+#[test]
+fn test_win_events_filter() {
+    use crate::position_size::move_window_to_xywh;
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let other = TestWindowBuilder::new().build().unwrap();
+    let events = win_events(&EventFilter {
+        hwnd: Some(window.hwnd()),
+        event_kinds: EventKinds::LOCATION_CHANGE,
+        ..Default::default()
+    })
+    .unwrap();
+    move_window_to_xywh(other.hwnd(), 200, 200, 300, 200).unwrap();
+    move_window_to_xywh(window.hwnd(), 200, 200, 300, 200).unwrap();
+    let event = events.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(event.kind, EventKinds::LOCATION_CHANGE);
+    assert_eq!(event.hwnd, window.hwnd());
+}
+
+#[test]
+fn test_rate_limiter() {
+    let event = WinEvent {
+        kind: EventKinds::LOCATION_CHANGE,
+        hwnd: 1,
+        time: 0,
+    };
+    let mut debounce = RateLimiter {
+        rate_limit: RateLimit::Debounce(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    assert!(!debounce.push(&event));
+    assert!(!debounce.push(&WinEvent { time: 1, ..event }));
+    assert!(debounce.flush().is_empty());
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(debounce.flush(), vec![WinEvent { time: 1, ..event }]);
+
+    let mut throttle = RateLimiter {
+        rate_limit: RateLimit::Throttle(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    assert!(throttle.push(&event));
+    assert!(!throttle.push(&WinEvent { time: 1, ..event }));
+    assert!(!throttle.push(&WinEvent { time: 2, ..event }));
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(throttle.flush(), vec![WinEvent { time: 2, ..event }]);
+}
+
+#[test]
+fn test_on_window_created() {
+    use crate::test_support::TestWindowBuilder;
+
+    let (sender, receiver) = mpsc::channel();
+    let _guard = on_window_created(
+        WindowMatcher::new().title_contains("test_on_window_created"),
+        move |hwnd| {
+            let _ = sender.send(hwnd);
+        },
+    )
+    .unwrap();
+    let window = TestWindowBuilder::new()
+        .title("test_on_window_created")
+        .build()
+        .unwrap();
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_secs(1)).unwrap(),
+        window.hwnd()
+    );
+}
+
+#[test]
+fn test_window_created_reentrant() {
+    use crate::test_support::TestWindowBuilder;
+
+    let first = TestWindowBuilder::new()
+        .title("test_window_created_reentrant")
+        .build()
+        .unwrap();
+    let second = TestWindowBuilder::new()
+        .title("test_window_created_reentrant")
+        .build()
+        .unwrap();
+    let (first_hwnd, second_hwnd) = (first.hwnd(), second.hwnd());
+    let notify = |event: u32, hwnd: usize| unsafe {
+        window_created_proc(
+            HWINEVENTHOOK::default(),
+            event,
+            HWND(hwnd as *mut c_void),
+            OBJID_WINDOW.0,
+            CHILDID_SELF as i32,
+            0,
+            0,
+        )
+    };
+    let (sender, receiver) = mpsc::channel();
+    WINDOW_CREATED_STATE.set(Some(WindowCreatedState {
+        matcher: WindowMatcher::new().title_contains("test_window_created_reentrant"),
+        action: Box::new(move |hwnd| {
+            // 模拟action等待跨线程调用期间钩子被重入。
+            if hwnd == first_hwnd {
+                notify(EVENT_OBJECT_SHOW, second_hwnd);
+                notify(EVENT_OBJECT_DESTROY, first_hwnd);
+            }
+            let _ = sender.send(hwnd);
+        }),
+        handled: HashSet::new(),
+    }));
+    notify(EVENT_OBJECT_SHOW, first_hwnd);
+    assert_eq!(
+        receiver.try_iter().collect::<Vec<_>>(),
+        vec![first_hwnd, second_hwnd]
+    );
+    let state = WINDOW_CREATED_STATE.take().unwrap();
+    assert_eq!(state.handled, HashSet::from([second_hwnd]));
+}
+
+#[test]
+fn test_owned_win_events() {
+    use crate::position_size::move_window_to_xywh;
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let events = owned_win_events(&EventFilter {
+        hwnd: Some(window.hwnd()),
+        event_kinds: EventKinds::LOCATION_CHANGE,
+        ..Default::default()
+    })
+    .unwrap();
+    move_window_to_xywh(window.hwnd(), 200, 200, 300, 200).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+    let (hwnd, class) = (window.hwnd(), window.class().to_string());
+    drop(window);
+    // 窗口已经关闭，事件中的信息仍然可用。
+    let event = events.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(event.hwnd, hwnd);
+    assert_eq!(event.class, Some(class));
+    assert_eq!(event.rect, Some(Rect::new(200, 200, 300, 200)));
+}
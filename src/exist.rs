@@ -1,12 +1,226 @@
+use std::cell::Cell;
 use std::ffi::c_void;
+use std::time::Duration;
+use std::time::Instant;
 
 use windows::Win32::Foundation::HWND;
-use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+
+use crate::backend::with_current_backend;
+use crate::class_title::get_window_class_unchecked;
+use crate::class_title::get_window_title_unchecked;
+use crate::error::WindowInspectorError;
+use crate::mode::get_validation;
+use crate::mode::Validation;
+use crate::position_size::get_client_xywh_unchecked;
+use crate::position_size::get_window_xywh_exclude_shadow_unchecked;
+use crate::position_size::get_window_xywh_include_shadow_unchecked;
+use crate::result::Result;
+use crate::top_most::get_window_top_most_unchecked;
 
 /// 判断窗口是否存在。
 /// 是[`IsWindow`]的封装。
 ///
 /// [`IsWindow`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/WindowsAndMessaging/fn.IsWindow.html
 pub fn is_window_exist(hwnd: usize) -> bool {
-    unsafe { IsWindow(HWND(hwnd as *mut c_void)) }.as_bool()
+    with_current_backend(|backend| backend.is_window(hwnd))
+}
+
+/// 批量判断窗口是否存在，返回与`handles`一一对应的结果。
+/// 只获取一次当前线程的后端，比循环调用[`is_window_exist`]开销更小。
+pub fn are_windows_exist(handles: &[usize]) -> Vec<bool> {
+    with_current_backend(|backend| {
+        handles
+            .iter()
+            .map(|&hwnd| backend.is_window(hwnd))
+            .collect()
+    })
+}
+
+/// 从`handles`中筛选出仍然存在的窗口，保持原来的顺序。
+pub fn filter_existing(handles: &[usize]) -> Vec<usize> {
+    with_current_backend(|backend| {
+        handles
+            .iter()
+            .copied()
+            .filter(|&hwnd| backend.is_window(hwnd))
+            .collect()
+    })
+}
+
+/// `f`失败且窗口已经不存在时，将错误替换为[`WindowInspectorError::WindowNotExist`]。
+fn not_exist_on_error<T>(hwnd: usize, result: Result<T>) -> Result<T> {
+    result.map_err(|e| {
+        if is_window_exist(hwnd) {
+            e
+        } else {
+            WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            }
+        }
+    })
+}
+
+/// 按[`Validation`]模式检查窗口是否存在并调用`f`。
+pub(crate) fn with_validation<T>(hwnd: usize, f: impl FnOnce(usize) -> Result<T>) -> Result<T> {
+    match get_validation() {
+        Validation::Eager => {
+            if !is_window_exist(hwnd) {
+                return Err(WindowInspectorError::WindowNotExist {
+                    hwnd: HWND(hwnd as *mut c_void),
+                });
+            }
+            f(hwnd)
+        }
+        Validation::Lazy => not_exist_on_error(hwnd, f(hwnd)),
+    }
+}
+
+/// [`ValidHwnd`]重新检查窗口是否存在的策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Liveness {
+    /// 每次调用前都检查，与普通函数相同。
+    #[default]
+    Always,
+    /// 距离上次检查超过指定时间才再次检查。
+    Every(Duration),
+    /// 创建后不再检查。
+    Never,
+}
+
+/// 已确认存在的窗口句柄，按[`Liveness`]策略延迟重新检查是否存在。
+/// 普通函数每次调用都会先调用`IsWindow`，在频繁轮询同一个窗口时可以用它省去多余的检查。
+/// 跳过检查时，如果调用失败，会再检查一次窗口是否存在，窗口已关闭时仍然返回[`WindowInspectorError::WindowNotExist`]。
+///
+/// # 示例
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use window_inspector::exist::Liveness;
+/// use window_inspector::exist::ValidHwnd;
+/// use window_inspector::foreground::get_foreground_hwnd;
+///
+/// let hwnd = get_foreground_hwnd();
+/// let window = ValidHwnd::new(hwnd, Liveness::Every(Duration::from_secs(1))).unwrap();
+/// println!("{:?}", window.xywh_include_shadow());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ValidHwnd {
+    hwnd: usize,
+    liveness: Liveness,
+    last_checked: Cell<Instant>,
+}
+
+impl ValidHwnd {
+    /// 检查窗口是否存在并创建。
+    pub fn new(hwnd: usize, liveness: Liveness) -> Result<Self> {
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+        Ok(Self {
+            hwnd,
+            liveness,
+            last_checked: Cell::new(Instant::now()),
+        })
+    }
+
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 检查策略。
+    pub fn liveness(&self) -> Liveness {
+        self.liveness
+    }
+
+    /// 按策略检查窗口是否存在，需要检查时才调用`IsWindow`。
+    pub fn check(&self) -> Result<()> {
+        let due = match self.liveness {
+            Liveness::Always => true,
+            Liveness::Every(interval) => self.last_checked.get().elapsed() >= interval,
+            Liveness::Never => false,
+        };
+        if due {
+            if !is_window_exist(self.hwnd) {
+                return Err(self.not_exist());
+            }
+            self.last_checked.set(Instant::now());
+        }
+        Ok(())
+    }
+
+    fn not_exist(&self) -> WindowInspectorError {
+        WindowInspectorError::WindowNotExist {
+            hwnd: HWND(self.hwnd as *mut c_void),
+        }
+    }
+
+    /// 按策略检查后调用`f`。`f`失败且窗口已经不存在时返回[`WindowInspectorError::WindowNotExist`]。
+    fn call<T>(&self, f: impl FnOnce(usize) -> Result<T>) -> Result<T> {
+        self.check()?;
+        not_exist_on_error(self.hwnd, f(self.hwnd))
+    }
+
+    /// 见[`get_window_class`](crate::class_title::get_window_class)。
+    pub fn class(&self) -> Result<String> {
+        self.call(get_window_class_unchecked)
+    }
+
+    /// 见[`get_window_title`](crate::class_title::get_window_title)。
+    pub fn title(&self) -> Result<String> {
+        self.call(get_window_title_unchecked)
+    }
+
+    /// 见[`get_window_xywh_include_shadow`](crate::position_size::get_window_xywh_include_shadow)。
+    pub fn xywh_include_shadow(&self) -> Result<(i32, i32, u32, u32)> {
+        self.call(get_window_xywh_include_shadow_unchecked)
+    }
+
+    /// 见[`get_window_xywh_exclude_shadow`](crate::position_size::get_window_xywh_exclude_shadow)。
+    pub fn xywh_exclude_shadow(&self) -> Result<(i32, i32, u32, u32)> {
+        self.call(get_window_xywh_exclude_shadow_unchecked)
+    }
+
+    /// 见[`get_client_xywh`](crate::position_size::get_client_xywh)。
+    pub fn client_xywh(&self) -> Result<(i32, i32, u32, u32)> {
+        self.call(get_client_xywh_unchecked)
+    }
+
+    /// 见[`get_window_top_most`](crate::top_most::get_window_top_most)。
+    pub fn top_most(&self) -> Result<bool> {
+        self.call(get_window_top_most_unchecked)
+    }
+}
+
+#[test]
+fn test_filter_existing() {
+    use std::sync::Arc;
+
+    use crate::backend::with_backend;
+    use crate::backend::MockBackend;
+    use crate::backend::MockWindow;
+
+    let backend = Arc::new(MockBackend::new());
+    let a = backend.add_window(MockWindow::new("Mock", "a"));
+    let b = backend.add_window(MockWindow::new("Mock", "b"));
+    backend.remove_window(a);
+    with_backend(backend, || {
+        assert_eq!(filter_existing(&[a, b, 0]), vec![b]);
+        assert_eq!(are_windows_exist(&[a, b]), vec![false, true]);
+    });
+
+    let backend = Arc::new(MockBackend::new());
+    let hwnd = backend.add_window(MockWindow::new("Mock", "valid"));
+    with_backend(backend.clone(), || {
+        let window = ValidHwnd::new(hwnd, Liveness::Never).unwrap();
+        assert_eq!(window.title().unwrap(), "valid");
+        backend.remove_window(hwnd);
+        assert!(window.check().is_ok());
+        assert!(matches!(
+            window.title(),
+            Err(WindowInspectorError::WindowNotExist { .. })
+        ));
+    });
 }
@@ -0,0 +1,182 @@
+//! C ABI接口，供C、C++、C#等非Rust程序使用。需要`ffi`特性。
+//!
+//! 所有函数返回错误码（`WI_OK`表示成功），结果通过指针参数输出。
+//! 字符串使用以0结尾的UTF-16（与Win32的`wchar_t*`一致）。
+//! 失败后可以调用[`wi_last_error_message`]获取当前线程最后一次错误的描述。
+//! 头文件见`include/window_inspector.h`。
+//!
+//! 编译动态库（`window_inspector.dll`）：
+//! ```text
+//! cargo rustc --release --crate-type cdylib --features ffi
+//! ```
+
+use std::cell::RefCell;
+use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
+
+use widestring::U16CStr;
+
+use crate::error::ErrorKind;
+use crate::error::WindowInspectorError;
+use crate::find::get_hwnd_wide;
+use crate::position_size::get_window_xywh_exclude_shadow;
+use crate::position_size::get_window_xywh_include_shadow;
+use crate::result::Result;
+use crate::top_most::cancel_window_top_most;
+use crate::top_most::set_window_top_most;
+
+/// 成功。
+pub const WI_OK: i32 = 0;
+/// 窗口、进程等不存在。
+pub const WI_ERROR_NOT_FOUND: i32 = 1;
+/// 权限不足。
+pub const WI_ERROR_ACCESS_DENIED: i32 = 2;
+/// 句柄无效。
+pub const WI_ERROR_INVALID_HANDLE: i32 = 3;
+/// 等待超时。
+pub const WI_ERROR_TIMEOUT: i32 = 4;
+/// 参数无效，例如输出指针为空。
+pub const WI_ERROR_INVALID_ARGUMENT: i32 = 5;
+/// 库内部发生了panic。
+pub const WI_ERROR_PANIC: i32 = 6;
+/// 其他错误。
+pub const WI_ERROR_OTHER: i32 = 99;
+
+/// 窗口矩形。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WiRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+thread_local! {
+    /// 当前线程最后一次错误的描述。
+    static LAST_ERROR_MESSAGE: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn set_last_error_message(message: String) {
+    LAST_ERROR_MESSAGE.set(message);
+}
+
+/// 执行`f`，将结果转换为错误码，并记录错误描述。
+fn call(f: impl FnOnce() -> Result<()>) -> i32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => WI_OK,
+        Ok(Err(e)) => {
+            let code = match e.kind() {
+                ErrorKind::NotFound => WI_ERROR_NOT_FOUND,
+                ErrorKind::AccessDenied => WI_ERROR_ACCESS_DENIED,
+                ErrorKind::InvalidHandle => WI_ERROR_INVALID_HANDLE,
+                ErrorKind::Timeout => WI_ERROR_TIMEOUT,
+                ErrorKind::Other => match e {
+                    WindowInspectorError::WindowClassTitleBothEmpty
+                    | WindowInspectorError::StringContainsNul { .. } => WI_ERROR_INVALID_ARGUMENT,
+                    _ => WI_ERROR_OTHER,
+                },
+            };
+            set_last_error_message(e.to_string());
+            code
+        }
+        Err(_) => {
+            set_last_error_message("panic".to_string());
+            WI_ERROR_PANIC
+        }
+    }
+}
+
+fn invalid_argument(message: &str) -> i32 {
+    set_last_error_message(message.to_string());
+    WI_ERROR_INVALID_ARGUMENT
+}
+
+/// 空指针视为空字符串。
+unsafe fn wide_or_empty<'a>(s: *const u16) -> &'a U16CStr {
+    if s.is_null() {
+        Default::default()
+    } else {
+        U16CStr::from_ptr_str(s)
+    }
+}
+
+/// 按窗口类名和标题查找顶层窗口，见[`get_hwnd`](crate::find::get_hwnd)。
+/// `window_class`、`window_title`为空指针或空字符串时表示不限制，但不能同时不限制。
+///
+/// # Safety
+/// `window_class`、`window_title`必须是空指针或以0结尾的UTF-16字符串，`out_hwnd`必须可写。
+#[no_mangle]
+pub unsafe extern "C" fn wi_find_window(
+    window_class: *const u16,
+    window_title: *const u16,
+    out_hwnd: *mut usize,
+) -> i32 {
+    if out_hwnd.is_null() {
+        return invalid_argument("out_hwnd is null");
+    }
+    call(|| {
+        *out_hwnd = get_hwnd_wide(wide_or_empty(window_class), wide_or_empty(window_title))?;
+        Ok(())
+    })
+}
+
+/// 获取窗口矩形，相对于屏幕。`exclude_shadow`不为0时不包括阴影，
+/// 见[`get_window_xywh_include_shadow`]、[`get_window_xywh_exclude_shadow`]。
+///
+/// # Safety
+/// `out_rect`必须可写。
+#[no_mangle]
+pub unsafe extern "C" fn wi_get_rect(
+    hwnd: usize,
+    exclude_shadow: i32,
+    out_rect: *mut WiRect,
+) -> i32 {
+    if out_rect.is_null() {
+        return invalid_argument("out_rect is null");
+    }
+    call(|| {
+        let (x, y, width, height) = if exclude_shadow != 0 {
+            get_window_xywh_exclude_shadow(hwnd)?
+        } else {
+            get_window_xywh_include_shadow(hwnd)?
+        };
+        *out_rect = WiRect {
+            x,
+            y,
+            width,
+            height,
+        };
+        Ok(())
+    })
+}
+
+/// 设置（`top_most`不为0）或取消（`top_most`为0）窗口置顶。
+#[no_mangle]
+pub extern "C" fn wi_set_topmost(hwnd: usize, top_most: i32) -> i32 {
+    call(|| {
+        if top_most != 0 {
+            set_window_top_most(hwnd)
+        } else {
+            cancel_window_top_most(hwnd)
+        }
+    })
+}
+
+/// 将当前线程最后一次错误的描述以UTF-16写入`buffer`，最多写入`len - 1`个字符并以0结尾。
+/// 返回完整描述的长度（不含结尾的0），`buffer`太小时可以用返回值分配足够的空间后再次调用。
+///
+/// # Safety
+/// `buffer`必须是空指针或至少能写入`len`个`u16`。
+#[no_mangle]
+pub unsafe extern "C" fn wi_last_error_message(buffer: *mut u16, len: usize) -> usize {
+    LAST_ERROR_MESSAGE.with_borrow(|message| {
+        let wide: Vec<u16> = message.encode_utf16().collect();
+        if !buffer.is_null() && len > 0 {
+            let n = wide.len().min(len - 1);
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), buffer, n);
+            *buffer.add(n) = 0;
+        }
+        wide.len()
+    })
+}
@@ -1,15 +1,39 @@
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::c_void;
+use std::ffi::OsStr;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::num::NonZeroUsize;
-use std::ptr::null;
 use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
 
 use lazy_static::lazy_static;
 use lru::LruCache;
-use windows::core::PCWSTR;
-use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use widestring::U16CStr;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::CoCreateInstance;
+use windows::Win32::System::Com::CLSCTX_INPROC_SERVER;
+use windows::Win32::UI::Accessibility::CUIAutomation;
+use windows::Win32::UI::Accessibility::IUIAutomation;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
 
+use crate::backend::with_current_backend;
+use crate::class_title::get_window_class;
+use crate::class_title::get_window_title;
+use crate::com::ComGuard;
+use crate::enumerate::get_top_level_windows;
 use crate::error::WindowInspectorError;
 use crate::exist::is_window_exist;
+use crate::monitor::get_monitors;
+use crate::monitor::get_window_monitor;
+use crate::position_size::get_window_rect_exclude_shadow;
+use crate::process::get_process_path;
+use crate::process::get_process_start_time;
+use crate::process::get_window_process;
 use crate::result::Result;
+use crate::wide::to_wide;
+use crate::z_order::get_activation_order;
 
 /// 获取窗口句柄。
 /// 是[`FindWindowExW`]的封装。
@@ -19,63 +43,648 @@ use crate::result::Result;
 ///
 /// [`FindWindowW`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/WindowsAndMessaging/fn.FindWindowExW.html
 pub fn get_hwnd(window_class: &str, window_title: &str) -> Result<usize> {
+    get_hwnd_os(OsStr::new(window_class), OsStr::new(window_title))
+}
+
+/// 获取窗口句柄，参数为[`OsStr`]。
+/// 与[`get_hwnd`]相同，但可以传入非UTF-8的窗口类名和标题（例如含有未配对代理项的标题），不会被有损转换。
+pub fn get_hwnd_os(window_class: &OsStr, window_title: &OsStr) -> Result<usize> {
+    get_hwnd_wide(&to_wide(window_class)?, &to_wide(window_title)?)
+}
+
+/// 获取窗口句柄，参数为已经编码好的UTF-16字符串。
+/// 与[`get_hwnd`]相同，但不需要每次调用都重新编码，适合反复查找同一个窗口时使用。
+/// 空字符串表示不限制该参数。
+pub fn get_hwnd_wide(window_class: &U16CStr, window_title: &U16CStr) -> Result<usize> {
     if window_class.is_empty() && window_title.is_empty() {
         return Err(WindowInspectorError::WindowClassTitleBothEmpty);
     }
-    fn str_to_pcwstr(s: &str) -> PCWSTR {
-        if s.is_empty() {
-            PCWSTR(null())
-        } else {
-            let v: Vec<u16> = s.encode_utf16().chain(std::iter::once(0)).collect();
-            PCWSTR(v.as_ptr())
+    with_current_backend(|backend| backend.find_window(window_class, window_title))
+}
+
+/// [`get_hwnd_ref_cache`]使用的缓存的配置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// 最多缓存的窗口句柄数量，超出时淘汰最久未使用的。默认为1024。
+    pub capacity: NonZeroUsize,
+    /// 缓存的有效期，超过有效期的缓存项会被重新查找。默认为`None`，即永不过期。
+    pub ttl: Option<Duration>,
+    /// 是否禁用缓存。禁用时[`get_hwnd_ref_cache`]等同于[`get_hwnd`]。默认为`false`。
+    pub disabled: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: NonZeroUsize::new(1024).unwrap(),
+            ttl: None,
+            disabled: false,
         }
     }
-    match unsafe {
-        FindWindowExW(
-            None,
-            None,
-            str_to_pcwstr(window_class),
-            str_to_pcwstr(window_title),
-        )
-    } {
-        Ok(hwnd) => Ok(hwnd.0 as usize),
-        Err(e) => Err(WindowInspectorError::FindWindowExWFailed {
-            window_class: window_class.to_string(),
-            window_title: window_title.to_string(),
-            error_message: format!("{:?}", e),
-        }),
-    }
+}
+
+/// 修改全局缓存或其配置的测试需要互斥运行。
+#[cfg(test)]
+static CACHE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// 缓存分片数量。每个分片有自己的锁，不同分片上的查找互不阻塞。
+const CACHE_SHARD_COUNT: usize = 16;
+
+struct HwndCacheShard {
+    /// 每个分片保存一份配置，读取配置不需要额外加锁。
+    config: CacheConfig,
+    /// (窗口类名, 窗口标题) -> (窗口句柄, 缓存时刻)
+    entries: LruCache<(String, String), (usize, Instant)>,
+}
+
+/// 每个分片的容量，总容量平均分配到各分片。
+fn shard_capacity(capacity: NonZeroUsize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity.get().div_ceil(CACHE_SHARD_COUNT)).unwrap()
 }
 
 lazy_static! {
-    static ref HWND_CACHE: Mutex<LruCache<(String, String), usize>> =
-        Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap()));
+    static ref HWND_CACHE: Vec<Mutex<HwndCacheShard>> = (0..CACHE_SHARD_COUNT)
+        .map(|_| {
+            Mutex::new(HwndCacheShard {
+                config: CacheConfig::default(),
+                entries: LruCache::new(shard_capacity(CacheConfig::default().capacity)),
+            })
+        })
+        .collect();
+}
+
+/// 获取键所在的分片。
+fn cache_shard(key: &(String, String)) -> &'static Mutex<HwndCacheShard> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    &HWND_CACHE[hasher.finish() as usize % CACHE_SHARD_COUNT]
+}
+
+/// 设置[`get_hwnd_ref_cache`]使用的缓存。
+/// 容量变小时会淘汰多出的缓存项，禁用时会清空缓存。
+/// 缓存分为多个分片，容量平均分配到各分片，因此实际能缓存的数量可能略少于`capacity`。
+pub fn configure_cache(config: CacheConfig) {
+    for shard in HWND_CACHE.iter() {
+        let mut shard = shard.lock().unwrap();
+        shard.entries.resize(shard_capacity(config.capacity));
+        if config.disabled {
+            shard.entries.clear();
+        }
+        shard.config = config;
+    }
+    let mut cache = PROCESS_CLASS_CACHE.lock().unwrap();
+    cache.resize(config.capacity);
+    if config.disabled {
+        cache.clear();
+    }
+}
+
+/// 获取[`get_hwnd_ref_cache`]使用的缓存的配置。
+pub fn get_cache_config() -> CacheConfig {
+    HWND_CACHE[0].lock().unwrap().config
+}
+
+/// 使某个窗口类名和标题的缓存失效。
+pub fn invalidate_cache(window_class: &str, window_title: &str) {
+    let key = (window_class.to_string(), window_title.to_string());
+    cache_shard(&key).lock().unwrap().entries.pop(&key);
+}
+
+/// 清空[`get_hwnd_ref_cache`]和[`get_hwnd_by_process_and_class_cached`]使用的缓存。
+pub fn clear_cache() {
+    for shard in HWND_CACHE.iter() {
+        shard.lock().unwrap().entries.clear();
+    }
+    PROCESS_CLASS_CACHE.lock().unwrap().clear();
 }
 
 /// 获取窗口句柄，参考缓存。
+/// 缓存的容量、有效期等可以通过[`configure_cache`]设置。
+/// 缓存命中时只加锁一次，且不同的键大多落在不同的分片上，多线程轮询时很少互相阻塞。
 /// # 可能不符合预期的行为
 /// 调用该函数成功找到窗口一次之后，如果窗口标题改变，但是还使用原先的参数调用该函数，将依然返回原先的窗口句柄。
-/// 因为缓存中有窗口句柄且窗口仍然存在。
+/// 因为缓存中有窗口句柄且窗口仍然存在。可以设置缓存有效期，或调用[`invalidate_cache`]使缓存失效。
 pub fn get_hwnd_ref_cache(window_class: &str, window_title: &str) -> Result<usize> {
     if window_class.is_empty() && window_title.is_empty() {
         return Err(WindowInspectorError::WindowClassTitleBothEmpty);
     }
     let key = (window_class.to_string(), window_title.to_string());
-    let hwnd = HWND_CACHE.lock().unwrap().get(&key).copied();
-    if hwnd.is_some_and(is_window_exist) {
-        Ok(hwnd.unwrap())
-    } else {
-        HWND_CACHE.lock().unwrap().pop(&key);
-        let hwnd = get_hwnd(window_class, window_title)?;
-        HWND_CACHE.lock().unwrap().put(key, hwnd);
-        Ok(hwnd)
+    let shard = cache_shard(&key);
+    let (config, hwnd) = {
+        let mut shard = shard.lock().unwrap();
+        let config = shard.config;
+        let hwnd = shard
+            .entries
+            .get(&key)
+            .filter(|(_, cached_at)| config.ttl.is_none_or(|ttl| cached_at.elapsed() < ttl))
+            .map(|(hwnd, _)| *hwnd);
+        (config, hwnd)
+    };
+    if config.disabled {
+        return get_hwnd(window_class, window_title);
     }
+    if let Some(hwnd) = hwnd.filter(|&hwnd| is_window_exist(hwnd)) {
+        return Ok(hwnd);
+    }
+    // 查找窗口期间不持有锁，找到后直接覆盖失效的缓存项。
+    match get_hwnd(window_class, window_title) {
+        Ok(hwnd) => {
+            shard
+                .lock()
+                .unwrap()
+                .entries
+                .put(key, (hwnd, Instant::now()));
+            Ok(hwnd)
+        }
+        Err(e) => {
+            shard.lock().unwrap().entries.pop(&key);
+            Err(e)
+        }
+    }
+}
+
+/// 窗口匹配条件。
+/// 所有设置了的条件都满足时才算匹配，没有设置任何条件时匹配所有窗口。
+///
+/// # 示例
+/// ```
+/// use window_inspector::find::WindowMatcher;
+///
+/// let matcher = WindowMatcher::new()
+///     .class("Notepad")
+///     .title_contains("记事本");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowMatcher {
+    /// 窗口类名，完全匹配。
+    pub class: Option<String>,
+    /// 窗口标题，完全匹配。
+    pub title: Option<String>,
+    /// 窗口标题包含的子串。
+    pub title_contains: Option<String>,
+    /// 所属进程ID。
+    pub process_id: Option<u32>,
+    /// 所属进程的可执行文件名，例如`notepad.exe`，不区分大小写。
+    pub process_name: Option<String>,
+}
+
+impl WindowMatcher {
+    /// 创建不带任何条件的匹配器。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置窗口类名。
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// 设置窗口标题。
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// 设置窗口标题包含的子串。
+    pub fn title_contains(mut self, title_contains: impl Into<String>) -> Self {
+        self.title_contains = Some(title_contains.into());
+        self
+    }
+
+    /// 设置所属进程ID。
+    pub fn process_id(mut self, process_id: u32) -> Self {
+        self.process_id = Some(process_id);
+        self
+    }
+
+    /// 设置所属进程的可执行文件名。
+    pub fn process_name(mut self, process_name: impl Into<String>) -> Self {
+        self.process_name = Some(process_name.into());
+        self
+    }
+
+    /// 判断窗口是否匹配。窗口不存在或信息获取失败时视为不匹配。
+    pub fn matches(&self, hwnd: usize) -> bool {
+        if let Some(class) = &self.class {
+            if !get_window_class(hwnd).is_ok_and(|c| &c == class) {
+                return false;
+            }
+        }
+        if self.title.is_some() || self.title_contains.is_some() {
+            // 标题为空时get_window_title会返回错误。
+            let title = get_window_title(hwnd).unwrap_or_default();
+            if self.title.as_ref().is_some_and(|t| t != &title) {
+                return false;
+            }
+            if self
+                .title_contains
+                .as_ref()
+                .is_some_and(|t| !title.contains(t.as_str()))
+            {
+                return false;
+            }
+        }
+        if self.process_id.is_some() || self.process_name.is_some() {
+            let Ok(process_id) = get_window_process(hwnd as isize) else {
+                return false;
+            };
+            if self.process_id.is_some_and(|id| id != process_id) {
+                return false;
+            }
+            if let Some(process_name) = &self.process_name {
+                let Ok(path) = get_process_path(process_id) else {
+                    return false;
+                };
+                let file_name = std::path::Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                if !file_name.eq_ignore_ascii_case(process_name) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// 指定进程的方式。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProcessRef {
+    /// 进程ID。
+    Id(u32),
+    /// 可执行文件名，例如`notepad.exe`，不区分大小写。
+    ExeName(String),
+}
+
+impl From<u32> for ProcessRef {
+    fn from(process_id: u32) -> Self {
+        ProcessRef::Id(process_id)
+    }
+}
+
+impl From<&str> for ProcessRef {
+    fn from(exe_name: &str) -> Self {
+        ProcessRef::ExeName(exe_name.to_string())
+    }
+}
+
+impl From<String> for ProcessRef {
+    fn from(exe_name: String) -> Self {
+        ProcessRef::ExeName(exe_name)
+    }
+}
+
+/// (进程, 窗口类名) -> (窗口句柄, 窗口所属进程ID, 缓存时刻)
+type ProcessClassCache = LruCache<(ProcessRef, String), (usize, u32, Instant)>;
+
+lazy_static! {
+    static ref PROCESS_CLASS_CACHE: Mutex<ProcessClassCache> =
+        Mutex::new(LruCache::new(CacheConfig::default().capacity));
+}
+
+/// 按进程和窗口类名查找顶层窗口，可见窗口优先。
+pub fn get_hwnd_by_process_and_class(
+    process: impl Into<ProcessRef>,
+    window_class: &str,
+) -> Result<usize> {
+    let matcher = match process.into() {
+        ProcessRef::Id(process_id) => WindowMatcher::new().process_id(process_id),
+        ProcessRef::ExeName(exe_name) => WindowMatcher::new().process_name(exe_name),
+    }
+    .class(window_class);
+    let windows: Vec<usize> = get_top_level_windows()?
+        .into_iter()
+        .filter(|&hwnd| matcher.matches(hwnd))
+        .collect();
+    windows
+        .iter()
+        .find(|&&hwnd| unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool())
+        .or(windows.first())
+        .copied()
+        .ok_or(WindowInspectorError::NoMatchingWindow)
+}
+
+/// 按进程和窗口类名查找顶层窗口，参考缓存。
+/// 与[`get_hwnd_ref_cache`]不同，缓存的键不包含窗口标题，窗口标题改变后仍能命中缓存，也不会返回过时的窗口。
+/// 缓存命中时会检查窗口仍然存在、类名不变且仍属于原来的进程，防止返回被复用的窗口句柄。
+/// [`configure_cache`]设置的容量、有效期和是否禁用同样适用于这个缓存。
+pub fn get_hwnd_by_process_and_class_cached(
+    process: impl Into<ProcessRef>,
+    window_class: &str,
+) -> Result<usize> {
+    let process = process.into();
+    let config = get_cache_config();
+    if config.disabled {
+        return get_hwnd_by_process_and_class(process, window_class);
+    }
+    let key = (process, window_class.to_string());
+    let cached = PROCESS_CLASS_CACHE.lock().unwrap().get(&key).copied();
+    if let Some((hwnd, process_id, cached_at)) = cached {
+        if config.ttl.is_none_or(|ttl| cached_at.elapsed() < ttl)
+            && is_window_exist(hwnd)
+            && get_window_process(hwnd as isize).is_ok_and(|id| id == process_id)
+            && get_window_class(hwnd).is_ok_and(|class| class == window_class)
+        {
+            return Ok(hwnd);
+        }
+    }
+    let hwnd = match get_hwnd_by_process_and_class(key.0.clone(), window_class) {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            PROCESS_CLASS_CACHE.lock().unwrap().pop(&key);
+            return Err(e);
+        }
+    };
+    let process_id = get_window_process(hwnd as isize)?;
+    PROCESS_CLASS_CACHE
+        .lock()
+        .unwrap()
+        .put(key, (hwnd, process_id, Instant::now()));
+    Ok(hwnd)
+}
+
+fn create_automation() -> Result<IUIAutomation> {
+    unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER) }
+        .map_err(|e| WindowInspectorError::UIAutomationFailed { error: e })
+}
+
+/// 获取窗口的无障碍名称（UI Automation的`Name`属性）。
+/// 通常与窗口标题相同，但许多UWP、Electron、游戏启动器的窗口标题为空，只有无障碍名称。
+pub fn get_window_accessible_name(hwnd: usize) -> Result<String> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let _com = ComGuard::new()?;
+    let automation = create_automation()?;
+    unsafe { automation.ElementFromHandle(HWND(hwnd as *mut c_void)) }
+        .and_then(|element| unsafe { element.CurrentName() })
+        .map(|name| name.to_string())
+        .map_err(|e| WindowInspectorError::UIAutomationFailed { error: e })
+}
+
+/// 按无障碍名称（UI Automation的`Name`属性）查找可见的顶层窗口，完全匹配。
+/// 可以找到标题为空、[`get_hwnd`]无法查找的窗口。需要逐个查询窗口，比[`get_hwnd`]慢得多。
+pub fn get_hwnd_by_accessible_name(name: &str) -> Result<usize> {
+    let _com = ComGuard::new()?;
+    let automation = create_automation()?;
+    get_top_level_windows()?
+        .into_iter()
+        .filter(|&hwnd| unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool())
+        .find(|&hwnd| {
+            unsafe { automation.ElementFromHandle(HWND(hwnd as *mut c_void)) }
+                .and_then(|element| unsafe { element.CurrentName() })
+                .is_ok_and(|element_name| element_name == name)
+        })
+        .ok_or(WindowInspectorError::NoMatchingWindow)
+}
+
+/// 多个窗口匹配时的选择方式，见[`disambiguate`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preference {
+    /// 最近激活的窗口，见[`crate::z_order::get_activation_order`]。
+    /// 没有用[`crate::z_order::start_activation_tracking`]记录激活顺序时选择Z序最靠上的窗口。
+    MostRecentlyActive,
+    /// 面积最大的窗口（不包括阴影）。
+    LargestWindow,
+    /// 所属进程最早启动的窗口，通常是程序的第一个实例。
+    OldestProcess,
+    /// 在第n个显示器（[`crate::monitor::get_monitors`]返回的列表的下标，从0开始）上的窗口，有多个时选择Z序最靠上的窗口。
+    OnMonitor(usize),
+}
+
+/// 从多个匹配的窗口中按`preference`选择一个。多实例程序的窗口往往无法用类名、标题区分，直接取第一个时结果不确定。
+/// 已经关闭的窗口会被忽略；没有可选的窗口时返回[`WindowInspectorError::NoMatchingWindow`]。
+/// 除[`Preference::MostRecentlyActive`]外，多个窗口同样合适时选择Z序最靠上的窗口。
+pub fn disambiguate(matches: Vec<usize>, preference: Preference) -> Result<usize> {
+    // 按最近激活或Z序排列，不可见的窗口和不是顶层窗口的放在最后。
+    let order = match preference {
+        Preference::MostRecentlyActive => get_activation_order()?,
+        _ => get_top_level_windows()?,
+    };
+    let mut candidates: Vec<usize> = order
+        .into_iter()
+        .filter(|hwnd| matches.contains(hwnd))
+        .collect();
+    let others: Vec<usize> = matches
+        .iter()
+        .copied()
+        .filter(|hwnd| !candidates.contains(hwnd) && is_window_exist(*hwnd))
+        .collect();
+    candidates.extend(others);
+    let chosen =
+        match preference {
+            Preference::MostRecentlyActive => candidates.first().copied(),
+            // max_by_key在相等时返回最后一个，反向遍历使相等时选择靠前的窗口。
+            Preference::LargestWindow => candidates.iter().rev().copied().max_by_key(|&hwnd| {
+                get_window_rect_exclude_shadow(hwnd).map_or(0, |rect| rect.area())
+            }),
+            Preference::OldestProcess => candidates
+                .iter()
+                .copied()
+                .filter_map(|hwnd| {
+                    let process_id = get_window_process(hwnd as isize).ok()?;
+                    Some((get_process_start_time(process_id).ok()?, hwnd))
+                })
+                .min_by_key(|&(start_time, _)| start_time)
+                .map(|(_, hwnd)| hwnd),
+            Preference::OnMonitor(index) => {
+                let Some(&monitor) = get_monitors()?.get(index) else {
+                    return Err(WindowInspectorError::NoMatchingWindow);
+                };
+                candidates
+                    .iter()
+                    .copied()
+                    .find(|&hwnd| get_window_monitor(hwnd).is_ok_and(|m| m == monitor))
+            }
+        };
+    chosen.ok_or(WindowInspectorError::NoMatchingWindow)
 }
 
 #[test]
 fn test_get_hwnd() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().title("无标题").build().unwrap();
     for _ in 0..1000 {
-        let hwnd = get_hwnd("", "无标题").unwrap();
+        let hwnd = get_hwnd(window.class(), "无标题").unwrap();
+        assert_eq!(hwnd, window.hwnd());
         assert!(is_window_exist(hwnd));
     }
 }
+
+#[test]
+fn test_disambiguate_on_monitor() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let monitors = get_monitors().unwrap();
+    let index = monitors
+        .iter()
+        .position(|&monitor| monitor == get_window_monitor(window.hwnd()).unwrap())
+        .unwrap();
+    assert_eq!(
+        disambiguate(vec![window.hwnd()], Preference::OnMonitor(index)).unwrap(),
+        window.hwnd()
+    );
+    assert!(matches!(
+        disambiguate(vec![window.hwnd()], Preference::OnMonitor(monitors.len())),
+        Err(WindowInspectorError::NoMatchingWindow)
+    ));
+}
+
+#[test]
+fn test_get_hwnd_by_process_and_class_cached() {
+    use crate::test_support::TestWindowBuilder;
+
+    let _lock = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let process_id = std::process::id();
+    let window = TestWindowBuilder::new().build().unwrap();
+    let class = window.class().to_string();
+    let key = (ProcessRef::Id(process_id), class.clone());
+    // 未命中时查找并缓存。
+    assert_eq!(
+        get_hwnd_by_process_and_class_cached(process_id, &class).unwrap(),
+        window.hwnd()
+    );
+    let cached = *PROCESS_CLASS_CACHE.lock().unwrap().peek(&key).unwrap();
+    assert_eq!((cached.0, cached.1), (window.hwnd(), process_id));
+    // 命中时不重新查找，缓存项不变。
+    assert_eq!(
+        get_hwnd_by_process_and_class_cached(process_id, &class).unwrap(),
+        window.hwnd()
+    );
+    assert_eq!(
+        PROCESS_CLASS_CACHE.lock().unwrap().peek(&key),
+        Some(&cached)
+    );
+    // 缓存的句柄已经属于另一个类名的窗口时重新查找。
+    let other = TestWindowBuilder::new().build().unwrap();
+    PROCESS_CLASS_CACHE
+        .lock()
+        .unwrap()
+        .put(key.clone(), (other.hwnd(), process_id, Instant::now()));
+    assert_eq!(
+        get_hwnd_by_process_and_class_cached(process_id, &class).unwrap(),
+        window.hwnd()
+    );
+    // 窗口关闭后找不到，缓存项被移除。
+    drop(window);
+    assert!(matches!(
+        get_hwnd_by_process_and_class_cached(process_id, &class),
+        Err(WindowInspectorError::NoMatchingWindow)
+    ));
+    assert!(!PROCESS_CLASS_CACHE.lock().unwrap().contains(&key));
+}
+
+#[test]
+fn test_cache_ttl() {
+    use crate::backend::with_backend;
+    use crate::backend::MockBackend;
+    use crate::backend::MockWindow;
+
+    let _lock = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let backend = std::sync::Arc::new(MockBackend::new());
+    backend.add_window(MockWindow::new("test_cache_ttl", ""));
+    configure_cache(CacheConfig {
+        ttl: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+    with_backend(backend.clone(), || {
+        get_hwnd_ref_cache("test_cache_ttl", "").unwrap();
+        get_hwnd_ref_cache("test_cache_ttl", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 1);
+        std::thread::sleep(Duration::from_millis(100));
+        get_hwnd_ref_cache("test_cache_ttl", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 2);
+    });
+    configure_cache(CacheConfig::default());
+}
+
+#[test]
+fn test_cache_invalidate_and_clear() {
+    use crate::backend::with_backend;
+    use crate::backend::MockBackend;
+    use crate::backend::MockWindow;
+
+    let _lock = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let backend = std::sync::Arc::new(MockBackend::new());
+    backend.add_window(MockWindow::new("test_cache_invalidate", ""));
+    with_backend(backend.clone(), || {
+        get_hwnd_ref_cache("test_cache_invalidate", "").unwrap();
+        get_hwnd_ref_cache("test_cache_invalidate", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 1);
+        invalidate_cache("test_cache_invalidate", "");
+        get_hwnd_ref_cache("test_cache_invalidate", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 2);
+        clear_cache();
+        get_hwnd_ref_cache("test_cache_invalidate", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 3);
+    });
+}
+
+#[test]
+fn test_cache_disabled() {
+    use crate::backend::with_backend;
+    use crate::backend::MockBackend;
+    use crate::backend::MockWindow;
+
+    let _lock = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let backend = std::sync::Arc::new(MockBackend::new());
+    backend.add_window(MockWindow::new("test_cache_disabled", ""));
+    configure_cache(CacheConfig {
+        disabled: true,
+        ..Default::default()
+    });
+    with_backend(backend.clone(), || {
+        get_hwnd_ref_cache("test_cache_disabled", "").unwrap();
+        get_hwnd_ref_cache("test_cache_disabled", "").unwrap();
+        assert_eq!(backend.find_window_calls(), 2);
+    });
+    let key = ("test_cache_disabled".to_string(), String::new());
+    assert!(!cache_shard(&key).lock().unwrap().entries.contains(&key));
+    configure_cache(CacheConfig::default());
+}
+
+#[test]
+fn test_cache_shards() {
+    use crate::backend::with_backend;
+    use crate::backend::MockBackend;
+    use crate::backend::MockWindow;
+
+    let _lock = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let backend = std::sync::Arc::new(MockBackend::new());
+    // 每个分片最多缓存2个。
+    configure_cache(CacheConfig {
+        capacity: NonZeroUsize::new(CACHE_SHARD_COUNT * 2).unwrap(),
+        ..Default::default()
+    });
+    clear_cache();
+    with_backend(backend.clone(), || {
+        for i in 0..CACHE_SHARD_COUNT * 8 {
+            let title = format!("test_cache_shards_{}", i);
+            backend.add_window(MockWindow::new("", title.as_str()));
+            get_hwnd_ref_cache("", &title).unwrap();
+        }
+    });
+    let lens: Vec<usize> = HWND_CACHE
+        .iter()
+        .map(|shard| shard.lock().unwrap().entries.len())
+        .collect();
+    assert!(lens.iter().filter(|&&len| len > 0).count() > 1);
+    assert!(lens.iter().all(|&len| len <= 2));
+    configure_cache(CacheConfig::default());
+}
@@ -0,0 +1,66 @@
+//! 键盘焦点。
+
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Input::KeyboardAndMouse::GetFocus;
+use windows::Win32::UI::Input::KeyboardAndMouse::SetFocus;
+use windows::Win32::UI::WindowsAndMessaging::IsChild;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::foreground::force_foreground;
+use crate::foreground::with_attached_input;
+use crate::foreground::Strategy;
+use crate::mode::dry_run;
+use crate::result::Result;
+
+/// 将键盘焦点设置到其他程序窗口中的控件上。
+/// 先将顶层窗口`top_hwnd`设为前台窗口，再附加到控件所在线程的输入队列调用`SetFocus`，最后确认焦点确实在控件上。
+/// 适合在发送按键前确定地放置焦点，例如自动填写表单。
+/// `control_hwnd`必须是`top_hwnd`的子孙窗口，或者与`top_hwnd`相同。
+pub fn set_focus_in_window(top_hwnd: usize, control_hwnd: usize) -> Result<()> {
+    for hwnd in [top_hwnd, control_hwnd] {
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+    }
+    if control_hwnd != top_hwnd
+        && !unsafe {
+            IsChild(
+                HWND(top_hwnd as *mut c_void),
+                HWND(control_hwnd as *mut c_void),
+            )
+        }
+        .as_bool()
+    {
+        return Err(WindowInspectorError::NotChildWindow {
+            parent: HWND(top_hwnd as *mut c_void),
+            child: HWND(control_hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "SetFocus {:#X} in {:#X}",
+        control_hwnd, top_hwnd
+    )) {
+        return Ok(());
+    }
+    force_foreground(top_hwnd, Strategy::Auto)?;
+    with_attached_input(control_hwnd, || {
+        let control = HWND(control_hwnd as *mut c_void);
+        unsafe { SetFocus(control) }.map_err(|e| WindowInspectorError::SetFocusFailed {
+            hwnd: control,
+            error: e,
+        })?;
+        // GetFocus只返回与当前线程输入队列相关的焦点，需要在附加期间检查。
+        if unsafe { GetFocus() } != control {
+            return Err(WindowInspectorError::SetFocusFailed {
+                hwnd: control,
+                error: windows::core::Error::from_win32(),
+            });
+        }
+        Ok(())
+    })?
+}
@@ -1,16 +1,58 @@
 use std::ffi::c_void;
+use std::mem::size_of;
 
 use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Threading::AttachThreadInput;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::SendInput;
+use windows::Win32::UI::Input::KeyboardAndMouse::INPUT;
+use windows::Win32::UI::Input::KeyboardAndMouse::INPUT_0;
+use windows::Win32::UI::Input::KeyboardAndMouse::INPUT_KEYBOARD;
+use windows::Win32::UI::Input::KeyboardAndMouse::KEYBDINPUT;
+use windows::Win32::UI::Input::KeyboardAndMouse::KEYBD_EVENT_FLAGS;
+use windows::Win32::UI::Input::KeyboardAndMouse::KEYEVENTF_KEYUP;
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_MENU;
+use windows::Win32::UI::WindowsAndMessaging::BringWindowToTop;
+use windows::Win32::UI::WindowsAndMessaging::GetAncestor;
 use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetLastActivePopup;
+use windows::Win32::UI::WindowsAndMessaging::GetWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
 use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::SwitchToThisWindow;
+use windows::Win32::UI::WindowsAndMessaging::GA_ROOTOWNER;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GW_HWNDPREV;
+use windows::Win32::UI::WindowsAndMessaging::HWND_NOTOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOP;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::SET_WINDOW_POS_FLAGS;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
 
+use crate::backend::with_current_backend;
+use crate::class_title::get_window_class;
+use crate::class_title::get_window_title;
+use crate::enumerate::get_top_level_windows;
 use crate::error::WindowInspectorError;
 use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::process::get_process_path;
+use crate::process::get_window_process;
 use crate::result::Result;
 
 /// 获取前台窗口句柄。
 pub fn get_foreground_hwnd() -> usize {
-    unsafe { GetForegroundWindow() }.0 as usize
+    with_current_backend(|backend| backend.foreground_window())
 }
 
 /// 判断窗口是否处于前台。
@@ -25,9 +67,333 @@ pub fn set_foreground_window(hwnd: usize) -> Result<()> {
             hwnd: HWND(hwnd as *mut c_void),
         });
     }
-    if !unsafe { SetForegroundWindow(HWND(hwnd as *mut c_void)) }.as_bool() {
+    if dry_run(format_args!("SetForegroundWindow {:#X}", hwnd)) {
+        return Ok(());
+    }
+    with_current_backend(|backend| backend.set_foreground_window(hwnd))
+}
+
+/// 设置前台窗口的方法。
+/// `SetForegroundWindow`受前台锁定规则限制，经常失败，各方法分别绕过不同的限制。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// 直接调用`SetForegroundWindow`。
+    Direct,
+    /// 将当前线程的输入队列附加到前台窗口所在线程，再调用`SetForegroundWindow`。
+    AttachThreadInput,
+    /// 模拟按下并松开ALT键，使当前进程获得设置前台窗口的权限，再调用`SetForegroundWindow`。
+    AltKey,
+    /// 先最小化再还原窗口，再调用`SetForegroundWindow`。会有明显的动画。
+    MinimizeRestore,
+    /// 依次尝试[`Strategy::Direct`]、[`Strategy::AttachThreadInput`]、[`Strategy::AltKey`]、
+    /// [`Strategy::MinimizeRestore`]，直到成功。
+    #[default]
+    Auto,
+}
+
+impl Strategy {
+    /// 使用该方法尝试设置前台窗口，返回窗口是否成为前台窗口。
+    fn apply(self, hwnd: HWND) -> bool {
+        match self {
+            Strategy::Direct => {
+                let _ = unsafe { SetForegroundWindow(hwnd) };
+            }
+            Strategy::AttachThreadInput => {
+                let foreground_thread_id =
+                    unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
+                let current_thread_id = unsafe { GetCurrentThreadId() };
+                let attached = foreground_thread_id != 0
+                    && foreground_thread_id != current_thread_id
+                    && unsafe { AttachThreadInput(current_thread_id, foreground_thread_id, true) }
+                        .as_bool();
+                let _ = unsafe { BringWindowToTop(hwnd) };
+                let _ = unsafe { SetForegroundWindow(hwnd) };
+                if attached {
+                    let _ = unsafe {
+                        AttachThreadInput(current_thread_id, foreground_thread_id, false)
+                    };
+                }
+            }
+            Strategy::AltKey => {
+                let key_input = |flags: KEYBD_EVENT_FLAGS| INPUT {
+                    r#type: INPUT_KEYBOARD,
+                    Anonymous: INPUT_0 {
+                        ki: KEYBDINPUT {
+                            wVk: VK_MENU,
+                            dwFlags: flags,
+                            ..Default::default()
+                        },
+                    },
+                };
+                let inputs = [
+                    key_input(KEYBD_EVENT_FLAGS::default()),
+                    key_input(KEYEVENTF_KEYUP),
+                ];
+                unsafe { SendInput(&inputs, size_of::<INPUT>() as i32) };
+                let _ = unsafe { SetForegroundWindow(hwnd) };
+            }
+            Strategy::MinimizeRestore => {
+                let _ = unsafe { ShowWindow(hwnd, SW_MINIMIZE) };
+                let _ = unsafe { ShowWindow(hwnd, SW_RESTORE) };
+                let _ = unsafe { SetForegroundWindow(hwnd) };
+            }
+            Strategy::Auto => {
+                return [
+                    Strategy::Direct,
+                    Strategy::AttachThreadInput,
+                    Strategy::AltKey,
+                    Strategy::MinimizeRestore,
+                ]
+                .into_iter()
+                .any(|strategy| strategy.apply(hwnd));
+            }
+        }
+        hwnd == unsafe { GetForegroundWindow() }
+    }
+}
+
+/// 使用指定方法设置前台窗口。最小化的窗口会先被还原。
+/// 与[`set_foreground_window`]不同，这个函数会检查窗口是否真的成为了前台窗口。
+pub fn force_foreground(hwnd: usize, strategy: Strategy) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if is_foreground(hwnd) {
+        return Ok(());
+    }
+    if dry_run(format_args!(
+        "force foreground {:#X} with {:?}",
+        hwnd, strategy
+    )) {
+        return Ok(());
+    }
+    if unsafe { IsIconic(HWND(hwnd as *mut c_void)) }.as_bool() {
+        let _ = unsafe { ShowWindow(HWND(hwnd as *mut c_void), SW_RESTORE) };
+    }
+    if !strategy.apply(HWND(hwnd as *mut c_void)) {
         return Err(WindowInspectorError::SetForegroundWindowFailed);
     }
+    Ok(())
+}
+
+/// 判断Z序中是否有可见的非置顶窗口位于该窗口之上。
+fn is_covered_by_non_top_most_window(hwnd: HWND) -> bool {
+    let mut current = hwnd;
+    while let Ok(prev) = unsafe { GetWindow(current, GW_HWNDPREV) } {
+        if unsafe { IsWindowVisible(prev) }.as_bool()
+            && unsafe { GetWindowLongW(prev, GWL_EXSTYLE) } as u32 & WS_EX_TOPMOST.0 == 0
+        {
+            return true;
+        }
+        current = prev;
+    }
+    false
+}
+
+/// 将窗口移到Z序顶部，但不激活窗口，不改变键盘焦点。
+/// 先使用`SetWindowPos(HWND_TOP, SWP_NOACTIVATE)`；如果窗口仍被其他非置顶窗口遮挡，则先置顶再取消置顶。
+/// 本来就置顶的窗口只会被移到置顶窗口的顶部，不会被取消置顶。
+pub fn bring_to_front_no_activate(hwnd: usize) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "bring {:#X} to front without activating",
+        hwnd
+    )) {
+        return Ok(());
+    }
+    let is_top_most = unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } as u32
+        & WS_EX_TOPMOST.0
+        != 0;
+    let flags: SET_WINDOW_POS_FLAGS = SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE;
+    let set_window_pos = |insert_after: HWND| {
+        unsafe { SetWindowPos(HWND(hwnd as *mut c_void), insert_after, 0, 0, 0, 0, flags) }.map_err(
+            |e| WindowInspectorError::SetWindowPosFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: e,
+            },
+        )
+    };
+    set_window_pos(HWND_TOP)?;
+    if !is_top_most && is_covered_by_non_top_most_window(HWND(hwnd as *mut c_void)) {
+        set_window_pos(HWND_TOPMOST)?;
+        set_window_pos(HWND_NOTOPMOST)?;
+    }
+    Ok(())
+}
+
+/// 像Alt+Tab一样切换到窗口。
+/// 是[`SwitchToThisWindow`]的封装，在`SetForegroundWindow`被拒绝的一些情况下仍然有效。
+/// `SwitchToThisWindow`没有返回值，窗口最终是否成为前台窗口需要调用[`is_foreground`]确认。
+/// # 参数
+/// - `restore_if_minimized`：窗口最小化时是否还原。
+///
+/// [`SwitchToThisWindow`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/WindowsAndMessaging/fn.SwitchToThisWindow.html
+pub fn switch_to_window(hwnd: usize, restore_if_minimized: bool) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "SwitchToThisWindow {:#X}, restore if minimized: {}",
+        hwnd, restore_if_minimized
+    )) {
+        return Ok(());
+    }
+    unsafe { SwitchToThisWindow(HWND(hwnd as *mut c_void), restore_if_minimized) };
+    Ok(())
+}
+
+/// 前台窗口信息。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForegroundInfo {
+    /// 窗口句柄。
+    pub hwnd: usize,
+    /// 窗口标题，没有标题时为空字符串。
+    pub title: String,
+    /// 窗口类名。
+    pub class: String,
+    /// 所属进程ID。
+    pub process_id: u32,
+    /// 所属进程路径。没有权限访问进程时（例如以管理员身份运行的程序）为`None`。
+    pub exe_path: Option<String>,
+}
+
+/// 一次获取前台窗口的句柄、标题、类名、进程ID和进程路径。
+/// 获取完成后会再次确认前台窗口没有变化，如果变化了则重新获取，保证各字段属于同一个窗口。
+pub fn get_foreground_info() -> Result<ForegroundInfo> {
+    loop {
+        let hwnd = get_foreground_hwnd();
+        if hwnd == 0 {
+            return Err(WindowInspectorError::NoForegroundWindow);
+        }
+        let class = get_window_class(hwnd)?;
+        let title = get_window_title(hwnd).unwrap_or_default();
+        let process_id = get_window_process(hwnd as isize)?;
+        let exe_path = get_process_path(process_id).ok();
+        if get_foreground_hwnd() == hwnd {
+            return Ok(ForegroundInfo {
+                hwnd,
+                title,
+                class,
+                process_id,
+                exe_path,
+            });
+        }
+    }
+}
+
+/// 在drop时解除[`with_attached_input`]附加的输入队列。
+struct AttachedInput {
+    current_thread_id: u32,
+    target_thread_id: u32,
+}
 
+impl Drop for AttachedInput {
+    fn drop(&mut self) {
+        let _ = unsafe { AttachThreadInput(self.current_thread_id, self.target_thread_id, false) };
+    }
+}
+
+/// 将当前线程的输入队列附加到窗口所在线程（`AttachThreadInput`），执行`f`后解除，`f`发生panic时也会解除。
+/// 附加期间两个线程共享键盘焦点、激活状态和光标（caret）状态，`SetFocus`、`GetFocus`、`SetForegroundWindow`等
+/// 只对本线程有效的函数也能作用于目标线程的窗口。窗口属于当前线程时直接执行`f`。
+pub fn with_attached_input<T>(hwnd: usize, f: impl FnOnce() -> T) -> Result<T> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let target_thread_id = unsafe { GetWindowThreadProcessId(HWND(hwnd as *mut c_void), None) };
+    if target_thread_id == 0 {
+        return Err(WindowInspectorError::GetWindowThreadProcessIdFailed {
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    let current_thread_id = unsafe { GetCurrentThreadId() };
+    if target_thread_id == current_thread_id {
+        return Ok(f());
+    }
+    if !unsafe { AttachThreadInput(current_thread_id, target_thread_id, true) }.as_bool() {
+        return Err(WindowInspectorError::AttachThreadInputFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    let _attached = AttachedInput {
+        current_thread_id,
+        target_thread_id,
+    };
+    Ok(f())
+}
+
+fn root_owner(hwnd: usize) -> usize {
+    let root = unsafe { GetAncestor(HWND(hwnd as *mut c_void), GA_ROOTOWNER) };
+    if root.is_invalid() {
+        hwnd
+    } else {
+        root.0 as usize
+    }
+}
+
+/// 像点击任务栏按钮一样激活窗口所在的整组窗口：根所有者窗口，以及它直接或间接拥有的所有可见窗口（浮动面板、工具窗口、对话框等）。
+/// 激活根所有者最后激活的弹出窗口（例如打开着的对话框），并把其他被拥有的窗口一起移到前面，保持它们之间原来的Z序。
+/// `hwnd`可以是组中的任意一个窗口。
+pub fn activate_window_group(hwnd: usize) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let root = root_owner(hwnd);
+    // 按Z序从上到下排列。
+    let owned: Vec<usize> = get_top_level_windows()?
+        .into_iter()
+        .filter(|&window| {
+            window != root
+                && unsafe { IsWindowVisible(HWND(window as *mut c_void)) }.as_bool()
+                && root_owner(window) == root
+        })
+        .collect();
+    if dry_run(format_args!(
+        "activate window group of {:#X}, owned windows: {:X?}",
+        root, owned
+    )) {
+        return Ok(());
+    }
+    if unsafe { IsIconic(HWND(root as *mut c_void)) }.as_bool() {
+        let _ = unsafe { ShowWindow(HWND(root as *mut c_void), SW_RESTORE) };
+    }
+    let popup = unsafe { GetLastActivePopup(HWND(root as *mut c_void)) };
+    let target = if popup.is_invalid() || !unsafe { IsWindowVisible(popup) }.as_bool() {
+        root
+    } else {
+        popup.0 as usize
+    };
+    force_foreground(target, Strategy::Auto)?;
+    // 从下往上逐个移到顶部，保持原来的相对顺序，最后把激活的窗口放在最上面。
+    for &window in owned
+        .iter()
+        .rev()
+        .chain(Some(&target).filter(|&&t| t != root))
+    {
+        let _ = unsafe {
+            SetWindowPos(
+                HWND(window as *mut c_void),
+                HWND_TOP,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        };
+    }
     Ok(())
 }
@@ -0,0 +1,266 @@
+//! 坐标、尺寸、矩形类型。比`(x, y, width, height)`元组更不容易混淆各个分量。
+
+use windows::Win32::Foundation::POINT;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::SIZE;
+
+/// 点。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl From<POINT> for Point {
+    fn from(point: POINT) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+impl From<Point> for POINT {
+    fn from(point: Point) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+/// 尺寸。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Size {
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Size {
+    pub fn new(w: u32, h: u32) -> Self {
+        Self { w, h }
+    }
+}
+
+impl From<(u32, u32)> for Size {
+    fn from((w, h): (u32, u32)) -> Self {
+        Self { w, h }
+    }
+}
+
+impl From<Size> for (u32, u32) {
+    fn from(size: Size) -> Self {
+        (size.w, size.h)
+    }
+}
+
+impl From<SIZE> for Size {
+    fn from(size: SIZE) -> Self {
+        Self {
+            w: size.cx.max(0) as u32,
+            h: size.cy.max(0) as u32,
+        }
+    }
+}
+
+impl From<Size> for SIZE {
+    fn from(size: Size) -> Self {
+        Self {
+            cx: size.w as i32,
+            cy: size.h as i32,
+        }
+    }
+}
+
+/// 矩形，左上角为`(x, y)`，宽`w`，高`h`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, w: u32, h: u32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// 由左上角和尺寸构造。
+    pub fn from_point_size(point: Point, size: Size) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+            w: size.w,
+            h: size.h,
+        }
+    }
+
+    /// 左上角。
+    pub fn point(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// 尺寸。
+    pub fn size(&self) -> Size {
+        Size::new(self.w, self.h)
+    }
+
+    /// 右边界（不包含）。
+    pub fn right(&self) -> i32 {
+        self.x + self.w as i32
+    }
+
+    /// 下边界（不包含）。
+    pub fn bottom(&self) -> i32 {
+        self.y + self.h as i32
+    }
+
+    /// 中心点。
+    pub fn center(&self) -> Point {
+        Point::new(self.x + (self.w / 2) as i32, self.y + (self.h / 2) as i32)
+    }
+
+    /// 面积。
+    pub fn area(&self) -> u64 {
+        self.w as u64 * self.h as u64
+    }
+
+    /// 宽或高为0。
+    pub fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    /// 是否包含点。左、上边界包含在内，右、下边界不包含，与`PtInRect`一致。
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.right() && point.y >= self.y && point.y < self.bottom()
+    }
+
+    /// 是否完全包含另一个矩形。
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// 交集，不相交时返回`None`。
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (left < right && top < bottom)
+            .then(|| Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// 平移。
+    pub fn offset(&self, dx: i32, dy: i32) -> Rect {
+        Rect::new(self.x + dx, self.y + dy, self.w, self.h)
+    }
+}
+
+impl From<(i32, i32, u32, u32)> for Rect {
+    fn from((x, y, w, h): (i32, i32, u32, u32)) -> Self {
+        Self { x, y, w, h }
+    }
+}
+
+impl From<Rect> for (i32, i32, u32, u32) {
+    fn from(rect: Rect) -> Self {
+        (rect.x, rect.y, rect.w, rect.h)
+    }
+}
+
+impl From<RECT> for Rect {
+    fn from(rect: RECT) -> Self {
+        Self {
+            x: rect.left,
+            y: rect.top,
+            w: (rect.right - rect.left).max(0) as u32,
+            h: (rect.bottom - rect.top).max(0) as u32,
+        }
+    }
+}
+
+impl From<Rect> for RECT {
+    fn from(rect: Rect) -> Self {
+        Self {
+            left: rect.x,
+            top: rect.y,
+            right: rect.right(),
+            bottom: rect.bottom(),
+        }
+    }
+}
+
+/// 屏幕坐标系中的点。与[`ClientPoint`]区分，避免把客户区坐标当作屏幕坐标使用。
+/// 用[`client_to_screen`](crate::position_size::client_to_screen)、[`screen_to_client`](crate::position_size::screen_to_client)互相转换。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScreenPoint(pub Point);
+
+impl ScreenPoint {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self(Point::new(x, y))
+    }
+}
+
+/// 某个窗口客户区坐标系中的点，原点为客户区左上角。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ClientPoint(pub Point);
+
+impl ClientPoint {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self(Point::new(x, y))
+    }
+}
+
+/// 屏幕坐标系中的矩形。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScreenRect(pub Rect);
+
+impl ScreenRect {
+    pub fn new(x: i32, y: i32, w: u32, h: u32) -> Self {
+        Self(Rect::new(x, y, w, h))
+    }
+}
+
+/// 某个窗口客户区坐标系中的矩形。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ClientRect(pub Rect);
+
+impl ClientRect {
+    pub fn new(x: i32, y: i32, w: u32, h: u32) -> Self {
+        Self(Rect::new(x, y, w, h))
+    }
+}
+
+#[test]
+fn test_rect() {
+    let a = Rect::new(0, 0, 100, 100);
+    let b = Rect::new(50, 50, 100, 100);
+    assert_eq!(a.intersect(&b), Some(Rect::new(50, 50, 50, 50)));
+    assert_eq!(a.intersect(&Rect::new(100, 0, 10, 10)), None);
+    assert!(a.contains(Point::new(0, 99)));
+    assert!(!a.contains(Point::new(100, 0)));
+    assert_eq!(b.center(), Point::new(100, 100));
+    assert_eq!(Rect::from(RECT::from(b)), b);
+}
@@ -0,0 +1,285 @@
+//! 记录窗口状态的变化，用于事后复现程序窗口的行为。
+
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::class_title::get_window_title;
+use crate::events::win_events;
+use crate::events::EventFilter;
+use crate::events::EventKinds;
+use crate::events::HookGuard;
+use crate::foreground::is_foreground;
+use crate::position_size::get_window_xywh_include_shadow;
+use crate::result::Result;
+
+/// 窗口状态的一次变化。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WindowChange {
+    /// 位置改变，新的左上角坐标（包括阴影）。
+    Moved {
+        x: i32,
+        y: i32,
+    },
+    /// 尺寸改变，新的尺寸（包括阴影）。
+    Resized {
+        width: u32,
+        height: u32,
+    },
+    Minimized,
+    /// 从最小化还原。
+    Restored,
+    Shown,
+    Hidden,
+    /// 标题改变，新的标题。
+    TitleChanged(String),
+    ForegroundGained,
+    ForegroundLost,
+    /// 窗口被销毁，之后不会再有记录。
+    Destroyed,
+}
+
+impl fmt::Display for WindowChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowChange::Moved { x, y } => write!(f, "moved {} {}", x, y),
+            WindowChange::Resized { width, height } => write!(f, "resized {} {}", width, height),
+            WindowChange::Minimized => write!(f, "minimized"),
+            WindowChange::Restored => write!(f, "restored"),
+            WindowChange::Shown => write!(f, "shown"),
+            WindowChange::Hidden => write!(f, "hidden"),
+            WindowChange::TitleChanged(title) => write!(f, "title {}", title),
+            WindowChange::ForegroundGained => write!(f, "foreground gained"),
+            WindowChange::ForegroundLost => write!(f, "foreground lost"),
+            WindowChange::Destroyed => write!(f, "destroyed"),
+        }
+    }
+}
+
+/// 带时间的变化记录。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowRecord {
+    /// 收到事件的时间。
+    pub time: SystemTime,
+    pub change: WindowChange,
+}
+
+type Records = Arc<Mutex<Vec<WindowRecord>>>;
+
+fn push(records: &Records, change: WindowChange) {
+    records.lock().unwrap().push(WindowRecord {
+        time: SystemTime::now(),
+        change,
+    });
+}
+
+/// 在后台记录一个窗口的状态变化：移动、改变大小、最小化、还原、显示、隐藏、标题改变、获得和失去前台。
+/// drop时停止记录。窗口被销毁后记录[`WindowChange::Destroyed`]并停止。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::foreground::get_foreground_hwnd;
+/// use window_inspector::history::WindowRecorder;
+///
+/// let recorder = WindowRecorder::start(get_foreground_hwnd()).unwrap();
+/// std::thread::sleep(std::time::Duration::from_secs(60));
+/// recorder.export(std::io::stdout()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct WindowRecorder {
+    hwnd: usize,
+    records: Records,
+    hooks: Vec<HookGuard>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl WindowRecorder {
+    /// 开始记录窗口的状态变化。
+    pub fn start(hwnd: usize) -> Result<WindowRecorder> {
+        let window_events = win_events(&EventFilter {
+            hwnd: Some(hwnd),
+            event_kinds: EventKinds::LOCATION_CHANGE
+                | EventKinds::MINIMIZE
+                | EventKinds::RESTORE
+                | EventKinds::SHOW
+                | EventKinds::HIDE
+                | EventKinds::NAME_CHANGE
+                | EventKinds::DESTROY,
+            ..Default::default()
+        })?;
+        // 失去前台时的事件属于其他窗口，需要订阅所有窗口的前台事件。
+        let foreground_events = win_events(&EventFilter {
+            event_kinds: EventKinds::FOREGROUND,
+            ..Default::default()
+        })?;
+        let records = Records::default();
+        let mut xywh = get_window_xywh_include_shadow(hwnd)?;
+        let mut title = get_window_title(hwnd).unwrap_or_default();
+        let mut foreground = is_foreground(hwnd);
+
+        let (window_receiver, window_hook) = window_events.into_parts();
+        let window_thread = {
+            let records = records.clone();
+            thread::spawn(move || {
+                for event in window_receiver.iter() {
+                    let kind = event.kind;
+                    if kind == EventKinds::LOCATION_CHANGE {
+                        let Ok(new_xywh) = get_window_xywh_include_shadow(hwnd) else {
+                            continue;
+                        };
+                        if (new_xywh.0, new_xywh.1) != (xywh.0, xywh.1) {
+                            push(
+                                &records,
+                                WindowChange::Moved {
+                                    x: new_xywh.0,
+                                    y: new_xywh.1,
+                                },
+                            );
+                        }
+                        if (new_xywh.2, new_xywh.3) != (xywh.2, xywh.3) {
+                            push(
+                                &records,
+                                WindowChange::Resized {
+                                    width: new_xywh.2,
+                                    height: new_xywh.3,
+                                },
+                            );
+                        }
+                        xywh = new_xywh;
+                    } else if kind == EventKinds::NAME_CHANGE {
+                        let Ok(new_title) = get_window_title(hwnd) else {
+                            continue;
+                        };
+                        if new_title != title {
+                            title = new_title;
+                            push(&records, WindowChange::TitleChanged(title.clone()));
+                        }
+                    } else if kind == EventKinds::MINIMIZE {
+                        push(&records, WindowChange::Minimized);
+                    } else if kind == EventKinds::RESTORE {
+                        push(&records, WindowChange::Restored);
+                    } else if kind == EventKinds::SHOW {
+                        push(&records, WindowChange::Shown);
+                    } else if kind == EventKinds::HIDE {
+                        push(&records, WindowChange::Hidden);
+                    } else if kind == EventKinds::DESTROY {
+                        push(&records, WindowChange::Destroyed);
+                        break;
+                    }
+                }
+            })
+        };
+
+        let (foreground_receiver, foreground_hook) = foreground_events.into_parts();
+        let foreground_thread = {
+            let records = records.clone();
+            thread::spawn(move || {
+                for event in foreground_receiver.iter() {
+                    let gained = event.hwnd == hwnd;
+                    if gained != foreground {
+                        foreground = gained;
+                        push(
+                            &records,
+                            if gained {
+                                WindowChange::ForegroundGained
+                            } else {
+                                WindowChange::ForegroundLost
+                            },
+                        );
+                    }
+                }
+            })
+        };
+
+        Ok(WindowRecorder {
+            hwnd,
+            records,
+            hooks: vec![window_hook, foreground_hook],
+            threads: vec![window_thread, foreground_thread],
+        })
+    }
+
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 到目前为止的所有记录，按时间排列。
+    pub fn records(&self) -> Vec<WindowRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// 某个时间之后（包括该时间）的记录。
+    pub fn records_since(&self, time: SystemTime) -> Vec<WindowRecord> {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.time >= time)
+            .cloned()
+            .collect()
+    }
+
+    /// 清空已有的记录，继续记录。
+    pub fn clear(&self) {
+        self.records.lock().unwrap().clear();
+    }
+
+    /// 以文本格式导出记录，每行一条：Unix时间戳（毫秒）、制表符、变化内容。
+    pub fn export(&self, mut writer: impl Write) -> io::Result<()> {
+        for record in self.records.lock().unwrap().iter() {
+            let millis = record
+                .time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            writeln!(writer, "{}\t{}", millis, record.change)?;
+        }
+        Ok(())
+    }
+
+    /// 停止记录，返回所有记录。
+    pub fn stop(self) -> Vec<WindowRecord> {
+        let records = self.records.clone();
+        drop(self);
+        let mut records = records.lock().unwrap();
+        mem::take(&mut *records)
+    }
+}
+
+impl Drop for WindowRecorder {
+    fn drop(&mut self) {
+        // 卸载钩子后事件接收端断开，后台线程随之结束。
+        for hook in self.hooks.drain(..) {
+            hook.shutdown();
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[test]
+fn test_window_recorder() {
+    use crate::position_size::move_window_to_xywh;
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new()
+        .xywh(100, 100, 400, 300)
+        .build()
+        .unwrap();
+    let recorder = WindowRecorder::start(window.hwnd()).unwrap();
+    move_window_to_xywh(window.hwnd(), 200, 100, 400, 300).unwrap();
+    thread::sleep(std::time::Duration::from_millis(200));
+    let records = recorder.stop();
+    assert!(records
+        .iter()
+        .any(|record| record.change == WindowChange::Moved { x: 200, y: 100 }));
+}
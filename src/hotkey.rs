@@ -0,0 +1,129 @@
+//! 全局热键。热键在后台线程中注册并处理，不需要自己运行消息循环。
+//!
+//! # 示例
+//! 按Ctrl+Alt+T切换前台窗口的置顶状态：
+//! ```no_run
+//! use window_inspector::foreground::get_foreground_hwnd;
+//! use window_inspector::hotkey::Hotkey;
+//! use window_inspector::hotkey::HotkeyListener;
+//! use window_inspector::hotkey::Modifiers;
+//! use window_inspector::top_most::toggle_window_top_most;
+//!
+//! let hotkey = Hotkey::new(Modifiers::CONTROL | Modifiers::ALT, 'T' as u32);
+//! let _listener = HotkeyListener::new(&[hotkey], |_| {
+//!     let _ = toggle_window_top_most(get_foreground_hwnd());
+//! })
+//! .unwrap();
+//! std::thread::park();
+//! ```
+
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+
+use bitflags::bitflags;
+use windows::Win32::UI::Input::KeyboardAndMouse::RegisterHotKey;
+use windows::Win32::UI::Input::KeyboardAndMouse::UnregisterHotKey;
+use windows::Win32::UI::Input::KeyboardAndMouse::HOT_KEY_MODIFIERS;
+use windows::Win32::UI::WindowsAndMessaging::WM_HOTKEY;
+
+use crate::error::WindowInspectorError;
+use crate::message_loop::MessageThread;
+use crate::result::Result;
+
+bitflags! {
+    /// 热键的修饰键，对应`MOD_*`。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Modifiers: u32 {
+        /// Alt键。
+        const ALT = 0x0001;
+        /// Ctrl键。
+        const CONTROL = 0x0002;
+        /// Shift键。
+        const SHIFT = 0x0004;
+        /// Win键。
+        const WIN = 0x0008;
+        /// 按住不放时不重复触发。
+        const NO_REPEAT = 0x4000;
+    }
+}
+
+/// 热键：修饰键加一个虚拟键码（`VK_*`，字母和数字键为对应的大写ASCII码）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub modifiers: Modifiers,
+    pub key: u32,
+}
+
+impl Hotkey {
+    pub fn new(modifiers: Modifiers, key: u32) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+/// 监听一组全局热键，drop时注销热键并结束后台线程。
+#[derive(Debug)]
+pub struct HotkeyListener {
+    _thread: MessageThread,
+}
+
+impl HotkeyListener {
+    /// 注册热键，按下任一热键时在后台线程中调用`callback`。
+    /// 任一热键注册失败（例如已被其他程序注册）时，已注册的热键会被注销，并返回错误。
+    pub fn new(
+        hotkeys: &[Hotkey],
+        mut callback: impl FnMut(Hotkey) + Send + 'static,
+    ) -> Result<HotkeyListener> {
+        let hotkeys = hotkeys.to_vec();
+        let registering = hotkeys.clone();
+        let count = hotkeys.len();
+        let thread = MessageThread::spawn(
+            move || {
+                for (id, hotkey) in registering.iter().enumerate() {
+                    if let Err(error) = unsafe {
+                        RegisterHotKey(
+                            None,
+                            id as i32,
+                            HOT_KEY_MODIFIERS(hotkey.modifiers.bits()),
+                            hotkey.key,
+                        )
+                    } {
+                        for id in 0..id {
+                            let _ = unsafe { UnregisterHotKey(None, id as i32) };
+                        }
+                        return Err((*hotkey, error));
+                    }
+                }
+                Ok(())
+            },
+            move |msg| {
+                if msg.message == WM_HOTKEY {
+                    if let Some(hotkey) = hotkeys.get(msg.wParam.0) {
+                        callback(*hotkey);
+                    }
+                }
+            },
+            move |()| {
+                for id in 0..count {
+                    let _ = unsafe { UnregisterHotKey(None, id as i32) };
+                }
+            },
+        )
+        .map_err(
+            |(hotkey, error)| WindowInspectorError::RegisterHotKeyFailed {
+                modifiers: hotkey.modifiers.bits(),
+                key: hotkey.key,
+                error,
+            },
+        )?;
+        Ok(HotkeyListener { _thread: thread })
+    }
+
+    /// 注册热键，按下的热键通过返回的[`Receiver`]接收。
+    pub fn channel(hotkeys: &[Hotkey]) -> Result<(HotkeyListener, Receiver<Hotkey>)> {
+        let (sender, receiver) = mpsc::channel();
+        let listener = HotkeyListener::new(hotkeys, move |hotkey| {
+            let _ = sender.send(hotkey);
+        })?;
+        Ok((listener, receiver))
+    }
+}
@@ -0,0 +1,139 @@
+//! 窗口指纹：句柄、进程ID都已改变（例如重启后）时，重新找到“同一个”窗口。
+
+use std::cmp::Reverse;
+use std::ffi::c_void;
+
+use windows::core::BSTR;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Storage::EnhancedStorage::PKEY_AppUserModel_ID;
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::PropertiesSystem::SHGetPropertyStoreForWindow;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+
+use crate::class_title::get_window_class;
+use crate::class_title::get_window_title;
+use crate::com::ComGuard;
+use crate::enumerate::get_top_level_windows;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::monitor::get_monitor_device_name;
+use crate::monitor::get_window_monitor;
+use crate::process::get_window_process_path;
+use crate::result::Result;
+
+/// 窗口指纹，只包含重启后仍然不变的信息。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WindowFingerprint {
+    /// 所属进程的可执行文件路径。
+    pub exe_path: String,
+    /// 窗口类名。
+    pub class: String,
+    /// 窗口的AppUserModelID，见[`get_window_aumid`]。
+    pub aumid: Option<String>,
+    /// 规范化后的标题，见[`normalize_title`]。
+    pub title_pattern: String,
+    /// 所在显示器的设备名，见[`get_monitor_device_name`]。
+    pub monitor: Option<String>,
+}
+
+/// 获取窗口显式设置的AppUserModelID，没有设置时返回`None`。
+/// 同一个可执行文件承载多个应用时（例如UWP应用、PWA）可以用它区分。
+pub fn get_window_aumid(hwnd: usize) -> Result<Option<String>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let _com = ComGuard::new()?;
+    let value = unsafe {
+        SHGetPropertyStoreForWindow::<_, IPropertyStore>(HWND(hwnd as *mut c_void))
+            .and_then(|store| store.GetValue(&PKEY_AppUserModel_ID))
+    }
+    .map_err(
+        |e| WindowInspectorError::SHGetPropertyStoreForWindowFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        },
+    )?;
+    Ok(BSTR::try_from(&value)
+        .ok()
+        .map(|aumid| aumid.to_string())
+        .filter(|aumid| !aumid.is_empty()))
+}
+
+/// 规范化标题：只保留最后一个` - `之后的部分（通常是应用名，前面是文档名），并把连续的数字替换为`#`。
+/// 例如`新建文本文档 (2).txt - 记事本`规范化为`记事本`，`第3页`规范化为`第#页`。
+pub fn normalize_title(title: &str) -> String {
+    let title = title.rsplit(" - ").next().unwrap_or(title).trim();
+    let mut pattern = String::with_capacity(title.len());
+    for c in title.chars() {
+        if !c.is_ascii_digit() {
+            pattern.push(c);
+        } else if !pattern.ends_with('#') {
+            pattern.push('#');
+        }
+    }
+    pattern
+}
+
+/// 获取窗口的指纹。
+pub fn fingerprint(hwnd: usize) -> Result<WindowFingerprint> {
+    Ok(WindowFingerprint {
+        exe_path: get_window_process_path(hwnd as isize)?,
+        class: get_window_class(hwnd)?,
+        aumid: get_window_aumid(hwnd)?,
+        title_pattern: normalize_title(&get_window_title(hwnd).unwrap_or_default()),
+        monitor: get_monitor_device_name(get_window_monitor(hwnd)?).ok(),
+    })
+}
+
+/// 按指纹查找顶层窗口。
+/// 可执行文件路径（不区分大小写）、类名、AppUserModelID必须相同；
+/// 有多个候选窗口时，依次优先可见的、规范化标题相同的、在同一显示器上的窗口，仍然相同时取Z序靠上的。
+/// 找不到时返回[`WindowInspectorError::NoMatchingWindow`]。
+pub fn find_by_fingerprint(fp: &WindowFingerprint) -> Result<usize> {
+    get_top_level_windows()?
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, hwnd)| {
+            if get_window_class(hwnd).ok()? != fp.class
+                || !get_window_process_path(hwnd as isize)
+                    .ok()?
+                    .eq_ignore_ascii_case(&fp.exe_path)
+                || get_window_aumid(hwnd).ok()? != fp.aumid
+            {
+                return None;
+            }
+            let visible = unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool();
+            let same_title =
+                normalize_title(&get_window_title(hwnd).unwrap_or_default()) == fp.title_pattern;
+            let same_monitor = fp.monitor.is_some()
+                && get_window_monitor(hwnd)
+                    .and_then(get_monitor_device_name)
+                    .ok()
+                    == fp.monitor;
+            Some(((visible, same_title, same_monitor, Reverse(index)), hwnd))
+        })
+        .max_by_key(|&(key, _)| key)
+        .map(|(_, hwnd)| hwnd)
+        .ok_or(WindowInspectorError::NoMatchingWindow)
+}
+
+#[test]
+fn test_normalize_title() {
+    assert_eq!(normalize_title("新建文本文档 (2).txt - 记事本"), "记事本");
+    assert_eq!(normalize_title("第12页 - 第3章"), "第#章");
+    assert_eq!(normalize_title("v1.2.3"), "v#.#.#");
+    assert_eq!(normalize_title(""), "");
+}
+
+#[test]
+fn test_fingerprint_untitled() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let fp = fingerprint(window.hwnd()).unwrap();
+    assert_eq!(fp.class, window.class());
+    assert_eq!(fp.title_pattern, "");
+    assert_eq!(find_by_fingerprint(&fp).unwrap(), window.hwnd());
+}
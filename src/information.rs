@@ -0,0 +1,476 @@
+use std::ffi::c_void;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use bitflags::bitflags;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::IsZoomed;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
+
+use crate::class_title::get_window_class_unchecked;
+use crate::class_title::get_window_title;
+use crate::class_title::get_window_title_into_unchecked;
+use crate::class_title::get_window_title_unchecked;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::geometry::Rect;
+use crate::position_size::get_client_xywh_unchecked;
+use crate::position_size::get_window_xywh_exclude_shadow_unchecked;
+use crate::position_size::get_window_xywh_include_shadow_unchecked;
+use crate::process::get_process_path;
+use crate::process::get_process_start_time;
+use crate::process::get_window_process;
+use crate::process::get_window_process_path;
+use crate::result::Result;
+use crate::top_most::get_window_top_most_unchecked;
+
+/// 获取窗口已存在的时长。
+/// Windows不记录窗口的创建时间，这里以所属进程的启动时间近似。
+/// 对于启动后很久才创建的窗口，得到的时长会偏大。
+pub fn get_window_uptime(hwnd: usize) -> Result<Duration> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let start_time = get_process_start_time(get_window_process(hwnd as isize)?)?;
+    Ok(SystemTime::now()
+        .duration_since(start_time)
+        .unwrap_or_default())
+}
+
+bitflags! {
+    /// [`query`]要获取的窗口信息。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct QueryFlags: u32 {
+        /// 窗口类名。
+        const CLASS = 1 << 0;
+        /// 窗口标题。
+        const TITLE = 1 << 1;
+        /// 所属进程ID。
+        const PROCESS_ID = 1 << 2;
+        /// 所属进程路径。
+        const PROCESS_PATH = 1 << 3;
+        /// 窗口位置尺寸（包括阴影）。
+        const WINDOW_XYWH_INCLUDE_SHADOW = 1 << 4;
+        /// 窗口位置尺寸（不包括阴影）。
+        const WINDOW_XYWH_EXCLUDE_SHADOW = 1 << 5;
+        /// 客户区位置尺寸。
+        const CLIENT_XYWH = 1 << 6;
+        /// 是否置顶。
+        const TOP_MOST = 1 << 7;
+        /// 是否可见。
+        const VISIBLE = 1 << 8;
+        /// 是否最小化。
+        const MINIMIZED = 1 << 9;
+    }
+}
+
+/// [`query`]的结果，没有请求的字段为`None`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartialWindowInfo {
+    /// 窗口类名。
+    pub class: Option<String>,
+    /// 窗口标题，没有标题时为空字符串。
+    pub title: Option<String>,
+    /// 所属进程ID。
+    pub process_id: Option<u32>,
+    /// 所属进程路径。
+    pub process_path: Option<String>,
+    /// 窗口位置尺寸（包括阴影），(x, y, width, height)。
+    pub window_xywh_include_shadow: Option<(i32, i32, u32, u32)>,
+    /// 窗口位置尺寸（不包括阴影），(x, y, width, height)。
+    pub window_xywh_exclude_shadow: Option<(i32, i32, u32, u32)>,
+    /// 客户区位置尺寸，(x, y, width, height)。
+    pub client_xywh: Option<(i32, i32, u32, u32)>,
+    /// 是否置顶。
+    pub top_most: Option<bool>,
+    /// 是否可见。
+    pub visible: Option<bool>,
+    /// 是否最小化。
+    pub minimized: Option<bool>,
+}
+
+/// 一次获取多项窗口信息。
+/// 只调用`flags`中请求的信息所需的Win32函数，并且只检查一次窗口是否存在。
+/// 逐个调用各个获取函数时，每个函数都会检查一次窗口是否存在，系统调用次数约为这里的两倍。
+/// # 错误
+/// 任何一项信息获取失败时返回错误。
+pub fn query(hwnd: usize, flags: QueryFlags) -> Result<PartialWindowInfo> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let mut info = PartialWindowInfo::default();
+    if flags.contains(QueryFlags::CLASS) {
+        info.class = Some(get_window_class_unchecked(hwnd)?);
+    }
+    if flags.contains(QueryFlags::TITLE) {
+        // 标题为空时GetWindowTextW也返回0，这里不视为错误。
+        info.title = Some(get_window_title_unchecked(hwnd).unwrap_or_default());
+    }
+    if flags.intersects(QueryFlags::PROCESS_ID | QueryFlags::PROCESS_PATH) {
+        let process_id = get_window_process(hwnd as isize)?;
+        if flags.contains(QueryFlags::PROCESS_PATH) {
+            info.process_path = Some(get_process_path(process_id)?);
+        }
+        if flags.contains(QueryFlags::PROCESS_ID) {
+            info.process_id = Some(process_id);
+        }
+    }
+    if flags.contains(QueryFlags::WINDOW_XYWH_INCLUDE_SHADOW) {
+        info.window_xywh_include_shadow = Some(get_window_xywh_include_shadow_unchecked(hwnd)?);
+    }
+    if flags.contains(QueryFlags::WINDOW_XYWH_EXCLUDE_SHADOW) {
+        info.window_xywh_exclude_shadow = Some(get_window_xywh_exclude_shadow_unchecked(hwnd)?);
+    }
+    if flags.contains(QueryFlags::CLIENT_XYWH) {
+        info.client_xywh = Some(get_client_xywh_unchecked(hwnd)?);
+    }
+    if flags.contains(QueryFlags::TOP_MOST) {
+        info.top_most = Some(get_window_top_most_unchecked(hwnd)?);
+    }
+    if flags.contains(QueryFlags::VISIBLE) {
+        info.visible = Some(unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool());
+    }
+    if flags.contains(QueryFlags::MINIMIZED) {
+        info.minimized = Some(unsafe { IsIconic(HWND(hwnd as *mut c_void)) }.as_bool());
+    }
+    Ok(info)
+}
+
+/// 窗口矩形（`GetWindowRect`，包括阴影）与DWM绘制的可见边框（`DWMWA_EXTENDED_FRAME_BOUNDS`，不包括阴影）在各边的差值，
+/// 即不可见的阴影、缩放边框的宽度。由[`get_window_frame_offsets`]返回。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FrameOffsets {
+    /// 左边框的差值。
+    pub left: i32,
+    /// 上边框的差值。
+    pub top: i32,
+    /// 右边框的差值。
+    pub right: i32,
+    /// 下边框的差值。
+    pub bottom: i32,
+}
+
+lazy_static! {
+    /// 以(窗口, 样式, 扩展样式, DPI)为键缓存边框差值。
+    static ref FRAME_OFFSETS_CACHE: Mutex<LruCache<(usize, u32, u32, u32), FrameOffsets>> =
+        Mutex::new(LruCache::new(NonZeroUsize::new(256).unwrap()));
+}
+
+/// 获取窗口矩形与可见边框在各边的差值。
+/// 已知窗口可见边框的位置时，用它换算出包括阴影的位置（反之亦然），不需要每次都分别获取两个矩形。
+/// 结果按窗口、样式（包括最大化等状态）和DPI缓存，样式或DPI变化后会重新计算。
+pub fn get_window_frame_offsets(hwnd: usize) -> Result<FrameOffsets> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let key = unsafe {
+        (
+            hwnd,
+            GetWindowLongW(HWND(hwnd as *mut c_void), GWL_STYLE) as u32,
+            GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) as u32,
+            GetDpiForWindow(HWND(hwnd as *mut c_void)),
+        )
+    };
+    if let Some(&offsets) = FRAME_OFFSETS_CACHE.lock().unwrap().get(&key) {
+        return Ok(offsets);
+    }
+    let (x, y, width, height) = get_window_xywh_include_shadow_unchecked(hwnd)?;
+    let (frame_x, frame_y, frame_width, frame_height) =
+        get_window_xywh_exclude_shadow_unchecked(hwnd)?;
+    let offsets = FrameOffsets {
+        left: frame_x - x,
+        top: frame_y - y,
+        right: (x + width as i32) - (frame_x + frame_width as i32),
+        bottom: (y + height as i32) - (frame_y + frame_height as i32),
+    };
+    FRAME_OFFSETS_CACHE.lock().unwrap().put(key, offsets);
+    Ok(offsets)
+}
+
+/// 从窗口标题中拆分出的文档名和应用名，由[`parse_app_title`]返回。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ParsedTitle {
+    /// 文档名、网页标题等，标题中没有分隔符时为`None`。
+    pub document: Option<String>,
+    /// 应用名。
+    pub application: String,
+}
+
+/// 针对某个程序的标题拆分规则，见[`TitleParser`]。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TitleRule {
+    /// 可执行文件名，例如`WINWORD.EXE`，不区分大小写。
+    pub process_name: String,
+    /// 标题中应用名在前、文档名在后，例如`应用 - 文档`。
+    pub application_first: bool,
+    /// 用这个名称代替标题中的应用名。
+    pub application: Option<String>,
+}
+
+/// 标题拆分的配置。
+/// 默认在最后一个分隔符处拆分，前面是文档名，后面是应用名，例如`report.xlsx - Excel`。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TitleParser {
+    /// 分隔符，按顺序尝试，使用第一个在标题中出现的。
+    pub separators: Vec<String>,
+    /// 各程序的规则。
+    pub rules: Vec<TitleRule>,
+}
+
+impl Default for TitleParser {
+    /// 分隔符为` - `、` — `、` – `、` | `，没有规则。
+    fn default() -> Self {
+        Self {
+            separators: [" - ", " — ", " – ", " | "]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl TitleParser {
+    /// 拆分标题。`process_name`为窗口所属进程的可执行文件名，用于匹配[`TitleRule`]。
+    pub fn parse(&self, title: &str, process_name: Option<&str>) -> ParsedTitle {
+        let rule = process_name.and_then(|process_name| {
+            self.rules
+                .iter()
+                .find(|rule| rule.process_name.eq_ignore_ascii_case(process_name))
+        });
+        let application_first = rule.is_some_and(|rule| rule.application_first);
+        let split = self.separators.iter().find_map(|separator| {
+            if application_first {
+                title.split_once(separator.as_str())
+            } else {
+                title
+                    .rsplit_once(separator.as_str())
+                    .map(|(document, application)| (application, document))
+            }
+        });
+        let (application, document) = match split {
+            Some((application, document)) => (application.trim(), Some(document.trim())),
+            None => (title.trim(), None),
+        };
+        ParsedTitle {
+            document: document
+                .filter(|document| !document.is_empty())
+                .map(String::from),
+            application: rule
+                .and_then(|rule| rule.application.clone())
+                .unwrap_or_else(|| application.to_string()),
+        }
+    }
+}
+
+/// 用默认的[`TitleParser`]把窗口标题拆分为文档名和应用名，例如`report.xlsx - Excel`拆分为`report.xlsx`和`Excel`。
+pub fn parse_app_title(hwnd: usize) -> Result<ParsedTitle> {
+    parse_app_title_with(hwnd, &TitleParser::default())
+}
+
+/// 用指定的[`TitleParser`]拆分窗口标题。
+pub fn parse_app_title_with(hwnd: usize, parser: &TitleParser) -> Result<ParsedTitle> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    // 标题为空时获取标题也会失败，视为空标题。
+    let title = get_window_title(hwnd).unwrap_or_default();
+    // 只有设置了规则时才需要获取进程名。
+    let process_path = if parser.rules.is_empty() {
+        None
+    } else {
+        get_window_process_path(hwnd as isize).ok()
+    };
+    let process_name = process_path.as_deref().and_then(|path| {
+        std::path::Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+    });
+    Ok(parser.parse(&title, process_name))
+}
+
+/// 窗口的显示状态，见[`WindowTracker`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShowState {
+    /// 不可见。
+    Hidden,
+    /// 可见，既没有最小化也没有最大化。
+    Normal,
+    /// 最小化。
+    Minimized,
+    /// 最大化。
+    Maximized,
+}
+
+fn get_show_state_unchecked(hwnd: usize) -> ShowState {
+    let hwnd = HWND(hwnd as *mut c_void);
+    if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+        ShowState::Hidden
+    } else if unsafe { IsIconic(hwnd) }.as_bool() {
+        ShowState::Minimized
+    } else if unsafe { IsZoomed(hwnd) }.as_bool() {
+        ShowState::Maximized
+    } else {
+        ShowState::Normal
+    }
+}
+
+/// [`WindowTracker::poll`]返回的变化，只有改变了的字段为`Some`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowChanges {
+    /// 新的窗口标题。
+    pub title: Option<String>,
+    /// 新的窗口矩形（包括阴影）。
+    pub rect: Option<Rect>,
+    /// 新的显示状态。
+    pub state: Option<ShowState>,
+}
+
+impl WindowChanges {
+    /// 是否没有任何变化。
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.rect.is_none() && self.state.is_none()
+    }
+}
+
+/// 跟踪窗口的标题、矩形和显示状态，每次[`poll`](WindowTracker::poll)只返回改变了的字段。
+/// 标题读入复用的缓冲区再比较，没有变化时不分配内存，适合高频刷新界面上的标签。
+#[derive(Debug, Clone)]
+pub struct WindowTracker {
+    hwnd: usize,
+    title: String,
+    buffer: String,
+    rect: Option<Rect>,
+    state: Option<ShowState>,
+}
+
+impl WindowTracker {
+    /// 开始跟踪窗口。第一次[`poll`](WindowTracker::poll)返回所有字段。
+    pub fn new(hwnd: usize) -> Result<WindowTracker> {
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+        Ok(WindowTracker {
+            hwnd,
+            title: String::new(),
+            buffer: String::new(),
+            rect: None,
+            state: None,
+        })
+    }
+
+    /// 跟踪的窗口。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 获取自上次调用以来改变了的字段。
+    /// # 错误
+    /// 窗口已经关闭时返回[`WindowInspectorError::WindowNotExist`]。
+    pub fn poll(&mut self) -> Result<WindowChanges> {
+        let hwnd = self.hwnd;
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+        let mut changes = WindowChanges::default();
+
+        // 标题为空时GetWindowTextW也返回0，这里不视为错误。
+        if get_window_title_into_unchecked(hwnd, &mut self.buffer).is_err() {
+            self.buffer.clear();
+        }
+        // 第一次poll时标题为空也要返回。
+        if self.buffer != self.title || self.state.is_none() {
+            std::mem::swap(&mut self.title, &mut self.buffer);
+            changes.title = Some(self.title.clone());
+        }
+
+        let rect = Rect::from(get_window_xywh_include_shadow_unchecked(hwnd)?);
+        if self.rect != Some(rect) {
+            self.rect = Some(rect);
+            changes.rect = Some(rect);
+        }
+
+        let state = get_show_state_unchecked(hwnd);
+        if self.state != Some(state) {
+            self.state = Some(state);
+            changes.state = Some(state);
+        }
+        Ok(changes)
+    }
+}
+
+#[test]
+fn test_title_parser() {
+    let parser = TitleParser {
+        rules: vec![TitleRule {
+            process_name: "app.exe".to_string(),
+            application_first: true,
+            application: Some("App".to_string()),
+        }],
+        ..Default::default()
+    };
+    assert_eq!(
+        parser.parse("report.xlsx - Excel", None),
+        ParsedTitle {
+            document: Some("report.xlsx".to_string()),
+            application: "Excel".to_string(),
+        }
+    );
+    assert_eq!(
+        parser
+            .parse("Page - Site - Mozilla Firefox", None)
+            .document
+            .as_deref(),
+        Some("Page - Site")
+    );
+    assert_eq!(
+        parser.parse("App Studio - notes.txt", Some("APP.EXE")),
+        ParsedTitle {
+            document: Some("notes.txt".to_string()),
+            application: "App".to_string(),
+        }
+    );
+    assert_eq!(parser.parse("Calculator", None).document, None);
+}
+
+#[test]
+fn test_window_tracker() {
+    use crate::position_size::move_window_to_xywh;
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().title("tracker").build().unwrap();
+    let mut tracker = WindowTracker::new(window.hwnd()).unwrap();
+    let changes = tracker.poll().unwrap();
+    assert_eq!(changes.title.as_deref(), Some("tracker"));
+    assert!(changes.rect.is_some() && changes.state.is_some());
+    assert!(tracker.poll().unwrap().is_empty());
+    move_window_to_xywh(window.hwnd(), 100, 100, 300, 200).unwrap();
+    assert_eq!(
+        tracker.poll().unwrap(),
+        WindowChanges {
+            rect: Some(Rect::new(100, 100, 300, 200)),
+            ..Default::default()
+        }
+    );
+}
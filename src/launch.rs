@@ -0,0 +1,261 @@
+use std::ffi::c_void;
+use std::ffi::OsStr;
+use std::os::windows::io::AsRawHandle;
+#[cfg(feature = "ntdll")]
+use std::os::windows::process::CommandExt;
+use std::process::Child;
+use std::process::Command;
+use std::thread::sleep;
+use std::time::Duration;
+use std::time::Instant;
+
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::LPARAM;
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::WAIT_OBJECT_0;
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::WPARAM;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::OpenProcess;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::TerminateProcess;
+use windows::Win32::System::Threading::WaitForInputIdle;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::WaitForSingleObject;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::PROCESS_SYNCHRONIZE;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::PROCESS_TERMINATE;
+#[cfg(feature = "ntdll")]
+use windows::Win32::UI::WindowsAndMessaging::GetWindowPlacement;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+#[cfg(feature = "ntdll")]
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+#[cfg(feature = "ntdll")]
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPlacement;
+#[cfg(feature = "ntdll")]
+use windows::Win32::UI::WindowsAndMessaging::WINDOWPLACEMENT;
+#[cfg(feature = "ntdll")]
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+
+use crate::enumerate::get_process_windows;
+use crate::error::WindowInspectorError;
+#[cfg(feature = "ntdll")]
+use crate::exist::is_window_exist;
+use crate::find::WindowMatcher;
+use crate::mode::dry_run;
+#[cfg(feature = "ntdll")]
+use crate::process::get_main_window;
+#[cfg(feature = "ntdll")]
+use crate::process::get_process_command_line;
+#[cfg(feature = "ntdll")]
+use crate::process::get_process_path;
+#[cfg(feature = "ntdll")]
+use crate::process::get_window_process;
+use crate::result::Result;
+
+/// 查找窗口的轮询间隔。
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 启动程序，并等待它的窗口出现。
+/// 先使用`WaitForInputIdle`等待程序完成初始化，然后轮询该进程的可见顶层窗口，返回第一个满足`matcher`的窗口。
+/// 只查找新进程自己的窗口。通过启动器再启动其他进程的程序（例如某些游戏启动器）无法用这个函数找到窗口。
+/// # 返回
+/// (子进程, 窗口句柄)
+/// # 错误
+/// 超时后返回[`WindowInspectorError::WaitForWindowTimeout`]。返回错误时子进程已被结束，不会留下无法访问的进程。
+pub fn spawn_and_wait_for_window<I, S>(
+    command: impl AsRef<OsStr>,
+    args: I,
+    matcher: WindowMatcher,
+    timeout: Duration,
+) -> Result<(Child, usize)>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let command = command.as_ref();
+    if dry_run(format_args!("spawn {}", command.to_string_lossy())) {
+        return Err(WindowInspectorError::DryRun {
+            operation: "spawn_and_wait_for_window",
+        });
+    }
+    let mut child = Command::new(command).args(args).spawn().map_err(|e| {
+        WindowInspectorError::SpawnFailed {
+            command: command.to_string_lossy().into_owned(),
+            error: e,
+        }
+    })?;
+    let start = Instant::now();
+    let process_handle = HANDLE(child.as_raw_handle());
+    // 控制台程序没有消息队列，WaitForInputIdle会立即失败，继续轮询即可。
+    unsafe {
+        WaitForInputIdle(
+            process_handle,
+            timeout.as_millis().min(u32::MAX as u128) as u32,
+        )
+    };
+    match wait_for_process_window(
+        child.id(),
+        &matcher,
+        timeout.saturating_sub(start.elapsed()),
+    ) {
+        Ok(hwnd) => Ok((child, hwnd)),
+        Err(e) => {
+            // 调用者拿不到子进程，结束它并回收，避免留下孤儿进程。
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(e)
+        }
+    }
+}
+
+/// 等待进程的可见顶层窗口出现，返回第一个满足`matcher`的窗口。
+fn wait_for_process_window(
+    process_id: u32,
+    matcher: &WindowMatcher,
+    timeout: Duration,
+) -> Result<usize> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let hwnd = get_process_windows(process_id)?.into_iter().find(|&hwnd| {
+            unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool() && matcher.matches(hwnd)
+        });
+        if let Some(hwnd) = hwnd {
+            return Ok(hwnd);
+        }
+        if Instant::now() >= deadline {
+            return Err(WindowInspectorError::WaitForWindowTimeout { process_id });
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
+
+/// 去掉命令行中的程序路径部分，返回参数部分。
+#[cfg(feature = "ntdll")]
+fn strip_program_from_command_line(command_line: &str) -> &str {
+    let command_line = command_line.trim_start();
+    let rest = match command_line.strip_prefix('"') {
+        Some(quoted) => quoted.find('"').map_or("", |end| &quoted[end + 1..]),
+        None => command_line
+            .find(char::is_whitespace)
+            .map_or("", |end| &command_line[end..]),
+    };
+    rest.trim_start()
+}
+
+/// 重启窗口所属的程序，并恢复窗口位置。
+/// 1. 记录程序路径、命令行和窗口位置（[`GetWindowPlacement`]）。
+/// 2. 向窗口发送`WM_CLOSE`，等待进程退出；超过`timeout`仍未退出（例如程序未响应）时结束进程。
+/// 3. 用相同的命令行重新启动程序，等待新的主窗口出现（最多`timeout`），恢复窗口位置。
+///
+/// 需要`ntdll`特性，用于获取原来的命令行。
+/// # 返回
+/// (新的子进程, 新的主窗口句柄)
+///
+/// [`GetWindowPlacement`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/WindowsAndMessaging/fn.GetWindowPlacement.html
+#[cfg(feature = "ntdll")]
+pub fn restart_window_app(hwnd: usize, timeout: Duration) -> Result<(Child, usize)> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let process_id = get_window_process(hwnd as isize)?;
+    let path = get_process_path(process_id)?;
+    let command_line = get_process_command_line(process_id)?;
+    let mut placement = WINDOWPLACEMENT {
+        length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+        ..Default::default()
+    };
+    unsafe { GetWindowPlacement(HWND(hwnd as *mut c_void), &mut placement) }.map_err(|e| {
+        WindowInspectorError::GetWindowPlacementFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        }
+    })?;
+
+    if dry_run(format_args!("restart {} ({})", path, process_id)) {
+        return Err(WindowInspectorError::DryRun {
+            operation: "restart_window_app",
+        });
+    }
+
+    let timeout_ms = timeout.as_millis().min(u32::MAX as u128) as u32;
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_TERMINATE, false, process_id) }
+            .map_err(|e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            })?;
+    let result = unsafe {
+        PostMessageW(
+            HWND(hwnd as *mut c_void),
+            WM_CLOSE,
+            WPARAM::default(),
+            LPARAM::default(),
+        )
+    }
+    .map_err(|e| WindowInspectorError::PostMessageWFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+    .and_then(|_| {
+        if unsafe { WaitForSingleObject(process_handle, timeout_ms) } == WAIT_OBJECT_0 {
+            return Ok(());
+        }
+        unsafe { TerminateProcess(process_handle, 1) }.map_err(|e| {
+            WindowInspectorError::TerminateProcessFailed {
+                process_id,
+                error: e,
+            }
+        })?;
+        unsafe { WaitForSingleObject(process_handle, timeout_ms) };
+        Ok(())
+    });
+    let _ = unsafe { CloseHandle(process_handle) };
+    result?;
+
+    let child = Command::new(&path)
+        .raw_arg(strip_program_from_command_line(&command_line))
+        .spawn()
+        .map_err(|e| WindowInspectorError::SpawnFailed {
+            command: command_line.clone(),
+            error: e,
+        })?;
+    let deadline = Instant::now() + timeout;
+    let new_hwnd = loop {
+        match get_main_window(child.id()) {
+            Ok(new_hwnd) => break new_hwnd,
+            Err(_) if Instant::now() < deadline => sleep(POLL_INTERVAL),
+            Err(_) => {
+                return Err(WindowInspectorError::WaitForWindowTimeout {
+                    process_id: child.id(),
+                })
+            }
+        }
+    };
+    unsafe { SetWindowPlacement(HWND(new_hwnd as *mut c_void), &placement) }.map_err(|e| {
+        WindowInspectorError::SetWindowPlacementFailed {
+            hwnd: HWND(new_hwnd as *mut c_void),
+            error: e,
+        }
+    })?;
+    Ok((child, new_hwnd))
+}
+
+#[cfg(feature = "ntdll")]
+#[test]
+fn test_strip_program_from_command_line() {
+    assert_eq!(
+        strip_program_from_command_line(r#""C:\Program Files\app.exe" --profile "a b""#),
+        r#"--profile "a b""#
+    );
+    assert_eq!(strip_program_from_command_line(r"C:\app.exe  -x"), "-x");
+    assert_eq!(strip_program_from_command_line(r#""C:\app.exe""#), "");
+    assert_eq!(strip_program_from_command_line("app.exe"), "");
+}
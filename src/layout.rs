@@ -0,0 +1,668 @@
+//! 窗口布局。
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fs;
+use std::io;
+use std::mem::size_of;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::BeginDeferWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::DeferWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::EndDeferWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowPlacement;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPlacement;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWMAXIMIZED;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWMINIMIZED;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+use windows::Win32::UI::WindowsAndMessaging::WINDOWPLACEMENT;
+use windows::Win32::UI::WindowsAndMessaging::WPF_RESTORETOMAXIMIZED;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+
+use crate::error::WindowInspectorError;
+use crate::events::win_events;
+use crate::events::EventFilter;
+use crate::events::EventKinds;
+use crate::events::HookGuard;
+use crate::exist::is_window_exist;
+use crate::geometry::Rect;
+use crate::identity::fingerprint;
+use crate::identity::WindowFingerprint;
+use crate::mode::dry_run;
+use crate::monitor::from_monitor_coords;
+use crate::monitor::get_monitor_device_name;
+use crate::monitor::get_monitor_rect;
+use crate::monitor::get_monitor_work_area;
+use crate::monitor::get_monitors;
+use crate::monitor::get_primary_monitor;
+use crate::monitor::get_window_monitor;
+use crate::monitor::to_monitor_coords;
+use crate::position_size::get_window_xywh_include_shadow;
+use crate::position_size::move_window_to_rect;
+use crate::position_size::move_window_to_xywh;
+use crate::result::Result;
+
+/// 将窗口固定在指定位置，drop时解除。由[`lock_position`]返回。
+#[must_use = "guard被drop时会立即解除固定"]
+#[derive(Debug)]
+pub struct LockGuard {
+    hwnd: usize,
+    hook: Option<HookGuard>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LockGuard {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // 卸载钩子后事件接收端断开，后台线程随之结束。
+        if let Some(hook) = self.hook.take() {
+            hook.shutdown();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 将窗口移动到`(x, y, width, height)`（包括阴影，与[`move_window_to_xywh`]一致）并固定：
+/// 之后窗口被移动或改变大小时（无论是用户拖动还是程序调用），立即移回原位。
+/// 适用于展示屏、多显示器看板等需要窗口一直在固定位置的场景。窗口关闭后自动停止。
+pub fn lock_position(
+    hwnd: usize,
+    (x, y, width, height): (i32, i32, u32, u32),
+) -> Result<LockGuard> {
+    let events = win_events(&EventFilter {
+        hwnd: Some(hwnd),
+        event_kinds: EventKinds::LOCATION_CHANGE | EventKinds::MOVE_SIZE_END,
+        ..Default::default()
+    })?;
+    move_window_to_xywh(hwnd, x, y, width, height)?;
+    let (receiver, hook) = events.into_parts();
+    let thread = thread::spawn(move || {
+        for _ in receiver.iter() {
+            match get_window_xywh_include_shadow(hwnd) {
+                Ok(xywh) if xywh == (x, y, width, height) => {}
+                Ok(_) => {
+                    let _ = move_window_to_xywh(hwnd, x, y, width, height);
+                }
+                // 窗口已经关闭。
+                Err(_) => break,
+            }
+        }
+    });
+    Ok(LockGuard {
+        hwnd,
+        hook: Some(hook),
+        thread: Some(thread),
+    })
+}
+
+/// 一次移动多个窗口（`BeginDeferWindowPos`/`DeferWindowPos`/`EndDeferWindowPos`）。
+/// 所有窗口在同一帧内移动，不会像依次调用[`move_window_to_xywh`]那样一个个错开、闪烁。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::layout::BatchMove;
+///
+/// # let (left, right) = (0, 0);
+/// BatchMove::new()
+///     .add(left, (0, 0, 960, 1040))
+///     .add(right, (960, 0, 960, 1040))
+///     .apply()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchMove {
+    moves: Vec<(usize, (i32, i32, u32, u32))>,
+}
+
+impl BatchMove {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个窗口及其目标位置尺寸`(x, y, width, height)`（包括阴影，与[`move_window_to_xywh`]一致）。
+    pub fn add(mut self, hwnd: usize, xywh: (i32, i32, u32, u32)) -> Self {
+        self.moves.push((hwnd, xywh));
+        self
+    }
+
+    /// 移动所有窗口。任一窗口不存在时不移动任何窗口。
+    pub fn apply(&self) -> Result<()> {
+        if let Some(&(hwnd, _)) = self.moves.iter().find(|(hwnd, _)| !is_window_exist(*hwnd)) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+        if self.moves.is_empty() || dry_run(format_args!("DeferWindowPos {:?}", self.moves)) {
+            return Ok(());
+        }
+        let mut hdwp = unsafe { BeginDeferWindowPos(self.moves.len() as i32) }
+            .map_err(|e| WindowInspectorError::BeginDeferWindowPosFailed { error: e })?;
+        for &(hwnd, (x, y, width, height)) in &self.moves {
+            // 失败时系统会释放hdwp，不需要调用EndDeferWindowPos。
+            hdwp = unsafe {
+                DeferWindowPos(
+                    hdwp,
+                    HWND(hwnd as *mut c_void),
+                    None,
+                    x,
+                    y,
+                    width as i32,
+                    height as i32,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                )
+            }
+            .map_err(|e| WindowInspectorError::DeferWindowPosFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: e,
+            })?;
+        }
+        unsafe { EndDeferWindowPos(hdwp) }
+            .map_err(|e| WindowInspectorError::EndDeferWindowPosFailed { error: e })
+    }
+}
+
+/// 动画的缓动函数，见[`animate_move`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Easing {
+    /// 匀速。
+    Linear,
+    /// 先慢后快。
+    EaseIn,
+    /// 先快后慢。
+    #[default]
+    EaseOut,
+    /// 两头慢中间快。
+    EaseInOut,
+}
+
+impl Easing {
+    /// 将时间进度`t`（0到1）映射为位置进度（0到1）。使用三次曲线。
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// 正在进行的窗口移动动画，由[`animate_move`]返回。
+/// drop时不会取消动画，动画会在后台继续进行直到完成。
+#[must_use = "drop不会取消动画，需要取消时调用cancel"]
+#[derive(Debug)]
+pub struct Animation {
+    cancelled: Arc<AtomicBool>,
+    thread: JoinHandle<bool>,
+}
+
+impl Animation {
+    /// 停止动画，窗口停在当前位置。
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// 动画是否已经结束（完成、取消或窗口已关闭）。
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// 等待动画结束。到达目标位置时返回`true`，被取消或窗口在动画过程中关闭时返回`false`。
+    pub fn wait(self) -> bool {
+        self.thread.join().unwrap_or(false)
+    }
+}
+
+/// 动画每帧的间隔，约60帧每秒。
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// 在后台线程中用`duration`将窗口平滑地移动到`(x, y, width, height)`（包括阴影，与[`move_window_to_xywh`]一致）。
+pub fn animate_move(
+    hwnd: usize,
+    (x, y, width, height): (i32, i32, u32, u32),
+    duration: Duration,
+    easing: Easing,
+) -> Result<Animation> {
+    let (start_x, start_y, start_width, start_height) = get_window_xywh_include_shadow(hwnd)?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let dry_run = dry_run(format_args!(
+        "animate {:#X} to ({}, {}, {}, {}) in {:?}",
+        hwnd, x, y, width, height, duration
+    ));
+    let thread = {
+        let cancelled = cancelled.clone();
+        thread::spawn(move || {
+            if dry_run {
+                return true;
+            }
+            let lerp = |from: f64, to: f64, progress: f64| (from + (to - from) * progress).round();
+            let start = Instant::now();
+            loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    return false;
+                }
+                let t = if duration.is_zero() {
+                    1.0
+                } else {
+                    start.elapsed().as_secs_f64() / duration.as_secs_f64()
+                };
+                let progress = easing.apply(t);
+                let frame = (
+                    lerp(start_x as f64, x as f64, progress) as i32,
+                    lerp(start_y as f64, y as f64, progress) as i32,
+                    lerp(start_width as f64, width as f64, progress) as u32,
+                    lerp(start_height as f64, height as f64, progress) as u32,
+                );
+                if move_window_to_xywh(hwnd, frame.0, frame.1, frame.2, frame.3).is_err() {
+                    return false;
+                }
+                if t >= 1.0 {
+                    return true;
+                }
+                thread::sleep(FRAME_INTERVAL);
+            }
+        })
+    };
+    Ok(Animation { cancelled, thread })
+}
+
+/// 窗口上与目标位置对齐的点，见[`move_to_relative`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// 锚点在窗口中的相对位置，`(0, 0)`为左上角，`(1, 1)`为右下角。
+    fn fractions(self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// 计算[`move_to_relative`]的目标矩形。
+fn relative_rect(
+    area: Rect,
+    anchor: Anchor,
+    (x_frac, y_frac): (f32, f32),
+    (w_frac, h_frac): (f32, f32),
+) -> Rect {
+    let width = (area.w as f32 * w_frac).round().max(0.0);
+    let height = (area.h as f32 * h_frac).round().max(0.0);
+    let (anchor_x, anchor_y) = anchor.fractions();
+    let x = area.x as f32 + area.w as f32 * x_frac - width * anchor_x;
+    let y = area.y as f32 + area.h as f32 * y_frac - height * anchor_y;
+    Rect::new(
+        x.round() as i32,
+        y.round() as i32,
+        width as u32,
+        height as u32,
+    )
+}
+
+/// 按显示器工作区的比例移动窗口并改变大小（包括阴影，与[`move_window_to_xywh`]一致），与分辨率无关。
+/// 窗口尺寸为工作区尺寸乘以`w_frac`、`h_frac`；窗口的`anchor`点对齐到工作区中`(x_frac, y_frac)`比例处。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::layout::move_to_relative;
+/// use window_inspector::layout::Anchor;
+/// use window_inspector::monitor::get_primary_monitor;
+///
+/// # let hwnd = 0;
+/// // 主显示器右侧三分之一。
+/// move_to_relative(hwnd, get_primary_monitor(), Anchor::TopRight, 1.0, 0.0, 1.0 / 3.0, 1.0).unwrap();
+/// ```
+pub fn move_to_relative(
+    hwnd: usize,
+    monitor: usize,
+    anchor: Anchor,
+    x_frac: f32,
+    y_frac: f32,
+    w_frac: f32,
+    h_frac: f32,
+) -> Result<()> {
+    let area = get_monitor_work_area(monitor)?;
+    let rect = relative_rect(area, anchor, (x_frac, y_frac), (w_frac, h_frac));
+    move_window_to_rect(hwnd, rect)
+}
+
+/// [`PlacementStore`]中的键：指纹中除显示器以外的部分。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PlacementKey {
+    exe_path: String,
+    class: String,
+    aumid: String,
+    title_pattern: String,
+}
+
+impl PlacementKey {
+    fn new(fp: &WindowFingerprint) -> Self {
+        // 文件中用制表符、换行分隔，替换掉字段中的制表符、换行。
+        let field = |s: &str| s.replace(['\t', '\r', '\n'], " ");
+        Self {
+            exe_path: field(&fp.exe_path.to_lowercase()),
+            class: field(&fp.class),
+            aumid: field(fp.aumid.as_deref().unwrap_or_default()),
+            title_pattern: field(&fp.title_pattern),
+        }
+    }
+}
+
+/// [`PlacementStore`]记住的窗口位置。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SavedPlacement {
+    /// 所在显示器的设备名，见[`get_monitor_device_name`]。
+    pub monitor: Option<String>,
+    /// 还原（非最大化、非最小化）时的窗口矩形，相对于显示器左上角。
+    pub rect: Rect,
+    /// 是否最大化。
+    pub maximized: bool,
+}
+
+fn format_line(key: &PlacementKey, placement: &SavedPlacement) -> String {
+    let rect = placement.rect;
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        key.exe_path,
+        key.class,
+        key.aumid,
+        key.title_pattern,
+        placement.monitor.as_deref().unwrap_or_default(),
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        placement.maximized as u8
+    )
+}
+
+fn parse_line(line: &str) -> Option<(PlacementKey, SavedPlacement)> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [exe_path, class, aumid, title_pattern, monitor, x, y, w, h, maximized] = fields[..] else {
+        return None;
+    };
+    let key = PlacementKey {
+        exe_path: exe_path.to_string(),
+        class: class.to_string(),
+        aumid: aumid.to_string(),
+        title_pattern: title_pattern.to_string(),
+    };
+    let placement = SavedPlacement {
+        monitor: (!monitor.is_empty()).then(|| monitor.to_string()),
+        rect: Rect::new(
+            x.parse().ok()?,
+            y.parse().ok()?,
+            w.parse().ok()?,
+            h.parse().ok()?,
+        ),
+        maximized: maximized == "1",
+    };
+    Some((key, placement))
+}
+
+/// `WINDOWPLACEMENT`使用工作区坐标（相对于主显示器工作区左上角），工具窗口除外。
+/// 返回工作区坐标加上它得到屏幕坐标的偏移。
+fn workspace_offset(hwnd: usize) -> Result<(i32, i32)> {
+    let ex_style = unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } as u32;
+    if ex_style & WS_EX_TOOLWINDOW.0 != 0 {
+        return Ok((0, 0));
+    }
+    let primary = get_primary_monitor();
+    let work_area = get_monitor_work_area(primary)?;
+    let monitor_rect = get_monitor_rect(primary)?;
+    Ok((work_area.x - monitor_rect.x, work_area.y - monitor_rect.y))
+}
+
+/// 按应用记住窗口位置，保存在文件中，窗口重新出现时（包括重启后）恢复。
+/// 应用由[`fingerprint`]中除显示器以外的部分区分。
+/// 文件为文本格式，每行一个应用，字段之间用制表符分隔。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::events::on_window_created;
+/// use window_inspector::find::WindowMatcher;
+/// use window_inspector::layout::PlacementStore;
+///
+/// let store = PlacementStore::open("placements.txt").unwrap();
+/// let _guard = on_window_created(WindowMatcher::new().process_name("notepad.exe"), move |hwnd| {
+///     let _ = store.restore_placement(hwnd);
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlacementStore {
+    path: PathBuf,
+    placements: HashMap<PlacementKey, SavedPlacement>,
+}
+
+impl PlacementStore {
+    /// 从文件读取记住的位置，文件不存在时为空。无法解析的行会被忽略。
+    pub fn open(path: impl AsRef<Path>) -> Result<PlacementStore> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(WindowInspectorError::ReadFileFailed {
+                    path: path.display().to_string(),
+                    error: e,
+                })
+            }
+        };
+        Ok(PlacementStore {
+            path,
+            placements: text.lines().filter_map(parse_line).collect(),
+        })
+    }
+
+    /// 获取指纹对应的应用记住的位置。
+    pub fn get(&self, fp: &WindowFingerprint) -> Option<&SavedPlacement> {
+        self.placements.get(&PlacementKey::new(fp))
+    }
+
+    /// 记住窗口当前的位置，并写入文件。通常在窗口关闭前或移动后调用。
+    pub fn remember(&mut self, hwnd: usize) -> Result<()> {
+        let fp = fingerprint(hwnd)?;
+        let mut placement = WINDOWPLACEMENT {
+            length: size_of::<WINDOWPLACEMENT>() as u32,
+            ..Default::default()
+        };
+        unsafe { GetWindowPlacement(HWND(hwnd as *mut c_void), &mut placement) }.map_err(|e| {
+            WindowInspectorError::GetWindowPlacementFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: e,
+            }
+        })?;
+        let (dx, dy) = workspace_offset(hwnd)?;
+        let rect = Rect::from(placement.rcNormalPosition).offset(dx, dy);
+        let monitor = get_window_monitor(hwnd)?;
+        let show_cmd = SHOW_WINDOW_CMD(placement.showCmd as i32);
+        let maximized = show_cmd == SW_SHOWMAXIMIZED
+            || (show_cmd == SW_SHOWMINIMIZED && placement.flags.contains(WPF_RESTORETOMAXIMIZED));
+        self.placements.insert(
+            PlacementKey::new(&fp),
+            SavedPlacement {
+                monitor: fp.monitor,
+                rect: to_monitor_coords(rect, monitor)?,
+                maximized,
+            },
+        );
+        self.save()
+    }
+
+    /// 把记住的位置应用到窗口，没有记住的位置时返回`false`。
+    /// 原来的显示器不存在时，恢复到窗口当前所在显示器上的相同相对位置。会显示并激活窗口。
+    pub fn restore_placement(&self, hwnd: usize) -> Result<bool> {
+        let Some(saved) = self.get(&fingerprint(hwnd)?) else {
+            return Ok(false);
+        };
+        let monitor = match get_monitors()?.into_iter().find(|&monitor| {
+            saved.monitor.is_some() && get_monitor_device_name(monitor).ok() == saved.monitor
+        }) {
+            Some(monitor) => monitor,
+            None => get_window_monitor(hwnd)?,
+        };
+        let (dx, dy) = workspace_offset(hwnd)?;
+        let rect = from_monitor_coords(saved.rect, monitor)?.offset(-dx, -dy);
+        if dry_run(format_args!("SetWindowPlacement {:#X} {:?}", hwnd, saved)) {
+            return Ok(true);
+        }
+        let placement = WINDOWPLACEMENT {
+            length: size_of::<WINDOWPLACEMENT>() as u32,
+            showCmd: if saved.maximized {
+                SW_SHOWMAXIMIZED.0 as u32
+            } else {
+                SW_SHOWNORMAL.0 as u32
+            },
+            rcNormalPosition: rect.into(),
+            ..Default::default()
+        };
+        unsafe { SetWindowPlacement(HWND(hwnd as *mut c_void), &placement) }.map_err(|e| {
+            WindowInspectorError::SetWindowPlacementFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: e,
+            }
+        })?;
+        Ok(true)
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut text = String::new();
+        for (key, placement) in &self.placements {
+            text.push_str(&format_line(key, placement));
+            text.push('\n');
+        }
+        fs::write(&self.path, text).map_err(|e| WindowInspectorError::WriteFileFailed {
+            path: self.path.display().to_string(),
+            error: e,
+        })
+    }
+}
+
+#[test]
+fn test_relative_rect() {
+    let area = Rect::new(1920, 0, 1920, 1040);
+    assert_eq!(
+        relative_rect(area, Anchor::TopRight, (1.0, 0.0), (0.25, 1.0)),
+        Rect::new(3360, 0, 480, 1040)
+    );
+    assert_eq!(
+        relative_rect(area, Anchor::Center, (0.5, 0.5), (0.5, 0.5)),
+        Rect::new(2400, 260, 960, 520)
+    );
+}
+
+#[test]
+fn test_lock_position() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let hwnd = window.hwnd();
+    let guard = lock_position(hwnd, (150, 150, 400, 300)).unwrap();
+    move_window_to_xywh(hwnd, 300, 300, 200, 200).unwrap();
+    thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(
+        get_window_xywh_include_shadow(hwnd).unwrap(),
+        (150, 150, 400, 300)
+    );
+    drop(guard);
+    move_window_to_xywh(hwnd, 300, 300, 200, 200).unwrap();
+    thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(
+        get_window_xywh_include_shadow(hwnd).unwrap(),
+        (300, 300, 200, 200)
+    );
+}
+
+#[test]
+fn test_placement_line() {
+    let key = PlacementKey {
+        exe_path: r"c:\windows\notepad.exe".to_string(),
+        class: "Notepad".to_string(),
+        aumid: String::new(),
+        title_pattern: "记事本".to_string(),
+    };
+    let placement = SavedPlacement {
+        monitor: Some(r"\\.\DISPLAY1".to_string()),
+        rect: Rect::new(-10, 20, 800, 600),
+        maximized: true,
+    };
+    assert_eq!(
+        parse_line(&format_line(&key, &placement)),
+        Some((key, placement))
+    );
+    assert_eq!(parse_line("invalid"), None);
+}
+
+#[test]
+fn test_placement_store_untitled() {
+    use crate::test_support::TestWindowBuilder;
+
+    let path = std::env::temp_dir().join(format!(
+        "window_inspector_test_placement_{}.txt",
+        std::process::id()
+    ));
+    let window = TestWindowBuilder::new()
+        .xywh(100, 100, 400, 300)
+        .build()
+        .unwrap();
+    let hwnd = window.hwnd();
+    let mut store = PlacementStore::open(&path).unwrap();
+    store.remember(hwnd).unwrap();
+    move_window_to_xywh(hwnd, 300, 300, 200, 200).unwrap();
+    let store = PlacementStore::open(&path).unwrap();
+    assert!(store.restore_placement(hwnd).unwrap());
+    assert_eq!(
+        get_window_xywh_include_shadow(hwnd).unwrap(),
+        (100, 100, 400, 300)
+    );
+    let _ = fs::remove_file(&path);
+}
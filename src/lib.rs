@@ -3,9 +3,49 @@
 pub mod class_title;
 pub mod exist;
 pub mod foreground;
+pub mod focus;
 pub mod find;
+pub mod geometry;
 pub mod position_size;
+pub mod monitor;
 pub mod process;
 pub mod top_most;
+pub mod z_order;
+pub mod style;
+pub mod operation;
+pub mod state;
+pub mod information;
+pub mod controls;
+pub mod menu;
+pub mod enumerate;
+pub mod snapshot;
+pub mod stats;
+pub mod launch;
+pub mod usage;
+pub mod shell;
+pub mod retry;
+pub mod mode;
+pub mod backend;
+pub mod picker;
+pub mod debug_draw;
+pub mod capture;
+pub mod hotkey;
+pub mod events;
+pub mod poll;
+pub mod layout;
+pub mod overlay;
+pub mod history;
+pub mod identity;
+pub mod rules;
+pub mod system;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_support;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod error;
 pub mod result;
+mod com;
+mod message_loop;
+mod wide;
+
+pub use widestring;
@@ -0,0 +1,213 @@
+//! 读取、操作其他窗口的菜单栏。只适用于使用系统菜单（`HMENU`）的传统Win32程序。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::GetMenu;
+use windows::Win32::UI::WindowsAndMessaging::GetMenuItemCount;
+use windows::Win32::UI::WindowsAndMessaging::GetMenuItemInfoW;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::HMENU;
+use windows::Win32::UI::WindowsAndMessaging::MENUITEMINFOW;
+use windows::Win32::UI::WindowsAndMessaging::MFS_CHECKED;
+use windows::Win32::UI::WindowsAndMessaging::MFS_DEFAULT;
+use windows::Win32::UI::WindowsAndMessaging::MFS_DISABLED;
+use windows::Win32::UI::WindowsAndMessaging::MFT_SEPARATOR;
+use windows::Win32::UI::WindowsAndMessaging::MIIM_FTYPE;
+use windows::Win32::UI::WindowsAndMessaging::MIIM_ID;
+use windows::Win32::UI::WindowsAndMessaging::MIIM_STATE;
+use windows::Win32::UI::WindowsAndMessaging::MIIM_STRING;
+use windows::Win32::UI::WindowsAndMessaging::MIIM_SUBMENU;
+use windows::Win32::UI::WindowsAndMessaging::WM_COMMAND;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::result::Result;
+
+/// 菜单项，见[`get_window_menu_tree`]。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MenuItem {
+    /// 命令ID，点击时通过`WM_COMMAND`发送给窗口。有子菜单的项没有意义。
+    pub id: u32,
+    /// 原始文本，包含助记符`&`和制表符后的快捷键，例如`"&Open...\tCtrl+O"`。
+    pub text: String,
+    /// 是否是分隔线。
+    pub separator: bool,
+    /// 是否勾选。
+    pub checked: bool,
+    /// 是否禁用（灰色）。
+    pub disabled: bool,
+    /// 是否是默认项（粗体）。
+    pub default: bool,
+    /// 子菜单，没有子菜单时为空。
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    /// 去掉助记符和快捷键后的文本，例如`"&Open...\tCtrl+O"`返回`"Open..."`。
+    pub fn label(&self) -> String {
+        let text = self.text.split('\t').next().unwrap_or_default();
+        let mut label = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '&' {
+                // `&&`表示字面的`&`。
+                if let Some(next) = chars.next() {
+                    label.push(next);
+                }
+            } else {
+                label.push(c);
+            }
+        }
+        label
+    }
+
+    /// 是否有子菜单。
+    pub fn has_children(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+fn get_menu_item(menu: HMENU, position: u32) -> Option<MenuItem> {
+    let mut info = MENUITEMINFOW {
+        cbSize: size_of::<MENUITEMINFOW>() as u32,
+        fMask: MIIM_FTYPE | MIIM_STATE | MIIM_ID | MIIM_SUBMENU | MIIM_STRING,
+        ..Default::default()
+    };
+    // 第一次获取文本长度，第二次获取文本。
+    unsafe { GetMenuItemInfoW(menu, position, true, &mut info) }.ok()?;
+    let mut buffer = vec![0u16; info.cch as usize + 1];
+    if info.cch > 0 {
+        info.dwTypeData = PWSTR(buffer.as_mut_ptr());
+        info.cch += 1;
+        unsafe { GetMenuItemInfoW(menu, position, true, &mut info) }.ok()?;
+    }
+    let length = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(MenuItem {
+        id: info.wID,
+        text: String::from_utf16_lossy(&buffer[..length]),
+        separator: info.fType.0 & MFT_SEPARATOR.0 != 0,
+        checked: info.fState.0 & MFS_CHECKED.0 != 0,
+        disabled: info.fState.0 & MFS_DISABLED.0 != 0,
+        default: info.fState.0 & MFS_DEFAULT.0 != 0,
+        children: if info.hSubMenu.is_invalid() {
+            Vec::new()
+        } else {
+            get_menu_items(info.hSubMenu)
+        },
+    })
+}
+
+fn get_menu_items(menu: HMENU) -> Vec<MenuItem> {
+    let count = unsafe { GetMenuItemCount(menu) }.max(0) as u32;
+    (0..count)
+        .filter_map(|position| get_menu_item(menu, position))
+        .collect()
+}
+
+/// 获取窗口菜单栏的所有菜单项，窗口没有菜单栏时返回空。
+/// 有些程序在弹出子菜单时（`WM_INITMENUPOPUP`）才填充或更新菜单项，此时读取到的可能不完整或不是最新状态。
+pub fn get_window_menu_tree(hwnd: usize) -> Result<Vec<MenuItem>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let menu = unsafe { GetMenu(HWND(hwnd as *mut c_void)) };
+    if menu.is_invalid() {
+        return Ok(Vec::new());
+    }
+    Ok(get_menu_items(menu))
+}
+
+/// 按路径查找菜单项，路径的每一级与[`MenuItem::label`]比较。
+fn find_menu_item<'a>(items: &'a [MenuItem], path: &[&str]) -> Option<&'a MenuItem> {
+    let (first, rest) = path.split_first()?;
+    let item = items
+        .iter()
+        .find(|item| !item.separator && item.label() == *first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        find_menu_item(&item.children, rest)
+    }
+}
+
+/// 点击窗口菜单栏中的菜单项（发送`WM_COMMAND`），不等待窗口处理。
+/// `path`是各级菜单项去掉助记符和快捷键后的文本，例如`&["File", "Save As..."]`。
+/// 菜单项不存在、有子菜单或被禁用时返回错误。
+pub fn click_menu_item(hwnd: usize, path: &[&str]) -> Result<()> {
+    let items = get_window_menu_tree(hwnd)?;
+    let item = find_menu_item(&items, path)
+        .filter(|item| !item.has_children())
+        .ok_or_else(|| WindowInspectorError::MenuItemNotFound {
+            hwnd: HWND(hwnd as *mut c_void),
+            path: path.join(" > "),
+        })?;
+    if item.disabled {
+        return Err(WindowInspectorError::MenuItemDisabled {
+            hwnd: HWND(hwnd as *mut c_void),
+            path: path.join(" > "),
+        });
+    }
+    if dry_run(format_args!(
+        "PostMessageW {:#X} WM_COMMAND {}",
+        hwnd, item.id
+    )) {
+        return Ok(());
+    }
+    // 高位为0表示来自菜单。
+    unsafe {
+        PostMessageW(
+            HWND(hwnd as *mut c_void),
+            WM_COMMAND,
+            WPARAM(item.id as u16 as usize),
+            LPARAM::default(),
+        )
+    }
+    .map_err(|e| WindowInspectorError::PostMessageWFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+}
+
+#[test]
+fn test_find_menu_item() {
+    let items = vec![MenuItem {
+        text: "&File".to_string(),
+        children: vec![
+            MenuItem {
+                id: 1,
+                text: "&Open...\tCtrl+O".to_string(),
+                ..Default::default()
+            },
+            MenuItem {
+                separator: true,
+                ..Default::default()
+            },
+            MenuItem {
+                id: 2,
+                text: "Save && E&xit".to_string(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }];
+    assert_eq!(find_menu_item(&items, &["File", "Open..."]).unwrap().id, 1);
+    assert_eq!(
+        find_menu_item(&items, &["File", "Save & Exit"]).unwrap().id,
+        2
+    );
+    assert!(find_menu_item(&items, &["File", "Close"]).is_none());
+    assert!(find_menu_item(&items, &[]).is_none());
+}
@@ -0,0 +1,93 @@
+//! 后台消息循环。钩子、热键、仅消息窗口等都需要一个处理消息的线程。
+
+use std::sync::mpsc;
+use std::thread;
+use std::thread::JoinHandle;
+
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::DispatchMessageW;
+use windows::Win32::UI::WindowsAndMessaging::GetMessageW;
+use windows::Win32::UI::WindowsAndMessaging::PeekMessageW;
+use windows::Win32::UI::WindowsAndMessaging::PostThreadMessageW;
+use windows::Win32::UI::WindowsAndMessaging::TranslateMessage;
+use windows::Win32::UI::WindowsAndMessaging::MSG;
+use windows::Win32::UI::WindowsAndMessaging::PM_NOREMOVE;
+use windows::Win32::UI::WindowsAndMessaging::WM_QUIT;
+
+/// 处理当前线程的消息，直到收到`WM_QUIT`。
+/// `on_thread_message`处理没有目标窗口的线程消息（例如`WM_HOTKEY`），其他消息正常分发。
+pub(crate) fn run_message_loop(mut on_thread_message: impl FnMut(&MSG)) {
+    let mut msg = MSG::default();
+    // GetMessageW出错时返回-1，也应结束循环。
+    while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {
+        if msg.hwnd.is_invalid() {
+            on_thread_message(&msg);
+        }
+        let _ = unsafe { TranslateMessage(&msg) };
+        unsafe { DispatchMessageW(&msg) };
+    }
+}
+
+/// 运行消息循环的后台线程。drop时结束消息循环并等待线程退出。
+#[derive(Debug)]
+pub(crate) struct MessageThread {
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MessageThread {
+    /// 启动线程：先在线程中调用`setup`（例如安装钩子、注册热键），然后运行消息循环，
+    /// 结束时用`setup`的返回值调用`teardown`。`setup`完成后才返回。
+    /// [`crate::error::WindowInspectorError`]包含不能跨线程传递的句柄，`setup`的错误需要使用其他类型。
+    pub(crate) fn spawn<S: 'static, E: Send + 'static>(
+        setup: impl FnOnce() -> std::result::Result<S, E> + Send + 'static,
+        on_thread_message: impl FnMut(&MSG) + Send + 'static,
+        teardown: impl FnOnce(S) + Send + 'static,
+    ) -> std::result::Result<MessageThread, E> {
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            // 创建消息队列，保证返回后PostThreadMessageW能成功。
+            let mut msg = MSG::default();
+            let _ = unsafe { PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE) };
+            let state = match setup() {
+                Ok(state) => state,
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    return;
+                }
+            };
+            let _ = sender.send(Ok(unsafe { GetCurrentThreadId() }));
+            run_message_loop(on_thread_message);
+            teardown(state);
+        });
+        match receiver.recv() {
+            Ok(Ok(thread_id)) => Ok(MessageThread {
+                thread_id,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => panic!("消息循环线程意外退出"),
+        }
+    }
+}
+
+impl Drop for MessageThread {
+    fn drop(&mut self) {
+        let _ = unsafe {
+            PostThreadMessageW(
+                self.thread_id,
+                WM_QUIT,
+                WPARAM::default(),
+                LPARAM::default(),
+            )
+        };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::Arguments;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// 操作模式，影响所有会修改窗口或进程的函数。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OperationMode {
+    /// 正常执行。
+    #[default]
+    Normal,
+    /// 只检查前提条件（例如窗口是否存在、能否打开进程），并通过[`log`]以`info`级别记录将要执行的操作，不实际执行。
+    /// 用于在真实的桌面上安全地测试窗口管理规则。
+    /// 无法模拟结果的操作（例如启动程序）会返回[`WindowInspectorError::DryRun`]。
+    ///
+    /// [`log`]: https://docs.rs/log
+    /// [`WindowInspectorError::DryRun`]: crate::error::WindowInspectorError::DryRun
+    DryRun,
+}
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// 设置操作模式，对所有线程生效。
+pub fn set_operation_mode(mode: OperationMode) {
+    DRY_RUN.store(mode == OperationMode::DryRun, Ordering::Relaxed);
+}
+
+/// 获取当前的操作模式。
+pub fn get_operation_mode() -> OperationMode {
+    if DRY_RUN.load(Ordering::Relaxed) {
+        OperationMode::DryRun
+    } else {
+        OperationMode::Normal
+    }
+}
+
+/// 如果处于[`OperationMode::DryRun`]，记录将要执行的操作并返回`true`，调用者应跳过实际操作。
+pub(crate) fn dry_run(action: Arguments) -> bool {
+    if get_operation_mode() != OperationMode::DryRun {
+        return false;
+    }
+    log::info!("dry run: {}", action);
+    true
+}
+
+/// 窗口存在性检查模式，影响获取类名、标题、位置尺寸、置顶状态等常用的查询函数。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Validation {
+    /// 调用前先用`IsWindow`检查窗口是否存在。
+    #[default]
+    Eager,
+    /// 直接调用，失败时才检查窗口是否存在，窗口已关闭时仍然返回[`WindowInspectorError::WindowNotExist`]。
+    /// 窗口存在时省去一次`IsWindow`，适合频繁轮询的场景。
+    ///
+    /// [`WindowInspectorError::WindowNotExist`]: crate::error::WindowInspectorError::WindowNotExist
+    Lazy,
+}
+
+static LAZY_VALIDATION: AtomicBool = AtomicBool::new(false);
+
+/// 设置窗口存在性检查模式，对所有线程生效。
+pub fn set_validation(validation: Validation) {
+    LAZY_VALIDATION.store(validation == Validation::Lazy, Ordering::Relaxed);
+}
+
+/// 获取当前的窗口存在性检查模式。
+pub fn get_validation() -> Validation {
+    if LAZY_VALIDATION.load(Ordering::Relaxed) {
+        Validation::Lazy
+    } else {
+        Validation::Eager
+    }
+}
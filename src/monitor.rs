@@ -0,0 +1,156 @@
+//! 显示器。显示器句柄（`HMONITOR`）与窗口句柄一样用`usize`表示。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::POINT;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
+use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
+use windows::Win32::Graphics::Gdi::MonitorFromPoint;
+use windows::Win32::Graphics::Gdi::MonitorFromWindow;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::Graphics::Gdi::MONITORINFO;
+use windows::Win32::Graphics::Gdi::MONITORINFOEXW;
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTOPRIMARY;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::geometry::Rect;
+use crate::position_size::get_window_rect_include_shadow;
+use crate::result::Result;
+
+unsafe extern "system" fn enum_monitors_proc(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    lparam: LPARAM,
+) -> BOOL {
+    let monitors = &mut *(lparam.0 as *mut Vec<usize>);
+    monitors.push(monitor.0 as usize);
+    true.into()
+}
+
+/// 获取所有显示器。
+pub fn get_monitors() -> Result<Vec<usize>> {
+    let mut monitors: Vec<usize> = Vec::new();
+    if !unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(enum_monitors_proc),
+            LPARAM(&mut monitors as *mut Vec<usize> as isize),
+        )
+    }
+    .as_bool()
+    {
+        return Err(WindowInspectorError::EnumDisplayMonitorsFailed {
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(monitors)
+}
+
+/// 获取主显示器。
+pub fn get_primary_monitor() -> usize {
+    unsafe { MonitorFromPoint(POINT::default(), MONITOR_DEFAULTTOPRIMARY) }.0 as usize
+}
+
+/// 获取窗口所在的显示器。窗口跨越多个显示器时返回与窗口相交面积最大的显示器，不在任何显示器上时返回最近的显示器。
+pub fn get_window_monitor(hwnd: usize) -> Result<usize> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    Ok(
+        unsafe { MonitorFromWindow(HWND(hwnd as *mut c_void), MONITOR_DEFAULTTONEAREST) }.0
+            as usize,
+    )
+}
+
+fn get_monitor_info(monitor: usize) -> Result<MONITORINFO> {
+    let mut info = MONITORINFO {
+        cbSize: size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !unsafe { GetMonitorInfoW(HMONITOR(monitor as *mut c_void), &mut info) }.as_bool() {
+        return Err(WindowInspectorError::GetMonitorInfoWFailed {
+            monitor,
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(info)
+}
+
+/// 获取显示器的矩形，相对于屏幕（虚拟桌面）。
+pub fn get_monitor_rect(monitor: usize) -> Result<Rect> {
+    get_monitor_info(monitor).map(|info| info.rcMonitor.into())
+}
+
+/// 获取显示器的工作区（不包括任务栏等停靠栏），相对于屏幕（虚拟桌面）。
+pub fn get_monitor_work_area(monitor: usize) -> Result<Rect> {
+    get_monitor_info(monitor).map(|info| info.rcWork.into())
+}
+
+/// 获取显示器的设备名，例如`\\.\DISPLAY1`。
+/// 显示器句柄在重新插拔、重启后会改变，设备名通常不变，适合保存下来。
+pub fn get_monitor_device_name(monitor: usize) -> Result<String> {
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = size_of::<MONITORINFOEXW>() as u32;
+    if !unsafe { GetMonitorInfoW(HMONITOR(monitor as *mut c_void), &mut info.monitorInfo) }
+        .as_bool()
+    {
+        return Err(WindowInspectorError::GetMonitorInfoWFailed {
+            monitor,
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    let len = info
+        .szDevice
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(info.szDevice.len());
+    Ok(String::from_utf16_lossy(&info.szDevice[..len]))
+}
+
+/// 将屏幕（虚拟桌面）坐标中的矩形转换为相对于显示器左上角的坐标。
+/// 保存相对坐标后，即使显示器排列改变（例如主显示器切换、显示器位置调整），也能用[`from_monitor_coords`]恢复到同一显示器上的同一位置。
+pub fn to_monitor_coords(rect: Rect, monitor: usize) -> Result<Rect> {
+    let monitor_rect = get_monitor_rect(monitor)?;
+    Ok(rect.offset(-monitor_rect.x, -monitor_rect.y))
+}
+
+/// 将相对于显示器左上角的矩形转换为屏幕（虚拟桌面）坐标。[`to_monitor_coords`]的逆运算。
+pub fn from_monitor_coords(rect: Rect, monitor: usize) -> Result<Rect> {
+    let monitor_rect = get_monitor_rect(monitor)?;
+    Ok(rect.offset(monitor_rect.x, monitor_rect.y))
+}
+
+/// 获取窗口所在的显示器，以及窗口（包括阴影）相对于该显示器左上角的矩形。
+/// # 返回
+/// (monitor, rect)
+pub fn get_window_monitor_coords(hwnd: usize) -> Result<(usize, Rect)> {
+    let monitor = get_window_monitor(hwnd)?;
+    let rect = to_monitor_coords(get_window_rect_include_shadow(hwnd)?, monitor)?;
+    Ok((monitor, rect))
+}
+
+#[test]
+fn test_get_monitors() {
+    let monitors = get_monitors().unwrap();
+    assert!(monitors.contains(&get_primary_monitor()));
+    for monitor in monitors {
+        let rect = get_monitor_rect(monitor).unwrap();
+        assert!(rect.contains_rect(&get_monitor_work_area(monitor).unwrap()));
+    }
+    let primary = get_primary_monitor();
+    let rect = Rect::new(100, 100, 400, 300);
+    let relative = to_monitor_coords(rect, primary).unwrap();
+    assert_eq!(from_monitor_coords(relative, primary).unwrap(), rect);
+}
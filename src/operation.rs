@@ -0,0 +1,403 @@
+//! 对窗口的一般操作。
+
+use std::ffi::c_void;
+use std::time::Duration;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Gdi::RedrawWindow;
+use windows::Win32::Graphics::Gdi::HRGN;
+use windows::Win32::Graphics::Gdi::RDW_ALLCHILDREN;
+use windows::Win32::Graphics::Gdi::RDW_ERASE;
+use windows::Win32::Graphics::Gdi::RDW_FRAME;
+use windows::Win32::Graphics::Gdi::RDW_INVALIDATE;
+use windows::Win32::Graphics::Gdi::RDW_UPDATENOW;
+use windows::Win32::UI::WindowsAndMessaging::AnimateWindow;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::ANIMATE_WINDOW_FLAGS;
+use windows::Win32::UI::WindowsAndMessaging::AW_BLEND;
+use windows::Win32::UI::WindowsAndMessaging::AW_CENTER;
+use windows::Win32::UI::WindowsAndMessaging::AW_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::AW_HOR_NEGATIVE;
+use windows::Win32::UI::WindowsAndMessaging::AW_HOR_POSITIVE;
+use windows::Win32::UI::WindowsAndMessaging::AW_SLIDE;
+use windows::Win32::UI::WindowsAndMessaging::AW_VER_NEGATIVE;
+use windows::Win32::UI::WindowsAndMessaging::AW_VER_POSITIVE;
+use windows::Win32::UI::WindowsAndMessaging::SC_MOVE;
+use windows::Win32::UI::WindowsAndMessaging::SC_SIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_BOTTOM;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_BOTTOMLEFT;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_BOTTOMRIGHT;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_LEFT;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_RIGHT;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_TOP;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_TOPLEFT;
+use windows::Win32::UI::WindowsAndMessaging::WMSZ_TOPRIGHT;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WM_SYSCOMMAND;
+
+use crate::enumerate::get_process_windows;
+use crate::enumerate::get_top_level_windows;
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::find::WindowMatcher;
+use crate::geometry::ClientRect;
+use crate::mode::dry_run;
+use crate::position_size::move_window_to_xywh;
+use crate::result::Result;
+use crate::top_most::cancel_window_top_most;
+use crate::top_most::set_window_top_most;
+
+/// 滑动方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+}
+
+/// 显示、隐藏窗口的动画效果，见[`show_window_animated`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Effect {
+    /// 滑入、滑出。
+    Slide(Direction),
+    /// 淡入、淡出。只适用于顶层窗口。
+    Fade,
+    /// 从中心展开、向中心收缩。
+    Collapse,
+}
+
+impl Effect {
+    fn flags(self) -> ANIMATE_WINDOW_FLAGS {
+        match self {
+            Effect::Slide(direction) => {
+                AW_SLIDE
+                    | match direction {
+                        Direction::LeftToRight => AW_HOR_POSITIVE,
+                        Direction::RightToLeft => AW_HOR_NEGATIVE,
+                        Direction::TopToBottom => AW_VER_POSITIVE,
+                        Direction::BottomToTop => AW_VER_NEGATIVE,
+                    }
+            }
+            Effect::Fade => AW_BLEND,
+            Effect::Collapse => AW_CENTER,
+        }
+    }
+}
+
+fn animate_window(hwnd: usize, effect: Effect, duration: Duration, hide: bool) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "AnimateWindow {:#X} {:?} {:?} hide: {}",
+        hwnd, effect, duration, hide
+    )) {
+        return Ok(());
+    }
+    let mut flags = effect.flags();
+    if hide {
+        flags |= AW_HIDE;
+    }
+    let time = duration.as_millis().min(u32::MAX as u128) as u32;
+    unsafe { AnimateWindow(HWND(hwnd as *mut c_void), time, flags) }.map_err(|e| {
+        WindowInspectorError::AnimateWindowFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        }
+    })
+}
+
+/// 用动画效果显示窗口，不会激活窗口。阻塞直到动画结束。
+/// 窗口已经可见时会失败。窗口需要正确处理`WM_PRINT`/`WM_PRINTCLIENT`，否则动画过程中可能显示为空白。
+pub fn show_window_animated(hwnd: usize, effect: Effect, duration: Duration) -> Result<()> {
+    animate_window(hwnd, effect, duration, false)
+}
+
+/// 用动画效果隐藏窗口。阻塞直到动画结束。窗口已经隐藏时会失败。
+pub fn hide_window_animated(hwnd: usize, effect: Effect, duration: Duration) -> Result<()> {
+    animate_window(hwnd, effect, duration, true)
+}
+
+/// 最小化窗口。
+pub fn minimize_window(hwnd: usize) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!("ShowWindow {:#X} SW_MINIMIZE", hwnd)) {
+        return Ok(());
+    }
+    // 返回值表示窗口之前是否可见，不表示成功与否。
+    let _ = unsafe { ShowWindow(HWND(hwnd as *mut c_void), SW_MINIMIZE) };
+    Ok(())
+}
+
+/// 还原最小化或最大化的窗口，会激活窗口。
+pub fn restore_window(hwnd: usize) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!("ShowWindow {:#X} SW_RESTORE", hwnd)) {
+        return Ok(());
+    }
+    let _ = unsafe { ShowWindow(HWND(hwnd as *mut c_void), SW_RESTORE) };
+    Ok(())
+}
+
+/// 最小化进程所有可见的顶层窗口，返回每个窗口的结果。不可见的窗口不会被处理，否则会被显示出来。
+pub fn minimize_process_windows(process_id: u32) -> Result<Vec<(usize, Result<()>)>> {
+    Ok(get_process_windows(process_id)?
+        .into_iter()
+        .filter(|&hwnd| {
+            unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool()
+                && !unsafe { IsIconic(HWND(hwnd as *mut c_void)) }.as_bool()
+        })
+        .map(|hwnd| (hwnd, minimize_window(hwnd)))
+        .collect())
+}
+
+/// 还原进程所有可见且最小化的顶层窗口，返回每个窗口的结果。
+pub fn restore_process_windows(process_id: u32) -> Result<Vec<(usize, Result<()>)>> {
+    Ok(get_process_windows(process_id)?
+        .into_iter()
+        .filter(|&hwnd| {
+            unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool()
+                && unsafe { IsIconic(HWND(hwnd as *mut c_void)) }.as_bool()
+        })
+        .map(|hwnd| (hwnd, restore_window(hwnd)))
+        .collect())
+}
+
+/// 请求关闭窗口（发送`WM_CLOSE`），不等待窗口关闭。程序可能弹出保存提示或拒绝关闭。
+pub fn close_window(hwnd: usize) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!("PostMessageW {:#X} WM_CLOSE", hwnd)) {
+        return Ok(());
+    }
+    unsafe {
+        PostMessageW(
+            HWND(hwnd as *mut c_void),
+            WM_CLOSE,
+            WPARAM::default(),
+            LPARAM::default(),
+        )
+    }
+    .map_err(|e| WindowInspectorError::PostMessageWFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+}
+
+/// 调整窗口大小时拖动的边或角，见[`begin_user_resize`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeEdge {
+    Left,
+    Right,
+    Top,
+    TopLeft,
+    TopRight,
+    Bottom,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ResizeEdge {
+    fn wmsz(self) -> u32 {
+        match self {
+            ResizeEdge::Left => WMSZ_LEFT,
+            ResizeEdge::Right => WMSZ_RIGHT,
+            ResizeEdge::Top => WMSZ_TOP,
+            ResizeEdge::TopLeft => WMSZ_TOPLEFT,
+            ResizeEdge::TopRight => WMSZ_TOPRIGHT,
+            ResizeEdge::Bottom => WMSZ_BOTTOM,
+            ResizeEdge::BottomLeft => WMSZ_BOTTOMLEFT,
+            ResizeEdge::BottomRight => WMSZ_BOTTOMRIGHT,
+        }
+    }
+}
+
+fn post_sys_command(hwnd: usize, command: u32) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "PostMessageW {:#X} WM_SYSCOMMAND {:#X}",
+        hwnd, command
+    )) {
+        return Ok(());
+    }
+    unsafe {
+        PostMessageW(
+            HWND(hwnd as *mut c_void),
+            WM_SYSCOMMAND,
+            WPARAM(command as usize),
+            LPARAM::default(),
+        )
+    }
+    .map_err(|e| WindowInspectorError::PostMessageWFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+}
+
+/// 让用户移动窗口，效果与窗口菜单中的“移动”相同：之后移动鼠标或按方向键会移动窗口，单击或按回车确认，按Esc取消。
+/// 不等待用户完成。最大化、最小化的窗口无法移动。
+pub fn begin_user_move(hwnd: usize) -> Result<()> {
+    post_sys_command(hwnd, SC_MOVE)
+}
+
+/// 让用户从`edge`调整窗口大小，效果与窗口菜单中的“大小”相同：之后移动鼠标或按方向键会调整大小，单击或按回车确认，按Esc取消。
+/// 不等待用户完成。不可调整大小的窗口没有效果。
+pub fn begin_user_resize(hwnd: usize, edge: ResizeEdge) -> Result<()> {
+    post_sys_command(hwnd, SC_SIZE | edge.wmsz())
+}
+
+/// [`redraw_window`]的选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RedrawOptions {
+    /// 只重绘客户区中的这个矩形，`None`表示整个客户区。
+    pub rect: Option<ClientRect>,
+    /// 重绘前擦除背景（`WM_ERASEBKGND`）。
+    pub erase: bool,
+    /// 同时重绘非客户区（标题栏、边框），修改样式后需要。
+    pub frame: bool,
+    /// 同时重绘所有子窗口。
+    pub all_children: bool,
+    /// 立即重绘，否则等窗口下次处理消息时重绘。
+    pub update_now: bool,
+}
+
+impl Default for RedrawOptions {
+    /// 立即重绘整个窗口，包括非客户区和子窗口。
+    fn default() -> Self {
+        Self {
+            rect: None,
+            erase: true,
+            frame: true,
+            all_children: true,
+            update_now: true,
+        }
+    }
+}
+
+/// 重绘窗口。修改样式、区域、边框等之后，窗口可能残留旧的画面，可以用这个函数清除。
+/// 是[`RedrawWindow`]的封装。
+///
+/// [`RedrawWindow`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/Graphics/Gdi/fn.RedrawWindow.html
+pub fn redraw_window(hwnd: usize, options: RedrawOptions) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!("RedrawWindow {:#X} {:?}", hwnd, options)) {
+        return Ok(());
+    }
+    let mut flags = RDW_INVALIDATE;
+    if options.erase {
+        flags |= RDW_ERASE;
+    }
+    if options.frame {
+        flags |= RDW_FRAME;
+    }
+    if options.all_children {
+        flags |= RDW_ALLCHILDREN;
+    }
+    if options.update_now {
+        flags |= RDW_UPDATENOW;
+    }
+    let rect = options.rect.map(|rect| RECT::from(rect.0));
+    let ok = unsafe {
+        RedrawWindow(
+            HWND(hwnd as *mut c_void),
+            rect.as_ref().map(|rect| rect as *const RECT),
+            HRGN::default(),
+            flags,
+        )
+    };
+    if !ok.as_bool() {
+        return Err(WindowInspectorError::RedrawWindowFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(())
+}
+
+/// 对窗口的操作，见[`for_each_matching`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// 见[`close_window`]。
+    Close,
+    /// 移动到`(x, y, width, height)`，见[`move_window_to_xywh`]。
+    Move((i32, i32, u32, u32)),
+    /// 设置（`true`）或取消（`false`）置顶。
+    TopMost(bool),
+    /// 见[`minimize_window`]。
+    Minimize,
+    /// 见[`restore_window`]。
+    Restore,
+}
+
+impl Op {
+    /// 对窗口执行操作。
+    pub fn apply(self, hwnd: usize) -> Result<()> {
+        match self {
+            Op::Close => close_window(hwnd),
+            Op::Move((x, y, width, height)) => move_window_to_xywh(hwnd, x, y, width, height),
+            Op::TopMost(true) => set_window_top_most(hwnd),
+            Op::TopMost(false) => cancel_window_top_most(hwnd),
+            Op::Minimize => minimize_window(hwnd),
+            Op::Restore => restore_window(hwnd),
+        }
+    }
+}
+
+/// 对所有匹配`matcher`的顶层窗口执行`op`，返回每个窗口的结果。
+/// [`Op::Minimize`]、[`Op::Restore`]会显示窗口，因此只对可见窗口执行；其他操作包括不可见窗口。
+/// 某个窗口失败不影响其他窗口。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::find::WindowMatcher;
+/// use window_inspector::operation::for_each_matching;
+/// use window_inspector::operation::Op;
+///
+/// let matcher = WindowMatcher::new().process_name("explorer.exe").class("CabinetWClass");
+/// for (hwnd, result) in for_each_matching(&matcher, Op::Minimize).unwrap() {
+///     if let Err(e) = result {
+///         eprintln!("{:#X}: {}", hwnd, e);
+///     }
+/// }
+/// ```
+pub fn for_each_matching(matcher: &WindowMatcher, op: Op) -> Result<Vec<(usize, Result<()>)>> {
+    Ok(get_top_level_windows()?
+        .into_iter()
+        .filter(|&hwnd| {
+            !matches!(op, Op::Minimize | Op::Restore)
+                || unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool()
+        })
+        .filter(|&hwnd| matcher.matches(hwnd))
+        .map(|hwnd| (hwnd, op.apply(hwnd)))
+        .collect())
+}
@@ -0,0 +1,361 @@
+//! 覆盖在其他窗口上的窗口。[`follow`]让自己的窗口跟随目标窗口移动。
+//!
+//! 内部还有围绕窗口绘制边框的覆盖窗口，用于高亮窗口。
+//! 它是置顶、不激活、鼠标穿透的分层工具窗口，通过窗口区域只保留边框部分，
+//! 边框画在目标矩形外侧，不会遮挡目标窗口。覆盖窗口属于创建它的线程，该线程需要处理消息。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+use std::thread;
+use std::thread::JoinHandle;
+
+use windows::core::w;
+use windows::core::HRESULT;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::COLORREF;
+use windows::Win32::Foundation::ERROR_CLASS_ALREADY_EXISTS;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Gdi::BeginPaint;
+use windows::Win32::Graphics::Gdi::CombineRgn;
+use windows::Win32::Graphics::Gdi::CreateRectRgn;
+use windows::Win32::Graphics::Gdi::CreateSolidBrush;
+use windows::Win32::Graphics::Gdi::DeleteObject;
+use windows::Win32::Graphics::Gdi::EndPaint;
+use windows::Win32::Graphics::Gdi::FillRect;
+use windows::Win32::Graphics::Gdi::InvalidateRect;
+use windows::Win32::Graphics::Gdi::SetWindowRgn;
+use windows::Win32::Graphics::Gdi::PAINTSTRUCT;
+use windows::Win32::Graphics::Gdi::RGN_DIFF;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::SetLayeredWindowAttributes;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::GWLP_USERDATA;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::LWA_ALPHA;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::SWP_SHOWWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::WM_PAINT;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TRANSPARENT;
+use windows::Win32::UI::WindowsAndMessaging::WS_POPUP;
+
+use crate::error::WindowInspectorError;
+use crate::events::win_events;
+use crate::events::EventFilter;
+use crate::events::EventKinds;
+use crate::events::HookGuard;
+use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::position_size::get_client_xywh;
+use crate::position_size::get_window_xywh_exclude_shadow;
+use crate::result::Result;
+use crate::z_order::insert_after_for_above;
+
+unsafe extern "system" fn overlay_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_PAINT {
+        // 颜色保存在GWLP_USERDATA中。
+        let color = COLORREF(GetWindowLongW(hwnd, GWLP_USERDATA) as u32);
+        let mut paint = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut paint);
+        let brush = CreateSolidBrush(color);
+        FillRect(hdc, &paint.rcPaint, brush);
+        let _ = DeleteObject(brush);
+        let _ = EndPaint(hwnd, &paint);
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// 边框覆盖窗口，drop时销毁。
+#[derive(Debug)]
+pub(crate) struct Overlay {
+    hwnd: HWND,
+    border_width: i32,
+}
+
+impl Overlay {
+    /// 在当前线程创建隐藏的覆盖窗口。`color`为`0x00BBGGRR`格式的颜色。
+    pub(crate) fn new(color: u32, border_width: i32) -> windows::core::Result<Overlay> {
+        let instance = unsafe { GetModuleHandleW(None) }?;
+        let window_class = WNDCLASSEXW {
+            cbSize: size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(overlay_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: w!("window_inspector_overlay"),
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&window_class) } == 0 {
+            let error = windows::core::Error::from_win32();
+            if error.code() != HRESULT::from_win32(ERROR_CLASS_ALREADY_EXISTS.0) {
+                return Err(error);
+            }
+        }
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WS_EX_LAYERED
+                    | WS_EX_TRANSPARENT
+                    | WS_EX_TOPMOST
+                    | WS_EX_NOACTIVATE
+                    | WS_EX_TOOLWINDOW,
+                w!("window_inspector_overlay"),
+                PCWSTR::null(),
+                WS_POPUP,
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                instance,
+                None,
+            )
+        }?;
+        let overlay = Overlay {
+            hwnd,
+            border_width: border_width.max(1),
+        };
+        unsafe { SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA) }?;
+        overlay.set_color(color);
+        Ok(overlay)
+    }
+
+    /// 覆盖窗口的句柄。
+    pub(crate) fn hwnd(&self) -> usize {
+        self.hwnd.0 as usize
+    }
+
+    /// 修改边框颜色。
+    pub(crate) fn set_color(&self, color: u32) {
+        unsafe {
+            SetWindowLongW(self.hwnd, GWLP_USERDATA, color as i32);
+            let _ = InvalidateRect(self.hwnd, None, true);
+        }
+    }
+
+    /// 在矩形`(x, y, width, height)`外侧显示边框。
+    pub(crate) fn show_around(&self, (x, y, width, height): (i32, i32, u32, u32)) {
+        let border = self.border_width;
+        let outer_width = width as i32 + border * 2;
+        let outer_height = height as i32 + border * 2;
+        unsafe {
+            // 窗口区域的坐标相对于覆盖窗口，SetWindowRgn成功后区域归系统所有，不需要删除。
+            let region = CreateRectRgn(0, 0, outer_width, outer_height);
+            let inner = CreateRectRgn(
+                border,
+                border,
+                border + width as i32,
+                border + height as i32,
+            );
+            CombineRgn(region, region, inner, RGN_DIFF);
+            let _ = DeleteObject(inner);
+            if SetWindowRgn(self.hwnd, region, true) == 0 {
+                let _ = DeleteObject(region);
+            }
+            let _ = SetWindowPos(
+                self.hwnd,
+                HWND_TOPMOST,
+                x - border,
+                y - border,
+                outer_width,
+                outer_height,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+        }
+    }
+
+    /// 隐藏边框。
+    pub(crate) fn hide(&self) {
+        let _ = unsafe { ShowWindow(self.hwnd, SW_HIDE) };
+    }
+}
+
+impl Drop for Overlay {
+    fn drop(&mut self) {
+        let _ = unsafe { DestroyWindow(self.hwnd) };
+    }
+}
+
+/// [`follow`]的选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FollowOptions {
+    /// 以目标窗口的客户区为基准，否则以可见边框（不包括阴影）为基准。
+    pub client_area: bool,
+    /// 跟随窗口的尺寸与基准区域一致，否则保持跟随窗口自己的尺寸。
+    pub match_size: bool,
+    /// 目标窗口隐藏或最小化时隐藏跟随窗口，恢复时重新显示。
+    pub sync_visibility: bool,
+    /// 保持跟随窗口在Z序中位于目标窗口正上方。
+    pub sync_z_order: bool,
+}
+
+impl Default for FollowOptions {
+    /// 以可见边框为基准，同步尺寸、可见性和Z序。
+    fn default() -> Self {
+        Self {
+            client_area: false,
+            match_size: true,
+            sync_visibility: true,
+            sync_z_order: true,
+        }
+    }
+}
+
+/// 保持跟随窗口贴合目标窗口，drop时停止。由[`follow`]返回。
+#[must_use = "guard被drop时会立即停止跟随"]
+#[derive(Debug)]
+pub struct FollowGuard {
+    hook: Option<HookGuard>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for FollowGuard {
+    fn drop(&mut self) {
+        // 卸载钩子后事件接收端断开，后台线程随之结束。
+        if let Some(hook) = self.hook.take() {
+            hook.shutdown();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 按目标窗口当前的状态放置跟随窗口。目标窗口已经关闭时返回`false`。
+fn sync_follower(
+    target: usize,
+    follower: usize,
+    (offset_x, offset_y): (i32, i32),
+    options: FollowOptions,
+) -> bool {
+    let target_hwnd = HWND(target as *mut c_void);
+    let follower_hwnd = HWND(follower as *mut c_void);
+    if options.sync_visibility
+        && (!unsafe { IsWindowVisible(target_hwnd) }.as_bool()
+            || unsafe { IsIconic(target_hwnd) }.as_bool())
+    {
+        let _ = unsafe { ShowWindow(follower_hwnd, SW_HIDE) };
+        return is_window_exist(target);
+    }
+    let area = if options.client_area {
+        get_client_xywh(target)
+    } else {
+        get_window_xywh_exclude_shadow(target)
+    };
+    let Ok((x, y, width, height)) = area else {
+        return false;
+    };
+    let mut flags = SWP_NOACTIVATE;
+    if !options.match_size {
+        flags |= SWP_NOSIZE;
+    }
+    if options.sync_visibility {
+        flags |= SWP_SHOWWINDOW;
+    }
+    let insert_after = if options.sync_z_order {
+        insert_after_for_above(follower_hwnd, target_hwnd)
+    } else {
+        None
+    };
+    if insert_after.is_none() {
+        flags |= SWP_NOZORDER;
+    }
+    let _ = unsafe {
+        SetWindowPos(
+            follower_hwnd,
+            insert_after.unwrap_or_default(),
+            x + offset_x,
+            y + offset_y,
+            width as i32,
+            height as i32,
+            flags,
+        )
+    };
+    true
+}
+
+/// 让`follower_hwnd`跟随`target_hwnd`：保持在目标窗口可见边框（或客户区）左上角偏移`offset`处，
+/// 并按`options`同步尺寸、可见性（包括最小化）和Z序（位于目标窗口正上方，不会置顶）。
+/// 由窗口事件驱动，目标窗口不动时不消耗CPU。目标窗口关闭后自动停止。
+/// 跟随窗口通常是自己创建的无边框弹出窗口，带阴影的窗口会按包括阴影的矩形放置。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::overlay::follow;
+/// use window_inspector::overlay::FollowOptions;
+///
+/// # let (target, my_overlay) = (0, 0);
+/// let _guard = follow(target, my_overlay, (0, 0), FollowOptions::default()).unwrap();
+/// ```
+pub fn follow(
+    target_hwnd: usize,
+    follower_hwnd: usize,
+    offset: (i32, i32),
+    options: FollowOptions,
+) -> Result<FollowGuard> {
+    for hwnd in [target_hwnd, follower_hwnd] {
+        if !is_window_exist(hwnd) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(hwnd as *mut c_void),
+            });
+        }
+    }
+    if dry_run(format_args!(
+        "follow {:#X} with {:#X} offset {:?} {:?}",
+        target_hwnd, follower_hwnd, offset, options
+    )) {
+        return Ok(FollowGuard {
+            hook: None,
+            thread: None,
+        });
+    }
+    let events = win_events(&EventFilter {
+        hwnd: Some(target_hwnd),
+        event_kinds: EventKinds::LOCATION_CHANGE
+            | EventKinds::MOVE_SIZE_END
+            | EventKinds::MINIMIZE
+            | EventKinds::RESTORE
+            | EventKinds::SHOW
+            | EventKinds::HIDE
+            | EventKinds::FOREGROUND,
+        ..Default::default()
+    })?;
+    sync_follower(target_hwnd, follower_hwnd, offset, options);
+    let (receiver, hook) = events.into_parts();
+    let thread = thread::spawn(move || {
+        for _ in receiver.iter() {
+            if !is_window_exist(follower_hwnd)
+                || !sync_follower(target_hwnd, follower_hwnd, offset, options)
+            {
+                break;
+            }
+        }
+    });
+    Ok(FollowGuard {
+        hook: Some(hook),
+        thread: Some(thread),
+    })
+}
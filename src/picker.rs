@@ -0,0 +1,234 @@
+//! 让用户用鼠标选择窗口。
+//!
+//! 移动鼠标时用边框高亮鼠标下的顶层窗口，单击左键或按回车、空格选择该窗口，单击右键或按Esc取消。
+//! 选择期间的这些点击和按键不会传递给其他程序。
+//!
+//! # 示例
+//! ```no_run
+//! use window_inspector::picker::pick_window;
+//! use window_inspector::picker::PickOptions;
+//!
+//! if let Some(hwnd) = pick_window(&PickOptions::default()).unwrap() {
+//!     println!("{:#X}", hwnd);
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::POINT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_ESCAPE;
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_RETURN;
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_SPACE;
+use windows::Win32::UI::WindowsAndMessaging::CallNextHookEx;
+use windows::Win32::UI::WindowsAndMessaging::GetAncestor;
+use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
+use windows::Win32::UI::WindowsAndMessaging::SetTimer;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowsHookExW;
+use windows::Win32::UI::WindowsAndMessaging::UnhookWindowsHookEx;
+use windows::Win32::UI::WindowsAndMessaging::WindowFromPoint;
+use windows::Win32::UI::WindowsAndMessaging::GA_ROOT;
+use windows::Win32::UI::WindowsAndMessaging::HC_ACTION;
+use windows::Win32::UI::WindowsAndMessaging::HHOOK;
+use windows::Win32::UI::WindowsAndMessaging::KBDLLHOOKSTRUCT;
+use windows::Win32::UI::WindowsAndMessaging::MSLLHOOKSTRUCT;
+use windows::Win32::UI::WindowsAndMessaging::WH_KEYBOARD_LL;
+use windows::Win32::UI::WindowsAndMessaging::WH_MOUSE_LL;
+use windows::Win32::UI::WindowsAndMessaging::WM_KEYDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_KEYUP;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONUP;
+use windows::Win32::UI::WindowsAndMessaging::WM_MOUSEMOVE;
+use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONUP;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::message_loop::MessageThread;
+use crate::overlay::Overlay;
+use crate::position_size::get_window_xywh_exclude_shadow;
+use crate::result::Result;
+
+/// [`pick_window`]的选项。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickOptions {
+    /// 高亮边框的颜色，格式为`0x00BBGGRR`。默认为红色。
+    pub highlight_color: u32,
+    /// 高亮边框的宽度（像素）。默认为3。
+    pub border_width: i32,
+    /// 超过这个时间没有选择时视为取消。默认为`None`，即一直等待。
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PickOptions {
+    fn default() -> Self {
+        Self {
+            highlight_color: 0x0000FF,
+            border_width: 3,
+            timeout: None,
+        }
+    }
+}
+
+/// 选择过程中的状态，只在选择线程中访问。
+struct PickState {
+    overlay: Overlay,
+    hovered: Option<usize>,
+    picked: Option<usize>,
+}
+
+thread_local! {
+    static PICK_STATE: RefCell<Option<PickState>> = const { RefCell::new(None) };
+}
+
+/// 后台线程初始化失败的原因。
+enum SetupError {
+    Overlay(windows::core::Error),
+    Hook(windows::core::Error),
+}
+
+/// 获取屏幕坐标处的顶层窗口，忽略覆盖窗口本身。
+fn root_window_from_point(point: POINT, overlay: &Overlay) -> Option<usize> {
+    let hwnd = unsafe { GetAncestor(WindowFromPoint(point), GA_ROOT) };
+    let hwnd = hwnd.0 as usize;
+    (hwnd != 0 && hwnd != overlay.hwnd()).then_some(hwnd)
+}
+
+/// 高亮屏幕坐标处的窗口。
+fn hover(point: POINT) {
+    PICK_STATE.with_borrow_mut(|state| {
+        let Some(state) = state else {
+            return;
+        };
+        let hwnd = root_window_from_point(point, &state.overlay);
+        if hwnd == state.hovered {
+            return;
+        }
+        state.hovered = hwnd;
+        match hwnd.and_then(|hwnd| get_window_xywh_exclude_shadow(hwnd).ok()) {
+            Some(xywh) => state.overlay.show_around(xywh),
+            None => state.overlay.hide(),
+        }
+    });
+}
+
+/// 结束选择。`pick`为`true`时选择当前高亮的窗口，否则取消。
+fn finish(pick: bool) {
+    PICK_STATE.with_borrow_mut(|state| {
+        if let Some(state) = state {
+            state.picked = if pick { state.hovered } else { None };
+            state.overlay.hide();
+        }
+    });
+    unsafe { PostQuitMessage(0) };
+}
+
+unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        match wparam.0 as u32 {
+            WM_MOUSEMOVE => hover(info.pt),
+            WM_LBUTTONDOWN => {
+                hover(info.pt);
+                finish(true);
+                return LRESULT(1);
+            }
+            WM_RBUTTONDOWN => {
+                finish(false);
+                return LRESULT(1);
+            }
+            // 按下已经被拦截，抬起也要拦截。
+            WM_LBUTTONUP | WM_RBUTTONUP => return LRESULT(1),
+            _ => {}
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let key = info.vkCode as u16;
+        if key == VK_ESCAPE.0 || key == VK_RETURN.0 || key == VK_SPACE.0 {
+            if wparam.0 as u32 == WM_KEYDOWN {
+                finish(key != VK_ESCAPE.0);
+            }
+            if wparam.0 as u32 == WM_KEYDOWN || wparam.0 as u32 == WM_KEYUP {
+                return LRESULT(1);
+            }
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn timeout_proc(_: HWND, _: u32, _: usize, _: u32) {
+    finish(false);
+}
+
+/// 安装钩子并创建覆盖窗口，在选择线程中调用。
+fn setup(options: PickOptions) -> std::result::Result<(HHOOK, HHOOK), SetupError> {
+    let overlay =
+        Overlay::new(options.highlight_color, options.border_width).map_err(SetupError::Overlay)?;
+    PICK_STATE.set(Some(PickState {
+        overlay,
+        hovered: None,
+        picked: None,
+    }));
+    let instance = unsafe { GetModuleHandleW(None) }.map_err(SetupError::Hook)?;
+    let mouse_hook = unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), instance, 0) }
+        .map_err(SetupError::Hook)?;
+    let keyboard_hook =
+        match unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), instance, 0) } {
+            Ok(hook) => hook,
+            Err(e) => {
+                let _ = unsafe { UnhookWindowsHookEx(mouse_hook) };
+                return Err(SetupError::Hook(e));
+            }
+        };
+    if let Some(timeout) = options.timeout {
+        let elapse = timeout.as_millis().clamp(1, u32::MAX as u128) as u32;
+        unsafe { SetTimer(None, 0, elapse, Some(timeout_proc)) };
+    }
+    // 立即高亮鼠标当前所在的窗口。
+    let mut point = POINT::default();
+    if unsafe { GetCursorPos(&mut point) }.is_ok() {
+        hover(point);
+    }
+    Ok((mouse_hook, keyboard_hook))
+}
+
+/// 让用户选择一个窗口，阻塞直到选择完成。
+/// 返回选择的顶层窗口，取消或超时时返回`None`。
+pub fn pick_window(options: &PickOptions) -> Result<Option<usize>> {
+    let options = *options;
+    let (sender, receiver) = mpsc::channel();
+    let thread = MessageThread::spawn(
+        move || setup(options),
+        |_| {},
+        move |(mouse_hook, keyboard_hook)| {
+            let _ = unsafe { UnhookWindowsHookEx(mouse_hook) };
+            let _ = unsafe { UnhookWindowsHookEx(keyboard_hook) };
+            let picked = PICK_STATE.take().and_then(|state| state.picked);
+            let _ = sender.send(picked);
+        },
+    )
+    .map_err(|e| match e {
+        SetupError::Overlay(error) => WindowInspectorError::CreateWindowExWFailed {
+            class: "window_inspector_overlay".to_string(),
+            title: String::new(),
+            error,
+        },
+        SetupError::Hook(error) => WindowInspectorError::SetWindowsHookExWFailed { error },
+    })?;
+    let picked = receiver.recv().unwrap_or(None);
+    drop(thread);
+    // 选择后窗口可能已经关闭。
+    Ok(picked.filter(|&hwnd| is_window_exist(hwnd)))
+}
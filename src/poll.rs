@@ -0,0 +1,304 @@
+//! 合并轮询：多处代码轮询同一窗口时，在一个后台线程中统一获取，只把变化通知给各个订阅。
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::exist::is_window_exist;
+use crate::information::query;
+use crate::information::PartialWindowInfo;
+use crate::information::QueryFlags;
+
+/// 订阅的标识，由[`Scheduler::register`]返回。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PollId(u64);
+
+/// 通知给订阅的变化。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollUpdate {
+    /// 有字段改变，只有改变了的字段为`Some`。第一次获取时包含所有订阅的字段。
+    Changed(PartialWindowInfo),
+    /// 窗口已经关闭，之后不会再通知，订阅自动移除。
+    Closed,
+}
+
+type Callback = Box<dyn FnMut(PollUpdate) + Send>;
+
+struct Subscription {
+    id: PollId,
+    hwnd: usize,
+    flags: QueryFlags,
+    interval: Duration,
+    callback: Callback,
+    next: Instant,
+    /// 上一次通知时的信息。
+    last: PartialWindowInfo,
+}
+
+enum Command {
+    Register(Box<Subscription>),
+    Unregister(PollId),
+}
+
+/// `flags`中请求的、从`old`到`new`改变了的字段，没有改变时返回`None`。
+fn diff(
+    old: &PartialWindowInfo,
+    new: &PartialWindowInfo,
+    flags: QueryFlags,
+) -> Option<PartialWindowInfo> {
+    fn pick<T: Clone + PartialEq>(requested: bool, old: &Option<T>, new: &Option<T>) -> Option<T> {
+        if requested && old != new {
+            new.clone()
+        } else {
+            None
+        }
+    }
+    let changed = PartialWindowInfo {
+        class: pick(flags.contains(QueryFlags::CLASS), &old.class, &new.class),
+        title: pick(flags.contains(QueryFlags::TITLE), &old.title, &new.title),
+        process_id: pick(
+            flags.contains(QueryFlags::PROCESS_ID),
+            &old.process_id,
+            &new.process_id,
+        ),
+        process_path: pick(
+            flags.contains(QueryFlags::PROCESS_PATH),
+            &old.process_path,
+            &new.process_path,
+        ),
+        window_xywh_include_shadow: pick(
+            flags.contains(QueryFlags::WINDOW_XYWH_INCLUDE_SHADOW),
+            &old.window_xywh_include_shadow,
+            &new.window_xywh_include_shadow,
+        ),
+        window_xywh_exclude_shadow: pick(
+            flags.contains(QueryFlags::WINDOW_XYWH_EXCLUDE_SHADOW),
+            &old.window_xywh_exclude_shadow,
+            &new.window_xywh_exclude_shadow,
+        ),
+        client_xywh: pick(
+            flags.contains(QueryFlags::CLIENT_XYWH),
+            &old.client_xywh,
+            &new.client_xywh,
+        ),
+        top_most: pick(
+            flags.contains(QueryFlags::TOP_MOST),
+            &old.top_most,
+            &new.top_most,
+        ),
+        visible: pick(
+            flags.contains(QueryFlags::VISIBLE),
+            &old.visible,
+            &new.visible,
+        ),
+        minimized: pick(
+            flags.contains(QueryFlags::MINIMIZED),
+            &old.minimized,
+            &new.minimized,
+        ),
+    };
+    (changed != PartialWindowInfo::default()).then_some(changed)
+}
+
+/// 一个窗口的合并查询结果。
+enum Polled {
+    Info(PartialWindowInfo),
+    Closed,
+    /// 窗口存在但合并查询失败，例如某个订阅请求的进程路径没有权限读取。
+    Failed,
+}
+
+/// 有变化时通知订阅。
+fn notify(subscription: &mut Subscription, info: &PartialWindowInfo) {
+    if let Some(changed) = diff(&subscription.last, info, subscription.flags) {
+        subscription.last = info.clone();
+        (subscription.callback)(PollUpdate::Changed(changed));
+    }
+}
+
+/// 轮询到期的订阅。同一窗口的订阅合并为一次[`query`]，请求的信息取并集。
+/// 合并查询失败时改为逐个订阅查询，一个订阅请求的信息无法获取不会影响同一窗口的其他订阅。
+fn poll_due(subscriptions: &mut Vec<Subscription>) {
+    let now = Instant::now();
+    let mut flags: HashMap<usize, QueryFlags> = HashMap::new();
+    for subscription in subscriptions.iter().filter(|s| s.next <= now) {
+        *flags
+            .entry(subscription.hwnd)
+            .or_insert(QueryFlags::empty()) |= subscription.flags;
+    }
+    let results: HashMap<usize, Polled> = flags
+        .into_iter()
+        .map(|(hwnd, flags)| {
+            let polled = match query(hwnd, flags) {
+                Ok(info) => Polled::Info(info),
+                Err(_) if !is_window_exist(hwnd) => Polled::Closed,
+                Err(_) => Polled::Failed,
+            };
+            (hwnd, polled)
+        })
+        .collect();
+    subscriptions.retain_mut(|subscription| {
+        if subscription.next > now {
+            return true;
+        }
+        subscription.next = now + subscription.interval;
+        match results.get(&subscription.hwnd) {
+            Some(Polled::Info(info)) => notify(subscription, info),
+            Some(Polled::Closed) => {
+                (subscription.callback)(PollUpdate::Closed);
+                return false;
+            }
+            Some(Polled::Failed) => match query(subscription.hwnd, subscription.flags) {
+                Ok(info) => notify(subscription, &info),
+                Err(_) if !is_window_exist(subscription.hwnd) => {
+                    (subscription.callback)(PollUpdate::Closed);
+                    return false;
+                }
+                // 这个订阅自己请求的信息也获取失败，跳过本次，下次到期时重试。
+                Err(_) => {}
+            },
+            None => {}
+        }
+        true
+    });
+}
+
+/// 合并多个窗口轮询的调度器。所有订阅在同一个后台线程中轮询，drop时停止。
+/// 同一时刻到期的、同一窗口的订阅只获取一次信息，信息没有变化时不调用回调。
+/// 回调在后台线程中执行，应尽快返回，否则会推迟其他订阅。
+///
+/// # 示例
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use window_inspector::foreground::get_foreground_hwnd;
+/// use window_inspector::information::QueryFlags;
+/// use window_inspector::poll::Scheduler;
+///
+/// let scheduler = Scheduler::new();
+/// scheduler.register(
+///     get_foreground_hwnd(),
+///     QueryFlags::TITLE,
+///     Duration::from_millis(200),
+///     |update| println!("{:?}", update),
+/// );
+/// std::thread::sleep(Duration::from_secs(60));
+/// ```
+#[must_use = "Scheduler被drop时会立即停止轮询"]
+#[derive(Debug)]
+pub struct Scheduler {
+    next_id: AtomicU64,
+    sender: Option<mpsc::Sender<Command>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Scheduler {
+    /// 创建调度器并启动后台线程。
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Command>();
+        let thread = thread::spawn(move || {
+            let mut subscriptions: Vec<Subscription> = Vec::new();
+            loop {
+                let command = match subscriptions.iter().map(|s| s.next).min() {
+                    Some(next) => {
+                        receiver.recv_timeout(next.saturating_duration_since(Instant::now()))
+                    }
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match command {
+                    Ok(Command::Register(subscription)) => subscriptions.push(*subscription),
+                    Ok(Command::Unregister(id)) => subscriptions.retain(|s| s.id != id),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // 调度器已经drop。
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                poll_due(&mut subscriptions);
+            }
+        });
+        Self {
+            next_id: AtomicU64::new(0),
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// 每隔`interval`获取窗口的`flags`信息，有变化时调用`callback`。注册后立即获取一次。
+    pub fn register(
+        &self,
+        hwnd: usize,
+        flags: QueryFlags,
+        interval: Duration,
+        callback: impl FnMut(PollUpdate) + Send + 'static,
+    ) -> PollId {
+        let id = PollId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(Command::Register(Box::new(Subscription {
+                id,
+                hwnd,
+                flags,
+                interval,
+                callback: Box::new(callback),
+                next: Instant::now(),
+                last: PartialWindowInfo::default(),
+            })));
+        }
+        id
+    }
+
+    /// 取消订阅。订阅已经因窗口关闭而移除时什么也不做。
+    pub fn unregister(&self, id: PollId) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(Command::Unregister(id));
+        }
+    }
+
+    /// 停止轮询，等待正在进行的回调结束。
+    pub fn stop(self) {}
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        // 发送端断开后后台线程随之结束。
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[test]
+fn test_diff() {
+    let old = PartialWindowInfo {
+        title: Some("a".to_string()),
+        visible: Some(true),
+        ..Default::default()
+    };
+    let new = PartialWindowInfo {
+        title: Some("b".to_string()),
+        visible: Some(true),
+        minimized: Some(true),
+        ..Default::default()
+    };
+    let flags = QueryFlags::TITLE | QueryFlags::VISIBLE;
+    assert_eq!(
+        diff(&old, &new, flags),
+        Some(PartialWindowInfo {
+            title: Some("b".to_string()),
+            ..Default::default()
+        })
+    );
+    assert_eq!(diff(&new, &new, flags), None);
+    assert_eq!(diff(&old, &new, QueryFlags::VISIBLE), None);
+}
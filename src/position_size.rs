@@ -1,53 +1,57 @@
 use std::ffi::c_void;
 use std::mem::size_of;
 
+use windows::Win32::Foundation::SetLastError;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::POINT;
 use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
 use windows::Win32::Graphics::Dwm::DWMWA_EXTENDED_FRAME_BOUNDS;
 use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::Graphics::Gdi::MapWindowPoints;
+use windows::Win32::Graphics::Gdi::ScreenToClient;
 use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
-use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
-use windows::Win32::UI::WindowsAndMessaging::MoveWindow;
 
+use crate::backend::with_current_backend;
 use crate::error::WindowInspectorError;
 use crate::exist::is_window_exist;
+use crate::exist::with_validation;
+use crate::geometry::ClientPoint;
+use crate::geometry::ClientRect;
+use crate::geometry::Point;
+use crate::geometry::Rect;
+use crate::geometry::ScreenPoint;
+use crate::geometry::ScreenRect;
+use crate::geometry::Size;
+use crate::mode::dry_run;
 use crate::result::Result;
 
 /// 获取窗口位置尺寸（包括阴影），相对于屏幕。
 /// # 返回
 /// (x, y, width, height)
 pub fn get_window_xywh_include_shadow(hwnd: usize) -> Result<(i32, i32, u32, u32)> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
-    let mut rect = RECT::default();
-    match unsafe { GetWindowRect(HWND(hwnd as *mut c_void), &mut rect) } {
-        Ok(_) => Ok((
-            rect.left,
-            rect.top,
-            (rect.right - rect.left) as u32,
-            (rect.bottom - rect.top) as u32,
-        )),
-        Err(e) => Err(WindowInspectorError::GetWindowRectFailed {
-            hwnd: HWND(hwnd as *mut c_void),
-            error_message: format!("{:?}", e),
-        }),
-    }
+    with_validation(hwnd, get_window_xywh_include_shadow_unchecked)
+}
+
+/// 获取窗口位置尺寸（包括阴影），不检查窗口是否存在。
+pub(crate) fn get_window_xywh_include_shadow_unchecked(
+    hwnd: usize,
+) -> Result<(i32, i32, u32, u32)> {
+    with_current_backend(|backend| backend.window_xywh(hwnd))
 }
 
 /// 获取窗口位置尺寸（不包括阴影），相对于屏幕。许多截屏软件获取窗口矩形时，不包括阴影。这个函数得到的窗口大小与截屏软件得到的窗口大小一致。
 /// # 返回
 /// (x, y, width, height)
 pub fn get_window_xywh_exclude_shadow(hwnd: usize) -> Result<(i32, i32, u32, u32)> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
+    with_validation(hwnd, get_window_xywh_exclude_shadow_unchecked)
+}
+
+/// 获取窗口位置尺寸（不包括阴影），不检查窗口是否存在。
+pub(crate) fn get_window_xywh_exclude_shadow_unchecked(
+    hwnd: usize,
+) -> Result<(i32, i32, u32, u32)> {
     let mut rect = RECT::default();
     match unsafe {
         DwmGetWindowAttribute(
@@ -65,7 +69,7 @@ pub fn get_window_xywh_exclude_shadow(hwnd: usize) -> Result<(i32, i32, u32, u32
         )),
         Err(e) => Err(WindowInspectorError::DwmGetWindowAttributeFailed {
             hwnd: HWND(hwnd as *mut c_void),
-            error_message: format!("{:?}", e),
+            error: e,
         }),
     }
 }
@@ -74,15 +78,16 @@ pub fn get_window_xywh_exclude_shadow(hwnd: usize) -> Result<(i32, i32, u32, u32
 /// # 返回
 /// (x, y)
 pub fn get_client_xy(hwnd: usize) -> Result<(i32, i32)> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
+    with_validation(hwnd, get_client_xy_unchecked)
+}
+
+/// 获取客户区左上角坐标，不检查窗口是否存在。
+pub(crate) fn get_client_xy_unchecked(hwnd: usize) -> Result<(i32, i32)> {
     let mut point = POINT::default();
     if !unsafe { ClientToScreen(HWND(hwnd as *mut c_void), &mut point) }.as_bool() {
         return Err(WindowInspectorError::ClientToScreenFailed {
             hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
         });
     }
     Ok((point.x, point.y))
@@ -92,11 +97,11 @@ pub fn get_client_xy(hwnd: usize) -> Result<(i32, i32)> {
 /// # 返回
 /// (width, height)
 pub fn get_client_wh(hwnd: usize) -> Result<(u32, u32)> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
+    with_validation(hwnd, get_client_wh_unchecked)
+}
+
+/// 获取客户区尺寸，不检查窗口是否存在。
+pub(crate) fn get_client_wh_unchecked(hwnd: usize) -> Result<(u32, u32)> {
     let mut rect = RECT::default();
     match unsafe { GetClientRect(HWND(hwnd as *mut c_void), &mut rect) } {
         Ok(_) => Ok((
@@ -105,18 +110,57 @@ pub fn get_client_wh(hwnd: usize) -> Result<(u32, u32)> {
         )),
         Err(e) => Err(WindowInspectorError::GetClientRectFailed {
             hwnd: HWND(hwnd as *mut c_void),
-            error_message: format!("{:?}", e),
+            error: e,
         }),
     }
 }
 
 /// 获取客户区位置尺寸，相对于屏幕。
+/// 用`GetClientRect`和`MapWindowPoints`一次获取，比分别调用[`get_client_xy`]、[`get_client_wh`]少一半系统调用。
 /// # 返回
 /// (x, y, width, height)
 pub fn get_client_xywh(hwnd: usize) -> Result<(i32, i32, u32, u32)> {
-    let (x, y) = get_client_xy(hwnd)?;
-    let (width, height) = get_client_wh(hwnd)?;
-    Ok((x, y, width, height))
+    with_validation(hwnd, get_client_xywh_unchecked)
+}
+
+/// 获取客户区位置尺寸，不检查窗口是否存在。
+pub(crate) fn get_client_xywh_unchecked(hwnd: usize) -> Result<(i32, i32, u32, u32)> {
+    let mut rect = RECT::default();
+    unsafe { GetClientRect(HWND(hwnd as *mut c_void), &mut rect) }.map_err(|e| {
+        WindowInspectorError::GetClientRectFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: e,
+        }
+    })?;
+    let mut points = [
+        POINT {
+            x: rect.left,
+            y: rect.top,
+        },
+        POINT {
+            x: rect.right,
+            y: rect.bottom,
+        },
+    ];
+    // 偏移为0时MapWindowPoints也返回0，需要先清除错误码，再用GetLastError区分是否失败。
+    unsafe { SetLastError(WIN32_ERROR(0)) };
+    if unsafe { MapWindowPoints(HWND(hwnd as *mut c_void), HWND::default(), &mut points) } == 0 {
+        let error = windows::core::Error::from_win32();
+        if error.code().is_err() {
+            return Err(WindowInspectorError::MapWindowPointsFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error,
+            });
+        }
+    }
+    // 从右到左布局的窗口映射后左右会交换。
+    let [a, b] = points;
+    Ok((
+        a.x.min(b.x),
+        a.y.min(b.y),
+        a.x.abs_diff(b.x),
+        a.y.abs_diff(b.y),
+    ))
 }
 
 /// 移动窗口到xywh。
@@ -126,20 +170,108 @@ pub fn move_window_to_xywh(hwnd: usize, x: i32, y: i32, width: u32, height: u32)
             hwnd: HWND(hwnd as *mut c_void),
         });
     }
-    unsafe {
-        if let Err(e) = MoveWindow(
-            HWND(hwnd as *mut c_void),
-            x,
-            y,
-            width as i32,
-            height as i32,
-            true,
-        ) {
-            return Err(WindowInspectorError::MoveWindowFailed {
-                hwnd: HWND(hwnd as *mut c_void),
-                error_message: format!("{:?}", e),
-            });
-        }
+    if dry_run(format_args!(
+        "MoveWindow {:#X} to ({}, {}, {}, {})",
+        hwnd, x, y, width, height
+    )) {
+        return Ok(());
+    }
+    with_current_backend(|backend| backend.move_window(hwnd, x, y, width, height))
+}
+
+/// 获取窗口矩形（包括阴影），相对于屏幕。见[`get_window_xywh_include_shadow`]。
+pub fn get_window_rect_include_shadow(hwnd: usize) -> Result<Rect> {
+    get_window_xywh_include_shadow(hwnd).map(Rect::from)
+}
+
+/// 获取窗口矩形（不包括阴影），相对于屏幕。见[`get_window_xywh_exclude_shadow`]。
+pub fn get_window_rect_exclude_shadow(hwnd: usize) -> Result<Rect> {
+    get_window_xywh_exclude_shadow(hwnd).map(Rect::from)
+}
+
+/// 获取客户区左上角坐标，相对于屏幕。见[`get_client_xy`]。
+pub fn get_client_point(hwnd: usize) -> Result<Point> {
+    get_client_xy(hwnd).map(Point::from)
+}
+
+/// 获取客户区尺寸。见[`get_client_wh`]。
+pub fn get_client_size(hwnd: usize) -> Result<Size> {
+    get_client_wh(hwnd).map(Size::from)
+}
+
+/// 获取客户区矩形，相对于屏幕。见[`get_client_xywh`]。
+pub fn get_client_rect(hwnd: usize) -> Result<Rect> {
+    get_client_xywh(hwnd).map(Rect::from)
+}
+
+/// 移动窗口到矩形（包括阴影）。见[`move_window_to_xywh`]。
+pub fn move_window_to_rect(hwnd: usize, rect: Rect) -> Result<()> {
+    move_window_to_xywh(hwnd, rect.x, rect.y, rect.w, rect.h)
+}
+
+/// 将窗口客户区坐标转换为屏幕坐标。
+pub fn client_to_screen(hwnd: usize, point: ClientPoint) -> Result<ScreenPoint> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
     }
-    Ok(())
+    let mut point = POINT::from(point.0);
+    if !unsafe { ClientToScreen(HWND(hwnd as *mut c_void), &mut point) }.as_bool() {
+        return Err(WindowInspectorError::ClientToScreenFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(ScreenPoint(point.into()))
+}
+
+/// 将屏幕坐标转换为窗口客户区坐标。
+pub fn screen_to_client(hwnd: usize, point: ScreenPoint) -> Result<ClientPoint> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let mut point = POINT::from(point.0);
+    if !unsafe { ScreenToClient(HWND(hwnd as *mut c_void), &mut point) }.as_bool() {
+        return Err(WindowInspectorError::ScreenToClientFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(ClientPoint(point.into()))
+}
+
+/// 将窗口客户区中的矩形转换为屏幕坐标，尺寸不变。
+pub fn client_rect_to_screen(hwnd: usize, rect: ClientRect) -> Result<ScreenRect> {
+    let ScreenPoint(point) = client_to_screen(hwnd, ClientPoint(rect.0.point()))?;
+    Ok(ScreenRect(Rect::from_point_size(point, rect.0.size())))
+}
+
+/// 将屏幕坐标中的矩形转换为窗口客户区坐标，尺寸不变。
+pub fn screen_rect_to_client(hwnd: usize, rect: ScreenRect) -> Result<ClientRect> {
+    let ClientPoint(point) = screen_to_client(hwnd, ScreenPoint(rect.0.point()))?;
+    Ok(ClientRect(Rect::from_point_size(point, rect.0.size())))
+}
+
+/// 获取客户区矩形，相对于屏幕。与[`get_client_rect`]相同，但返回类型标明了坐标系。
+pub fn get_client_screen_rect(hwnd: usize) -> Result<ScreenRect> {
+    get_client_rect(hwnd).map(ScreenRect)
+}
+
+#[test]
+fn test_get_client_xywh() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new()
+        .xywh(100, 100, 400, 300)
+        .build()
+        .unwrap();
+    let (x, y) = get_client_xy(window.hwnd()).unwrap();
+    let (width, height) = get_client_wh(window.hwnd()).unwrap();
+    assert_eq!(
+        get_client_xywh(window.hwnd()).unwrap(),
+        (x, y, width, height)
+    );
 }
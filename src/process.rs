@@ -1,27 +1,97 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ffi::c_void;
+use std::mem::size_of;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
+use windows::core::w;
+use windows::core::HSTRING;
+use windows::core::PCWSTR;
 use windows::core::PWSTR;
-use windows::Win32::Foundation::GetLastError;
+use windows::Management::Deployment::PackageManager;
+#[cfg(feature = "ntdll")]
+use windows::Wdk::System::Threading::NtQueryInformationProcess;
+#[cfg(feature = "ntdll")]
+use windows::Wdk::System::Threading::ProcessCommandLineInformation;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::FILETIME;
+use windows::Win32::Foundation::HANDLE;
 use windows::Win32::Foundation::HWND;
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::NTSTATUS;
+#[cfg(feature = "ntdll")]
+use windows::Win32::Foundation::UNICODE_STRING;
+use windows::Win32::Security::GetTokenInformation;
+use windows::Win32::Security::LookupAccountSidW;
+use windows::Win32::Security::TokenUser;
+use windows::Win32::Security::SID_NAME_USE;
+use windows::Win32::Security::TOKEN_QUERY;
+use windows::Win32::Security::TOKEN_USER;
+use windows::Win32::Storage::FileSystem::GetFileVersionInfoSizeW;
+use windows::Win32::Storage::FileSystem::GetFileVersionInfoW;
+use windows::Win32::Storage::FileSystem::VerQueryValueW;
+use windows::Win32::Storage::FileSystem::VS_FIXEDFILEINFO;
+use windows::Win32::Storage::Packaging::Appx::GetPackageFullName;
+use windows::Win32::System::Diagnostics::ToolHelp::CreateToolhelp32Snapshot;
+use windows::Win32::System::Diagnostics::ToolHelp::Process32FirstW;
+use windows::Win32::System::Diagnostics::ToolHelp::Process32NextW;
+use windows::Win32::System::Diagnostics::ToolHelp::PROCESSENTRY32W;
+use windows::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPPROCESS;
+use windows::Win32::System::ProcessStatus::GetProcessMemoryInfo;
+use windows::Win32::System::ProcessStatus::PROCESS_MEMORY_COUNTERS;
+use windows::Win32::System::ProcessStatus::PROCESS_MEMORY_COUNTERS_EX;
+use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows::Win32::System::SystemInformation::GetSystemInfo;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE_AMD64;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE_ARM64;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE_ARMNT;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE_I386;
+use windows::Win32::System::SystemInformation::IMAGE_FILE_MACHINE_UNKNOWN;
+use windows::Win32::System::SystemInformation::SYSTEM_INFO;
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::System::Threading::GetPriorityClass;
+use windows::Win32::System::Threading::GetProcessTimes;
+use windows::Win32::System::Threading::IsWow64Process2;
 use windows::Win32::System::Threading::OpenProcess;
+use windows::Win32::System::Threading::OpenProcessToken;
 use windows::Win32::System::Threading::QueryFullProcessImageNameW;
+use windows::Win32::System::Threading::SetPriorityClass;
+use windows::Win32::System::Threading::ABOVE_NORMAL_PRIORITY_CLASS;
+use windows::Win32::System::Threading::BELOW_NORMAL_PRIORITY_CLASS;
+use windows::Win32::System::Threading::HIGH_PRIORITY_CLASS;
+use windows::Win32::System::Threading::IDLE_PRIORITY_CLASS;
+use windows::Win32::System::Threading::NORMAL_PRIORITY_CLASS;
+use windows::Win32::System::Threading::PROCESS_CREATION_FLAGS;
 use windows::Win32::System::Threading::PROCESS_NAME_FORMAT;
 use windows::Win32::System::Threading::PROCESS_QUERY_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_QUERY_LIMITED_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_SET_INFORMATION;
+#[cfg(feature = "ntdll")]
+use windows::Win32::System::Threading::PROCESS_SUSPEND_RESUME;
 use windows::Win32::System::Threading::PROCESS_VM_READ;
-use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::System::Threading::REALTIME_PRIORITY_CLASS;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowTextLengthW;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GW_OWNER;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
 
+use crate::backend::with_current_backend;
+use crate::enumerate::get_process_windows;
 use crate::error::WindowInspectorError;
+use crate::mode::dry_run;
 use crate::result::Result;
 
 /// 获取窗口所属进程。
 pub fn get_window_process(hwnd: isize) -> Result<u32> {
-    let mut process_id = 0;
-    if unsafe { GetWindowThreadProcessId(HWND(hwnd as *mut c_void), Some(&mut process_id)) } == 0 {
-        return Err(WindowInspectorError::GetWindowThreadProcessIdFailed {
-            error_code: unsafe { GetLastError() }.0,
-        });
-    }
-    Ok(process_id)
+    with_current_backend(|backend| backend.window_process(hwnd as usize))
 }
 
 /// 获取进程路径。
@@ -35,7 +105,7 @@ pub fn get_process_path(process_id: u32) -> Result<String> {
     }
     .map_err(|e| WindowInspectorError::OpenProcessFailed {
         process_id,
-        error_message: format!("{}", e),
+        error: e,
     })?;
 
     let mut buffer = [0u16; 1024];
@@ -52,7 +122,7 @@ pub fn get_process_path(process_id: u32) -> Result<String> {
         Ok(_) => Ok(unsafe { pwstr.to_string() }.unwrap()),
         Err(e) => Err(WindowInspectorError::QueryFullProcessImageNameWFailed {
             process_id,
-            error_message: format!("{}", e),
+            error: e,
         }),
     }
 }
@@ -61,3 +131,746 @@ pub fn get_process_path(process_id: u32) -> Result<String> {
 pub fn get_window_process_path(hwnd: isize) -> Result<String> {
     get_process_path(get_window_process(hwnd)?)
 }
+
+/// 获取进程命令行。
+/// 使用[`NtQueryInformationProcess`]查询`ProcessCommandLineInformation`，需要Windows 8.1及以上。
+/// 同一程序的多个实例路径相同时，可以通过命令行参数区分。
+///
+/// [`NtQueryInformationProcess`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Wdk/System/Threading/fn.NtQueryInformationProcess.html
+#[cfg(feature = "ntdll")]
+pub fn get_process_command_line(process_id: u32) -> Result<String> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+
+    // 第一次调用只为获取所需的缓冲区大小。
+    let mut return_length = 0u32;
+    let _ = unsafe {
+        NtQueryInformationProcess(
+            process_handle,
+            ProcessCommandLineInformation,
+            std::ptr::null_mut(),
+            0,
+            &mut return_length,
+        )
+    };
+    // 返回的数据以UNICODE_STRING开头，使用usize缓冲区保证对齐。
+    let mut buffer = vec![0usize; (return_length as usize).div_ceil(size_of::<usize>()).max(2)];
+    let status = unsafe {
+        NtQueryInformationProcess(
+            process_handle,
+            ProcessCommandLineInformation,
+            buffer.as_mut_ptr() as *mut c_void,
+            (buffer.len() * size_of::<usize>()) as u32,
+            &mut return_length,
+        )
+    };
+    let _ = unsafe { CloseHandle(process_handle) };
+    status
+        .ok()
+        .map_err(|e| WindowInspectorError::NtQueryInformationProcessFailed {
+            process_id,
+            error: e,
+        })?;
+
+    let command_line = unsafe { &*(buffer.as_ptr() as *const UNICODE_STRING) };
+    if command_line.Buffer.is_null() || command_line.Length == 0 {
+        return Ok(String::new());
+    }
+    let wide = unsafe {
+        std::slice::from_raw_parts(command_line.Buffer.0, command_line.Length as usize / 2)
+    };
+    Ok(String::from_utf16_lossy(wide))
+}
+
+/// 获取所有进程的父进程ID，使用同一个toolhelp快照，保证结果一致。
+/// # 返回
+/// 进程ID -> 父进程ID
+fn get_parent_pid_map() -> Result<HashMap<u32, u32>> {
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }
+        .map_err(|e| WindowInspectorError::CreateToolhelp32SnapshotFailed { error: e })?;
+    let mut parent_pid_map = HashMap::new();
+    let mut entry = PROCESSENTRY32W {
+        dwSize: size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut next = unsafe { Process32FirstW(snapshot, &mut entry) };
+    while next.is_ok() {
+        parent_pid_map.insert(entry.th32ProcessID, entry.th32ParentProcessID);
+        next = unsafe { Process32NextW(snapshot, &mut entry) };
+    }
+    let _ = unsafe { CloseHandle(snapshot) };
+    Ok(parent_pid_map)
+}
+
+/// 获取父进程ID。
+/// 父进程可能已经退出，此时返回的ID可能已不存在或已被其他进程复用。
+pub fn get_parent_pid(process_id: u32) -> Result<u32> {
+    get_parent_pid_map()?
+        .get(&process_id)
+        .copied()
+        .ok_or(WindowInspectorError::ProcessNotExist { process_id })
+}
+
+/// 获取进程的祖先链。
+/// # 返回
+/// 从该进程开始，依次为父进程、祖父进程……直到找不到父进程为止。第一个元素是`process_id`本身。
+pub fn get_process_tree(process_id: u32) -> Result<Vec<u32>> {
+    let parent_pid_map = get_parent_pid_map()?;
+    if !parent_pid_map.contains_key(&process_id) {
+        return Err(WindowInspectorError::ProcessNotExist { process_id });
+    }
+    let mut tree = vec![process_id];
+    let mut visited = HashSet::from([process_id]);
+    let mut current = process_id;
+    // 进程ID可能被复用，遇到环时停止。
+    while let Some(&parent) = parent_pid_map.get(&current) {
+        if parent == 0 || !parent_pid_map.contains_key(&parent) || !visited.insert(parent) {
+            break;
+        }
+        tree.push(parent);
+        current = parent;
+    }
+    Ok(tree)
+}
+
+/// 获取进程所属用户。
+/// # 返回
+/// `域\用户名`，例如`DESKTOP-ABC\Alice`、`NT AUTHORITY\SYSTEM`。
+pub fn get_process_user(process_id: u32) -> Result<String> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+    let mut token_handle = HANDLE::default();
+    let result = unsafe { OpenProcessToken(process_handle, TOKEN_QUERY, &mut token_handle) };
+    let _ = unsafe { CloseHandle(process_handle) };
+    result.map_err(|e| WindowInspectorError::OpenProcessTokenFailed {
+        process_id,
+        error: e,
+    })?;
+
+    // 第一次调用只为获取所需的缓冲区大小。
+    let mut return_length = 0u32;
+    let _ = unsafe { GetTokenInformation(token_handle, TokenUser, None, 0, &mut return_length) };
+    // TOKEN_USER中含有指针，使用usize缓冲区保证对齐。
+    let mut buffer = vec![0usize; (return_length as usize).div_ceil(size_of::<usize>())];
+    let result = unsafe {
+        GetTokenInformation(
+            token_handle,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            (buffer.len() * size_of::<usize>()) as u32,
+            &mut return_length,
+        )
+    };
+    let _ = unsafe { CloseHandle(token_handle) };
+    result.map_err(|e| WindowInspectorError::GetTokenInformationFailed {
+        process_id,
+        error: e,
+    })?;
+    let token_user = unsafe { &*(buffer.as_ptr() as *const TOKEN_USER) };
+
+    let mut name = [0u16; 256];
+    let mut name_len = name.len() as u32;
+    let mut domain = [0u16; 256];
+    let mut domain_len = domain.len() as u32;
+    let mut sid_name_use = SID_NAME_USE::default();
+    unsafe {
+        LookupAccountSidW(
+            None,
+            token_user.User.Sid,
+            PWSTR(name.as_mut_ptr()),
+            &mut name_len,
+            PWSTR(domain.as_mut_ptr()),
+            &mut domain_len,
+            &mut sid_name_use,
+        )
+    }
+    .map_err(|e| WindowInspectorError::LookupAccountSidWFailed {
+        process_id,
+        error: e,
+    })?;
+    let name = String::from_utf16_lossy(&name[..name_len as usize]);
+    let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+    if domain.is_empty() {
+        Ok(name)
+    } else {
+        Ok(format!("{}\\{}", domain, name))
+    }
+}
+
+/// 获取进程所在会话的ID。
+pub fn get_process_session_id(process_id: u32) -> Result<u32> {
+    let mut session_id = 0;
+    unsafe { ProcessIdToSessionId(process_id, &mut session_id) }.map_err(|e| {
+        WindowInspectorError::ProcessIdToSessionIdFailed {
+            process_id,
+            error: e,
+        }
+    })?;
+    Ok(session_id)
+}
+
+/// 判断进程是否与当前进程处于同一会话。
+/// 不同会话中的窗口通常无法操作，且操作往往静默失败。
+pub fn is_same_session_as_caller(process_id: u32) -> Result<bool> {
+    let current_session_id = get_process_session_id(unsafe { GetCurrentProcessId() })?;
+    Ok(get_process_session_id(process_id)? == current_session_id)
+}
+
+/// 进程架构。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessArchitecture {
+    X86,
+    X64,
+    Arm,
+    Arm64,
+    /// 其他架构，值为`IMAGE_FILE_MACHINE_*`常量。
+    Other(u16),
+}
+
+impl From<IMAGE_FILE_MACHINE> for ProcessArchitecture {
+    fn from(machine: IMAGE_FILE_MACHINE) -> Self {
+        match machine {
+            IMAGE_FILE_MACHINE_I386 => ProcessArchitecture::X86,
+            IMAGE_FILE_MACHINE_AMD64 => ProcessArchitecture::X64,
+            IMAGE_FILE_MACHINE_ARMNT => ProcessArchitecture::Arm,
+            IMAGE_FILE_MACHINE_ARM64 => ProcessArchitecture::Arm64,
+            machine => ProcessArchitecture::Other(machine.0),
+        }
+    }
+}
+
+/// 获取进程架构。
+/// 是[`IsWow64Process2`]的封装，需要Windows 10 1709及以上。
+///
+/// [`IsWow64Process2`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/System/Threading/fn.IsWow64Process2.html
+pub fn get_process_architecture(process_id: u32) -> Result<ProcessArchitecture> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+    let mut process_machine = IMAGE_FILE_MACHINE::default();
+    let mut native_machine = IMAGE_FILE_MACHINE::default();
+    let result = unsafe {
+        IsWow64Process2(
+            process_handle,
+            &mut process_machine,
+            Some(&mut native_machine),
+        )
+    };
+    let _ = unsafe { CloseHandle(process_handle) };
+    result.map_err(|e| WindowInspectorError::IsWow64Process2Failed {
+        process_id,
+        error: e,
+    })?;
+    // 不是WOW64进程时，process_machine为IMAGE_FILE_MACHINE_UNKNOWN，进程架构与系统架构相同。
+    if process_machine == IMAGE_FILE_MACHINE_UNKNOWN {
+        Ok(native_machine.into())
+    } else {
+        Ok(process_machine.into())
+    }
+}
+
+/// 可执行文件版本资源中的信息。
+/// 版本资源中不存在的字段为`None`。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessVersionInfo {
+    /// 产品名称，例如`Google Chrome`。
+    pub product_name: Option<String>,
+    /// 文件说明，例如`Google Chrome`。
+    pub file_description: Option<String>,
+    /// 公司名称，例如`Google LLC`。
+    pub company_name: Option<String>,
+    /// 文件版本，例如`126.0.6478.127`。
+    pub file_version: Option<String>,
+    /// 产品版本，例如`126.0.6478.127`。
+    pub product_version: Option<String>,
+}
+
+/// 读取文件的版本资源。
+pub fn get_file_version_info(path: &str) -> Result<ProcessVersionInfo> {
+    let wide_path = HSTRING::from(path);
+    let size = unsafe { GetFileVersionInfoSizeW(&wide_path, None) };
+    if size == 0 {
+        return Err(WindowInspectorError::GetFileVersionInfoWFailed {
+            path: path.to_string(),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    let mut data = vec![0u8; size as usize];
+    unsafe { GetFileVersionInfoW(&wide_path, 0, size, data.as_mut_ptr() as *mut c_void) }.map_err(
+        |e| WindowInspectorError::GetFileVersionInfoWFailed {
+            path: path.to_string(),
+            error: e,
+        },
+    )?;
+
+    fn query_value(data: &[u8], sub_block: &str) -> Option<(*const c_void, u32)> {
+        let mut value = std::ptr::null_mut();
+        let mut len = 0;
+        let ok = unsafe {
+            VerQueryValueW(
+                data.as_ptr() as *const c_void,
+                &HSTRING::from(sub_block),
+                &mut value,
+                &mut len,
+            )
+        }
+        .as_bool();
+        (ok && !value.is_null() && len > 0).then_some((value as *const c_void, len))
+    }
+
+    // 字符串信息按语言和代码页存放，优先使用Translation中的第一项，其次是英语（美国）+ Unicode。
+    let mut translations = vec![(0x0409u16, 0x04b0u16)];
+    if let Some((value, len)) = query_value(&data, "\\VarFileInfo\\Translation") {
+        let pairs =
+            unsafe { std::slice::from_raw_parts(value as *const [u16; 2], len as usize / 4) };
+        translations.splice(0..0, pairs.iter().map(|pair| (pair[0], pair[1])));
+    }
+    let query_string = |name: &str| -> Option<String> {
+        translations.iter().find_map(|(language, code_page)| {
+            let sub_block = format!(
+                "\\StringFileInfo\\{:04x}{:04x}\\{}",
+                language, code_page, name
+            );
+            let (value, len) = query_value(&data, &sub_block)?;
+            let wide = unsafe { std::slice::from_raw_parts(value as *const u16, len as usize) };
+            let s = String::from_utf16_lossy(wide);
+            let s = s.trim_end_matches('\0').trim();
+            (!s.is_empty()).then(|| s.to_string())
+        })
+    };
+    let fixed_version =
+        |ms: u32, ls: u32| format!("{}.{}.{}.{}", ms >> 16, ms & 0xffff, ls >> 16, ls & 0xffff);
+    let fixed_file_info =
+        query_value(&data, "\\").map(|(value, _)| unsafe { *(value as *const VS_FIXEDFILEINFO) });
+
+    Ok(ProcessVersionInfo {
+        product_name: query_string("ProductName"),
+        file_description: query_string("FileDescription"),
+        company_name: query_string("CompanyName"),
+        file_version: fixed_file_info
+            .map(|info| fixed_version(info.dwFileVersionMS, info.dwFileVersionLS))
+            .or_else(|| query_string("FileVersion")),
+        product_version: fixed_file_info
+            .map(|info| fixed_version(info.dwProductVersionMS, info.dwProductVersionLS))
+            .or_else(|| query_string("ProductVersion")),
+    })
+}
+
+/// 获取进程可执行文件的版本信息。
+pub fn get_process_version_info(process_id: u32) -> Result<ProcessVersionInfo> {
+    get_file_version_info(&get_process_path(process_id)?)
+}
+
+/// 获取进程的包全名，只有打包应用（UWP、MSIX）才有。
+fn get_package_full_name(process_id: u32) -> Option<String> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.ok()?;
+    let mut buffer = [0u16; 256];
+    let mut len = buffer.len() as u32;
+    let result =
+        unsafe { GetPackageFullName(process_handle, &mut len, PWSTR(buffer.as_mut_ptr())) };
+    let _ = unsafe { CloseHandle(process_handle) };
+    if result.is_err() || len == 0 {
+        return None;
+    }
+    // len包括结尾的空字符。
+    Some(String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+/// 获取打包应用的显示名称，例如`计算器`。
+fn get_package_display_name(process_id: u32) -> Option<String> {
+    let package_full_name = get_package_full_name(process_id)?;
+    let package = PackageManager::new()
+        .ok()?
+        .FindPackageByUserSecurityIdPackageFullName(
+            &HSTRING::new(),
+            &HSTRING::from(package_full_name),
+        )
+        .ok()?;
+    let display_name = package.DisplayName().ok()?.to_string();
+    (!display_name.is_empty()).then_some(display_name)
+}
+
+/// 获取窗口所属应用的显示名称，例如`Google Chrome`、`计算器`。
+/// 依次尝试：
+/// 1. 打包应用（UWP、MSIX）的包显示名称。
+/// 2. 可执行文件版本资源中的文件说明、产品名称。
+/// 3. 可执行文件名（不含扩展名）。
+///
+/// UWP应用的窗口由`ApplicationFrameHost.exe`承载，此时会解析到实际的应用进程。
+pub fn get_app_display_name(hwnd: isize) -> Result<String> {
+    let mut process_id = get_window_process(hwnd)?;
+    let mut path = get_process_path(process_id)?;
+    if Path::new(&path)
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case("ApplicationFrameHost.exe"))
+    {
+        let core_window = unsafe {
+            FindWindowExW(
+                HWND(hwnd as *mut c_void),
+                None,
+                w!("Windows.UI.Core.CoreWindow"),
+                PCWSTR::null(),
+            )
+        };
+        if let Ok(core_window) = core_window {
+            if let Ok(app_process_id) = get_window_process(core_window.0 as isize) {
+                process_id = app_process_id;
+                path = get_process_path(process_id).unwrap_or(path);
+            }
+        }
+    }
+
+    if let Some(display_name) = get_package_display_name(process_id) {
+        return Ok(display_name);
+    }
+    if let Ok(version_info) = get_file_version_info(&path) {
+        if let Some(name) = version_info.file_description.or(version_info.product_name) {
+            return Ok(name);
+        }
+    }
+    Ok(Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or(path))
+}
+
+/// 进程内存使用情况，单位为字节。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessMemoryInfo {
+    /// 工作集。
+    pub working_set: usize,
+    /// 工作集峰值。
+    pub peak_working_set: usize,
+    /// 专用字节，即任务管理器中的“内存”列。
+    pub private_bytes: usize,
+    /// 页面文件使用量。
+    pub pagefile_usage: usize,
+}
+
+/// 获取进程内存使用情况。
+/// 是[`GetProcessMemoryInfo`]的封装。
+///
+/// [`GetProcessMemoryInfo`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/System/ProcessStatus/fn.GetProcessMemoryInfo.html
+pub fn get_process_memory(process_id: u32) -> Result<ProcessMemoryInfo> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+    let mut counters = PROCESS_MEMORY_COUNTERS_EX {
+        cb: size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+        ..Default::default()
+    };
+    let result = unsafe {
+        GetProcessMemoryInfo(
+            process_handle,
+            &mut counters as *mut _ as *mut PROCESS_MEMORY_COUNTERS,
+            counters.cb,
+        )
+    };
+    let _ = unsafe { CloseHandle(process_handle) };
+    result.map_err(|e| WindowInspectorError::GetProcessMemoryInfoFailed {
+        process_id,
+        error: e,
+    })?;
+    Ok(ProcessMemoryInfo {
+        working_set: counters.WorkingSetSize,
+        peak_working_set: counters.PeakWorkingSetSize,
+        private_bytes: counters.PrivateUsage,
+        pagefile_usage: counters.PagefileUsage,
+    })
+}
+
+/// 进程时间。
+struct ProcessTimes {
+    /// 创建时间。
+    creation_time: FILETIME,
+    /// 内核态与用户态CPU时间之和，单位为100纳秒。
+    cpu_time: u64,
+}
+
+/// 获取进程时间。
+/// 是[`GetProcessTimes`]的封装。
+///
+/// [`GetProcessTimes`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/System/Threading/fn.GetProcessTimes.html
+fn get_process_times(process_id: u32) -> Result<ProcessTimes> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+    let mut creation_time = FILETIME::default();
+    let mut exit_time = FILETIME::default();
+    let mut kernel_time = FILETIME::default();
+    let mut user_time = FILETIME::default();
+    let result = unsafe {
+        GetProcessTimes(
+            process_handle,
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        )
+    };
+    let _ = unsafe { CloseHandle(process_handle) };
+    result.map_err(|e| WindowInspectorError::GetProcessTimesFailed {
+        process_id,
+        error: e,
+    })?;
+    let to_u64 = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+    Ok(ProcessTimes {
+        creation_time,
+        cpu_time: to_u64(kernel_time) + to_u64(user_time),
+    })
+}
+
+/// 进程CPU使用率采样器。
+/// 记录每个进程上一次采样时的CPU时间，[`CpuSampler::sample`]返回两次采样之间的CPU使用率。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::process::CpuSampler;
+///
+/// let mut sampler = CpuSampler::new([1234, 5678]);
+/// sampler.sample();
+/// std::thread::sleep(std::time::Duration::from_secs(1));
+/// for (process_id, percent) in sampler.sample() {
+///     println!("{}: {:.1}%", process_id, percent);
+/// }
+/// ```
+pub struct CpuSampler {
+    /// 进程ID -> 上一次采样（CPU时间，采样时刻）
+    last_samples: HashMap<u32, Option<(u64, Instant)>>,
+    processor_count: u32,
+}
+
+impl CpuSampler {
+    /// 创建采样器。
+    pub fn new(process_ids: impl IntoIterator<Item = u32>) -> Self {
+        let mut system_info = SYSTEM_INFO::default();
+        unsafe { GetSystemInfo(&mut system_info) };
+        Self {
+            last_samples: process_ids.into_iter().map(|id| (id, None)).collect(),
+            processor_count: system_info.dwNumberOfProcessors.max(1),
+        }
+    }
+
+    /// 添加要采样的进程。
+    pub fn add(&mut self, process_id: u32) {
+        self.last_samples.entry(process_id).or_insert(None);
+    }
+
+    /// 移除要采样的进程。
+    pub fn remove(&mut self, process_id: u32) {
+        self.last_samples.remove(&process_id);
+    }
+
+    /// 采样。
+    /// # 返回
+    /// 进程ID -> 与上一次采样之间的CPU使用率（0~100，已按CPU核心数归一化，与任务管理器一致）。
+    /// 第一次采样的进程和无法获取时间的进程（例如已退出）不包含在结果中。
+    pub fn sample(&mut self) -> HashMap<u32, f64> {
+        let mut usage = HashMap::new();
+        for (&process_id, last_sample) in self.last_samples.iter_mut() {
+            let Ok(times) = get_process_times(process_id) else {
+                *last_sample = None;
+                continue;
+            };
+            let now = Instant::now();
+            if let Some((last_cpu_time, last_instant)) = *last_sample {
+                let elapsed = now.duration_since(last_instant).as_secs_f64();
+                if elapsed > 0.0 {
+                    let cpu_seconds = times.cpu_time.saturating_sub(last_cpu_time) as f64 / 1e7;
+                    let percent = cpu_seconds / elapsed / self.processor_count as f64 * 100.0;
+                    usage.insert(process_id, percent.min(100.0));
+                }
+            }
+            *last_sample = Some((times.cpu_time, now));
+        }
+        usage
+    }
+}
+
+/// 获取进程启动时间。
+pub fn get_process_start_time(process_id: u32) -> Result<SystemTime> {
+    let creation_time = get_process_times(process_id)?.creation_time;
+    // FILETIME是从1601-01-01开始的100纳秒数。
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+    let ticks = ((creation_time.dwHighDateTime as u64) << 32) | creation_time.dwLowDateTime as u64;
+    let since_unix_epoch = ticks.saturating_sub(UNIX_EPOCH_AS_FILETIME);
+    Ok(SystemTime::UNIX_EPOCH + Duration::from_nanos(since_unix_epoch * 100))
+}
+
+#[cfg(feature = "ntdll")]
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtSuspendProcess(process_handle: HANDLE) -> NTSTATUS;
+    fn NtResumeProcess(process_handle: HANDLE) -> NTSTATUS;
+}
+
+/// 挂起进程的所有线程。
+/// 使用未公开的`NtSuspendProcess`，挂起期间进程的窗口不会响应任何消息。
+/// 挂起是计数的，需要调用相同次数的[`resume_process`]才能恢复。
+#[cfg(feature = "ntdll")]
+pub fn suspend_process(process_id: u32) -> Result<()> {
+    let process_handle = unsafe { OpenProcess(PROCESS_SUSPEND_RESUME, false, process_id) }
+        .map_err(|e| WindowInspectorError::OpenProcessFailed {
+            process_id,
+            error: e,
+        })?;
+    if dry_run(format_args!("NtSuspendProcess {}", process_id)) {
+        let _ = unsafe { CloseHandle(process_handle) };
+        return Ok(());
+    }
+    let status = unsafe { NtSuspendProcess(process_handle) };
+    let _ = unsafe { CloseHandle(process_handle) };
+    status
+        .ok()
+        .map_err(|e| WindowInspectorError::NtSuspendProcessFailed {
+            process_id,
+            error: e,
+        })
+}
+
+/// 恢复被[`suspend_process`]挂起的进程。
+#[cfg(feature = "ntdll")]
+pub fn resume_process(process_id: u32) -> Result<()> {
+    let process_handle = unsafe { OpenProcess(PROCESS_SUSPEND_RESUME, false, process_id) }
+        .map_err(|e| WindowInspectorError::OpenProcessFailed {
+            process_id,
+            error: e,
+        })?;
+    if dry_run(format_args!("NtResumeProcess {}", process_id)) {
+        let _ = unsafe { CloseHandle(process_handle) };
+        return Ok(());
+    }
+    let status = unsafe { NtResumeProcess(process_handle) };
+    let _ = unsafe { CloseHandle(process_handle) };
+    status
+        .ok()
+        .map_err(|e| WindowInspectorError::NtResumeProcessFailed {
+            process_id,
+            error: e,
+        })
+}
+
+/// 进程优先级。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityClass {
+    /// 低。
+    Idle,
+    /// 低于正常。
+    BelowNormal,
+    /// 正常。
+    Normal,
+    /// 高于正常。
+    AboveNormal,
+    /// 高。
+    High,
+    /// 实时。没有管理员权限时，系统会改为设置为高。
+    Realtime,
+}
+
+impl PriorityClass {
+    fn to_flags(self) -> PROCESS_CREATION_FLAGS {
+        match self {
+            PriorityClass::Idle => IDLE_PRIORITY_CLASS,
+            PriorityClass::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            PriorityClass::Normal => NORMAL_PRIORITY_CLASS,
+            PriorityClass::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+            PriorityClass::High => HIGH_PRIORITY_CLASS,
+            PriorityClass::Realtime => REALTIME_PRIORITY_CLASS,
+        }
+    }
+
+    fn from_flags(flags: u32) -> Option<Self> {
+        [
+            PriorityClass::Idle,
+            PriorityClass::BelowNormal,
+            PriorityClass::Normal,
+            PriorityClass::AboveNormal,
+            PriorityClass::High,
+            PriorityClass::Realtime,
+        ]
+        .into_iter()
+        .find(|priority| priority.to_flags().0 == flags)
+    }
+}
+
+/// 获取进程优先级。
+pub fn get_priority(process_id: u32) -> Result<PriorityClass> {
+    let process_handle =
+        unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) }.map_err(
+            |e| WindowInspectorError::OpenProcessFailed {
+                process_id,
+                error: e,
+            },
+        )?;
+    let flags = unsafe { GetPriorityClass(process_handle) };
+    let error = windows::core::Error::from_win32();
+    let _ = unsafe { CloseHandle(process_handle) };
+    PriorityClass::from_flags(flags)
+        .ok_or(WindowInspectorError::GetPriorityClassFailed { process_id, error })
+}
+
+/// 设置进程优先级。
+pub fn set_priority(process_id: u32, priority: PriorityClass) -> Result<()> {
+    let process_handle = unsafe { OpenProcess(PROCESS_SET_INFORMATION, false, process_id) }
+        .map_err(|e| WindowInspectorError::OpenProcessFailed {
+            process_id,
+            error: e,
+        })?;
+    if dry_run(format_args!(
+        "SetPriorityClass {} to {:?}",
+        process_id, priority
+    )) {
+        let _ = unsafe { CloseHandle(process_handle) };
+        return Ok(());
+    }
+    let result = unsafe { SetPriorityClass(process_handle, priority.to_flags()) };
+    let _ = unsafe { CloseHandle(process_handle) };
+    result.map_err(|e| WindowInspectorError::SetPriorityClassFailed {
+        process_id,
+        error: e,
+    })
+}
+
+/// 获取进程的主窗口。
+/// 在进程的顶层窗口中，选择可见、没有所有者、不是工具窗口的窗口，有标题的优先。
+/// 多个窗口都满足时，选择Z序最靠上的。
+pub fn get_main_window(process_id: u32) -> Result<usize> {
+    let candidates: Vec<usize> = get_process_windows(process_id)?
+        .into_iter()
+        .filter(|&hwnd| {
+            let hwnd = HWND(hwnd as *mut c_void);
+            unsafe { IsWindowVisible(hwnd) }.as_bool()
+                && unsafe { GetWindow(hwnd, GW_OWNER) }.is_err()
+                && unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32 & WS_EX_TOOLWINDOW.0 == 0
+        })
+        .collect();
+    candidates
+        .iter()
+        .find(|&&hwnd| unsafe { GetWindowTextLengthW(HWND(hwnd as *mut c_void)) } > 0)
+        .or(candidates.first())
+        .copied()
+        .ok_or(WindowInspectorError::MainWindowNotExist { process_id })
+}
@@ -0,0 +1,101 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::result::Result;
+
+/// [`with_retry`]的重试策略。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// 最多尝试的次数，包括第一次。默认为3。
+    pub max_attempts: u32,
+    /// 第一次重试前等待的时间。默认为50毫秒。
+    pub delay: Duration,
+    /// 每次重试后等待时间乘以的倍数。默认为2。小于1或不是有限数时按1处理。
+    pub backoff: f64,
+    /// 等待时间的上限。默认为1秒。
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            delay: Duration::from_millis(50),
+            backoff: 2.0,
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// 执行`op`，遇到暂时性的错误（[`WindowInspectorError::is_transient`]）时按`policy`重试。
+/// 其他错误，以及最后一次尝试的错误会直接返回。
+///
+/// # 示例
+/// ```no_run
+/// use window_inspector::find::get_hwnd;
+/// use window_inspector::foreground::set_foreground_window;
+/// use window_inspector::retry::with_retry;
+/// use window_inspector::retry::RetryPolicy;
+///
+/// with_retry(RetryPolicy::default(), || set_foreground_window(get_hwnd("Notepad", "")?)).unwrap();
+/// ```
+///
+/// [`WindowInspectorError::is_transient`]: crate::error::WindowInspectorError::is_transient
+pub fn with_retry<T>(policy: RetryPolicy, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let backoff = if policy.backoff.is_finite() {
+        policy.backoff.max(1.0)
+    } else {
+        1.0
+    };
+    let mut delay = policy.delay;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if e.is_transient() && attempt < policy.max_attempts => {
+                sleep(delay);
+                // 乘积超出Duration的范围时取上限。
+                delay = Duration::try_from_secs_f64(delay.as_secs_f64() * backoff)
+                    .unwrap_or(policy.max_delay)
+                    .min(policy.max_delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[test]
+fn test_with_retry() {
+    use crate::error::WindowInspectorError;
+
+    let policy = RetryPolicy {
+        delay: Duration::ZERO,
+        ..Default::default()
+    };
+    let mut calls = 0;
+    let result = with_retry(policy, || {
+        calls += 1;
+        match calls {
+            1 | 2 => Err(WindowInspectorError::SetForegroundWindowFailed),
+            _ => Ok(calls),
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+
+    let mut calls = 0;
+    let result: Result<()> = with_retry(policy, || {
+        calls += 1;
+        Err(WindowInspectorError::NoMatchingWindow)
+    });
+    assert!(result.is_err());
+    assert_eq!(calls, 1);
+
+    let policy = RetryPolicy {
+        backoff: f64::NAN,
+        ..policy
+    };
+    let result: Result<()> = with_retry(policy, || {
+        Err(WindowInspectorError::SetForegroundWindowFailed)
+    });
+    assert!(result.is_err());
+}
@@ -0,0 +1,170 @@
+//! 限制用户对窗口的操作。
+
+use std::ffi::c_void;
+use std::thread;
+use std::thread::JoinHandle;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::EnableMenuItem;
+use windows::Win32::UI::WindowsAndMessaging::GetSystemMenu;
+use windows::Win32::UI::WindowsAndMessaging::MF_BYCOMMAND;
+use windows::Win32::UI::WindowsAndMessaging::MF_ENABLED;
+use windows::Win32::UI::WindowsAndMessaging::MF_GRAYED;
+use windows::Win32::UI::WindowsAndMessaging::SC_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WS_MINIMIZEBOX;
+
+use crate::error::WindowInspectorError;
+use crate::events::win_events;
+use crate::events::EventFilter;
+use crate::events::EventKinds;
+use crate::events::HookGuard;
+use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::operation::restore_window;
+use crate::result::Result;
+use crate::style::get_window_style;
+use crate::style::set_window_style_scoped;
+use crate::style::StyleGuard;
+
+/// 要阻止的操作，见[`protect_window`]。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Protection {
+    /// 阻止最小化：窗口被最小化后立即还原。
+    pub no_minimize: bool,
+    /// 阻止关闭：禁用关闭按钮和窗口菜单中的“关闭”，`Alt+F4`也随之失效。
+    pub no_close: bool,
+    /// 同时去掉最小化按钮（`WS_MINIMIZEBOX`），只在`no_minimize`为`true`时有效。
+    pub disable_buttons: bool,
+}
+
+/// 保护窗口不被最小化或关闭，drop时恢复。由[`protect_window`]返回。
+#[must_use = "guard被drop时会立即停止保护"]
+#[derive(Debug)]
+pub struct ProtectGuard {
+    hwnd: usize,
+    protection: Protection,
+    style: Option<StyleGuard>,
+    close_disabled: bool,
+    hook: Option<HookGuard>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProtectGuard {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 保护的内容。
+    pub fn protection(&self) -> Protection {
+        self.protection
+    }
+}
+
+impl Drop for ProtectGuard {
+    fn drop(&mut self) {
+        // 卸载钩子后事件接收端断开，后台线程随之结束。
+        if let Some(hook) = self.hook.take() {
+            hook.shutdown();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        if self.close_disabled && is_window_exist(self.hwnd) {
+            set_close_enabled(self.hwnd, true);
+        }
+    }
+}
+
+/// 启用或禁用窗口菜单中的“关闭”，标题栏的关闭按钮随之变化。
+fn set_close_enabled(hwnd: usize, enabled: bool) {
+    let menu = unsafe { GetSystemMenu(HWND(hwnd as *mut c_void), false) };
+    if menu.is_invalid() {
+        return;
+    }
+    // 返回值是原来的状态，没有该菜单项时为-1，都不需要处理。
+    let _ = unsafe {
+        EnableMenuItem(
+            menu,
+            SC_CLOSE,
+            MF_BYCOMMAND | if enabled { MF_ENABLED } else { MF_GRAYED },
+        )
+    };
+}
+
+/// 保护窗口不被用户意外最小化或关闭，例如监控大屏上的窗口。返回的guard被drop时恢复。
+/// 关闭无法撤销，因此`no_close`通过禁用关闭按钮实现，不能阻止其他程序发送`WM_CLOSE`或结束进程。
+/// 有些程序会重建窗口菜单，此时关闭按钮可能重新可用。
+pub fn protect_window(hwnd: usize, protection: Protection) -> Result<ProtectGuard> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let mut guard = ProtectGuard {
+        hwnd,
+        protection,
+        style: None,
+        close_disabled: false,
+        hook: None,
+        thread: None,
+    };
+    if dry_run(format_args!("protect {:#X} {:?}", hwnd, protection)) {
+        return Ok(guard);
+    }
+    if protection.no_minimize {
+        if protection.disable_buttons {
+            let style = get_window_style(hwnd)?;
+            guard.style = Some(set_window_style_scoped(hwnd, style & !WS_MINIMIZEBOX.0)?);
+        }
+        let events = win_events(&EventFilter {
+            hwnd: Some(hwnd),
+            event_kinds: EventKinds::MINIMIZE,
+            ..Default::default()
+        })?;
+        let (receiver, hook) = events.into_parts();
+        guard.hook = Some(hook);
+        guard.thread = Some(thread::spawn(move || {
+            for _ in receiver.iter() {
+                if restore_window(hwnd).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    if protection.no_close {
+        set_close_enabled(hwnd, false);
+        guard.close_disabled = true;
+    }
+    Ok(guard)
+}
+
+#[test]
+fn test_protect_window() {
+    use windows::Win32::UI::WindowsAndMessaging::GetMenuState;
+
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let is_close_grayed = || {
+        let state = unsafe {
+            GetMenuState(
+                GetSystemMenu(HWND(window.hwnd() as *mut c_void), false),
+                SC_CLOSE,
+                MF_BYCOMMAND,
+            )
+        };
+        state & MF_GRAYED.0 != 0
+    };
+    let guard = protect_window(
+        window.hwnd(),
+        Protection {
+            no_close: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(is_close_grayed());
+    drop(guard);
+    assert!(!is_close_grayed());
+}
@@ -0,0 +1,190 @@
+//! 桌面、Shell的状态。
+
+use std::ffi::c_void;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use windows::core::Interface;
+use windows::core::VARIANT;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::ERROR_ACCESS_DENIED;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::CoCreateInstance;
+use windows::Win32::System::Com::CoTaskMemFree;
+use windows::Win32::System::Com::IServiceProvider;
+use windows::Win32::System::Com::CLSCTX_ALL;
+use windows::Win32::System::DataExchange::GetClipboardOwner;
+use windows::Win32::System::DataExchange::GetOpenClipboardWindow;
+use windows::Win32::System::StationsAndDesktops::CloseDesktop;
+use windows::Win32::System::StationsAndDesktops::GetUserObjectInformationW;
+use windows::Win32::System::StationsAndDesktops::OpenInputDesktop;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_CONTROL_FLAGS;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_READOBJECTS;
+use windows::Win32::System::StationsAndDesktops::UOI_NAME;
+use windows::Win32::UI::Shell::IFolderView;
+use windows::Win32::UI::Shell::IPersistFolder2;
+use windows::Win32::UI::Shell::IShellBrowser;
+use windows::Win32::UI::Shell::IShellWindows;
+use windows::Win32::UI::Shell::IWebBrowserApp;
+use windows::Win32::UI::Shell::SHGetNameFromIDList;
+use windows::Win32::UI::Shell::SID_STopLevelBrowser;
+use windows::Win32::UI::Shell::ShellWindows;
+use windows::Win32::UI::Shell::SIGDN_DESKTOPABSOLUTEPARSING;
+use windows::Win32::UI::WindowsAndMessaging::SystemParametersInfoW;
+use windows::Win32::UI::WindowsAndMessaging::SPI_GETSCREENSAVERRUNNING;
+use windows::Win32::UI::WindowsAndMessaging::SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS;
+
+use crate::com::ComGuard;
+use crate::error::WindowInspectorError;
+use crate::events::display_power_events;
+use crate::events::DisplayPower;
+use crate::exist::is_window_exist;
+use crate::result::Result;
+
+/// 安全桌面（UAC提示、锁屏、Ctrl+Alt+Del界面）是否正在接收输入。
+/// 此时普通程序无法操作任何窗口，窗口操作的失败不是程序的问题，应等待安全桌面退出后重试。
+/// 通过`OpenInputDesktop`判断：普通程序无权打开安全桌面，能打开时比较桌面名是否为`Default`。
+pub fn is_secure_desktop_active() -> Result<bool> {
+    let desktop =
+        match unsafe { OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS) } {
+            Ok(desktop) => desktop,
+            Err(e) if e.code() == ERROR_ACCESS_DENIED.to_hresult() => return Ok(true),
+            Err(e) => return Err(WindowInspectorError::OpenInputDesktopFailed { error: e }),
+        };
+    let mut name = [0u16; 256];
+    let result = unsafe {
+        GetUserObjectInformationW(
+            HANDLE(desktop.0),
+            UOI_NAME,
+            Some(name.as_mut_ptr().cast()),
+            (name.len() * 2) as u32,
+            None,
+        )
+    };
+    let _ = unsafe { CloseDesktop(desktop) };
+    result.map_err(|e| WindowInspectorError::GetUserObjectInformationWFailed { error: e })?;
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Ok(!String::from_utf16_lossy(&name[..len]).eq_ignore_ascii_case("Default"))
+}
+
+/// 屏幕保护程序是否正在运行。轮询、使用时长统计等可以在此期间暂停。
+pub fn is_screensaver_running() -> Result<bool> {
+    let mut running = BOOL::default();
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETSCREENSAVERRUNNING,
+            0,
+            Some((&mut running as *mut BOOL).cast()),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .map_err(|e| WindowInspectorError::SystemParametersInfoWFailed { error: e })?;
+    Ok(running.as_bool())
+}
+
+/// 由后台订阅维护的显示器电源状态，见[`is_display_on`]。
+#[derive(Default)]
+struct DisplayPowerState {
+    /// 是否已经启动后台订阅。
+    listening: bool,
+    /// 最近一次收到的状态。
+    power: Option<DisplayPower>,
+}
+
+lazy_static! {
+    static ref DISPLAY_POWER: Mutex<DisplayPowerState> = Mutex::new(DisplayPowerState::default());
+}
+
+/// 显示器是否打开（变暗也视为打开）。
+/// 第一次调用时启动一个在进程生命周期内一直存在的[`display_power_events`]订阅，并等待系统发送当前状态（最多1秒），
+/// 之后的调用直接返回订阅维护的最新状态。系统没有发送当前状态时视为打开。
+pub fn is_display_on() -> Result<bool> {
+    let mut state = DISPLAY_POWER.lock().unwrap();
+    if !state.listening {
+        let events = display_power_events()?;
+        state.power = events.recv_timeout(Duration::from_secs(1));
+        state.listening = true;
+        thread::spawn(move || {
+            for power in events.iter() {
+                DISPLAY_POWER.lock().unwrap().power = Some(power);
+            }
+        });
+    }
+    Ok(state.power != Some(DisplayPower::Off))
+}
+
+/// 获取资源管理器窗口当前显示的文件夹。
+fn get_shell_browser_path(browser: &IWebBrowserApp) -> windows::core::Result<String> {
+    unsafe {
+        let shell_browser: IShellBrowser = browser
+            .cast::<IServiceProvider>()?
+            .QueryService(&SID_STopLevelBrowser)?;
+        let folder: IPersistFolder2 = shell_browser
+            .QueryActiveShellView()?
+            .cast::<IFolderView>()?
+            .GetFolder()?;
+        let pidl = folder.GetCurFolder()?;
+        let name = SHGetNameFromIDList(pidl, SIGDN_DESKTOPABSOLUTEPARSING);
+        CoTaskMemFree(Some(pidl as *const c_void));
+        let name = name?;
+        let path = name.to_string();
+        CoTaskMemFree(Some(name.0 as *const c_void));
+        Ok(path?)
+    }
+}
+
+/// 获取资源管理器窗口当前显示的文件夹路径，例如`C:\Users`。窗口不是资源管理器窗口时返回`None`。
+/// 窗口标题通常不包含完整路径，这个函数通过`IShellWindows`找到窗口对应的浏览器对象再获取路径。
+/// “此电脑”等虚拟文件夹返回形如`::{20D04FE0-3AEA-1069-A2D8-08002B30309D}`的解析名称。
+/// Windows 11有多个标签页时返回其中一个标签页的路径。
+pub fn get_explorer_window_path(hwnd: usize) -> Result<Option<String>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let _com = ComGuard::new()?;
+    let shell_windows: IShellWindows = unsafe { CoCreateInstance(&ShellWindows, None, CLSCTX_ALL) }
+        .map_err(|e| WindowInspectorError::ShellWindowsFailed { error: e })?;
+    let count = unsafe { shell_windows.Count() }
+        .map_err(|e| WindowInspectorError::ShellWindowsFailed { error: e })?;
+    for index in 0..count {
+        let Ok(item) = (unsafe { shell_windows.Item(&VARIANT::from(index)) }) else {
+            continue;
+        };
+        let Ok(browser) = item.cast::<IWebBrowserApp>() else {
+            continue;
+        };
+        if unsafe { browser.HWND() }.is_ok_and(|browser_hwnd| browser_hwnd.0 as usize == hwnd) {
+            return get_shell_browser_path(&browser).map(Some).map_err(|e| {
+                WindowInspectorError::GetExplorerWindowPathFailed {
+                    hwnd: HWND(hwnd as *mut c_void),
+                    error: e,
+                }
+            });
+        }
+    }
+    Ok(None)
+}
+
+/// 获取剪贴板的所有者窗口，即最后一次清空剪贴板并写入内容的窗口，没有所有者时返回`None`。
+/// 所有者窗口可能已经关闭，调用方应检查窗口是否存在。
+pub fn get_clipboard_owner_window() -> Option<usize> {
+    unsafe { GetClipboardOwner() }
+        .ok()
+        .filter(|hwnd| !hwnd.is_invalid())
+        .map(|hwnd| hwnd.0 as usize)
+}
+
+/// 获取当前打开剪贴板的窗口，没有窗口打开剪贴板时返回`None`。
+/// 某个程序长时间打开剪贴板不关闭时，其他程序无法读写剪贴板，可以用这个函数找出是哪个程序（配合[`get_window_process`](crate::process::get_window_process)）。
+/// 以`NULL`窗口打开剪贴板时也返回`None`。
+pub fn get_open_clipboard_window() -> Option<usize> {
+    unsafe { GetOpenClipboardWindow() }
+        .ok()
+        .filter(|hwnd| !hwnd.is_invalid())
+        .map(|hwnd| hwnd.0 as usize)
+}
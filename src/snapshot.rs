@@ -0,0 +1,143 @@
+//! 桌面快照：一次获取所有顶层窗口的常用信息。
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::enumerate::get_top_level_windows_filtered;
+use crate::enumerate::FilterOptions;
+use crate::information::query;
+use crate::information::QueryFlags;
+use crate::monitor::get_window_monitor;
+use crate::process::get_process_path;
+use crate::result::Result;
+
+/// 快照中的一个窗口。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowSnapshot {
+    /// 窗口句柄。
+    pub hwnd: usize,
+    /// 窗口类名。
+    pub class: String,
+    /// 窗口标题，没有标题时为空字符串。
+    pub title: String,
+    /// 所属进程ID。
+    pub process_id: u32,
+    /// 所属进程路径，没有权限读取时（例如以管理员权限运行的进程）为`None`。
+    pub process_path: Option<String>,
+    /// 窗口位置尺寸（包括阴影），(x, y, width, height)。
+    pub window_xywh: (i32, i32, u32, u32),
+    /// 客户区位置尺寸，(x, y, width, height)。
+    pub client_xywh: (i32, i32, u32, u32),
+    /// 所在的显示器，见[`get_window_monitor`]。
+    pub monitor: usize,
+    /// 是否置顶。
+    pub top_most: bool,
+    /// 是否可见。
+    pub visible: bool,
+    /// 是否最小化。
+    pub minimized: bool,
+}
+
+/// 桌面快照，由[`capture_desktop`]返回。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopSnapshot {
+    /// 获取快照的时间。
+    pub time: SystemTime,
+    /// 所有顶层窗口，按Z序从上到下排列。
+    pub windows: Vec<WindowSnapshot>,
+}
+
+/// 对每一项执行`f`。启用`rayon` feature时在线程池中并行执行。
+fn map_all<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    #[cfg(feature = "rayon")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// 获取窗口信息，不包括进程路径。窗口在获取过程中关闭时返回`None`。
+fn capture_window(hwnd: usize) -> Option<WindowSnapshot> {
+    let info = query(
+        hwnd,
+        QueryFlags::CLASS
+            | QueryFlags::TITLE
+            | QueryFlags::PROCESS_ID
+            | QueryFlags::WINDOW_XYWH_INCLUDE_SHADOW
+            | QueryFlags::CLIENT_XYWH
+            | QueryFlags::TOP_MOST
+            | QueryFlags::VISIBLE
+            | QueryFlags::MINIMIZED,
+    )
+    .ok()?;
+    Some(WindowSnapshot {
+        hwnd,
+        class: info.class?,
+        title: info.title?,
+        process_id: info.process_id?,
+        process_path: None,
+        window_xywh: info.window_xywh_include_shadow?,
+        client_xywh: info.client_xywh?,
+        monitor: get_window_monitor(hwnd).ok()?,
+        top_most: info.top_most?,
+        visible: info.visible?,
+        minimized: info.minimized?,
+    })
+}
+
+/// 获取所有顶层窗口（包括不可见窗口）的类名、标题、进程、位置尺寸等信息。
+/// 快照期间关闭的窗口会被跳过。每个进程的路径只获取一次。
+///
+/// 启用`rayon` feature时，各窗口的信息和进程路径在线程池中并行获取，窗口很多时明显更快。
+/// 注意并行获取时不会使用[`with_backend`](crate::backend::with_backend)设置的后端。
+pub fn capture_desktop() -> Result<DesktopSnapshot> {
+    capture_desktop_filtered(&FilterOptions::default())
+}
+
+/// 同[`capture_desktop`]，但只包括满足`options`的窗口。
+pub fn capture_desktop_filtered(options: &FilterOptions) -> Result<DesktopSnapshot> {
+    let time = SystemTime::now();
+    let hwnds = get_top_level_windows_filtered(options)?;
+    let mut windows: Vec<WindowSnapshot> = map_all(&hwnds, |&hwnd| capture_window(hwnd))
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut process_ids: Vec<u32> = windows.iter().map(|window| window.process_id).collect();
+    process_ids.sort_unstable();
+    process_ids.dedup();
+    let paths: HashMap<u32, String> = map_all(&process_ids, |&process_id| {
+        get_process_path(process_id)
+            .ok()
+            .map(|path| (process_id, path))
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    for window in &mut windows {
+        window.process_path = paths.get(&window.process_id).cloned();
+    }
+
+    Ok(DesktopSnapshot { time, windows })
+}
+
+#[test]
+fn test_capture_desktop() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let snapshot = capture_desktop().unwrap();
+    let captured = snapshot
+        .windows
+        .iter()
+        .find(|captured| captured.hwnd == window.hwnd())
+        .unwrap();
+    assert_eq!(captured.process_id, std::process::id());
+    assert!(captured.process_path.is_some());
+}
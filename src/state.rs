@@ -0,0 +1,152 @@
+//! 窗口状态。
+
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use lazy_static::lazy_static;
+use windows::core::s;
+use windows::core::w;
+use windows::core::PCSTR;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
+use windows::Win32::Graphics::Dwm::DWMWA_CLOAKED;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::LibraryLoader::GetProcAddress;
+use windows::Win32::UI::WindowsAndMessaging::GetWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::IsHungAppWindow;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::GW_OWNER;
+use windows::Win32::UI::WindowsAndMessaging::WS_CHILD;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_APPWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::result::Result;
+
+/// `HungWindowFromGhostWindow`、`GhostWindowFromHungWindow`的类型。
+type WindowMapFn = unsafe extern "system" fn(HWND) -> HWND;
+
+/// 从user32.dll中获取未公开的函数。
+fn load_user32_function(name: PCSTR) -> Option<WindowMapFn> {
+    let module = unsafe { GetModuleHandleW(w!("user32.dll")) }.ok()?;
+    let function = unsafe { GetProcAddress(module, name) }?;
+    Some(unsafe {
+        std::mem::transmute::<unsafe extern "system" fn() -> isize, WindowMapFn>(function)
+    })
+}
+
+lazy_static! {
+    static ref HUNG_WINDOW_FROM_GHOST_WINDOW: Option<WindowMapFn> =
+        load_user32_function(s!("HungWindowFromGhostWindow"));
+    static ref GHOST_WINDOW_FROM_HUNG_WINDOW: Option<WindowMapFn> =
+        load_user32_function(s!("GhostWindowFromHungWindow"));
+}
+
+/// 窗口是否未响应（`IsHungAppWindow`）。窗口5秒内没有处理消息时视为未响应。
+pub fn is_window_hung(hwnd: usize) -> Result<bool> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    Ok(unsafe { IsHungAppWindow(HWND(hwnd as *mut c_void)) }.as_bool())
+}
+
+/// 如果窗口是系统为未响应的窗口创建的幽灵窗口（标题带有“未响应”、类名为`Ghost`的窗口），返回真正的未响应窗口，否则返回窗口本身。
+/// 枚举窗口时，未响应的程序会同时出现幽灵窗口和原窗口，对幽灵窗口的操作也不会作用到原窗口，可以先用这个函数转换。
+/// 使用未公开的`HungWindowFromGhostWindow`，系统不支持时返回错误。
+pub fn resolve_ghost_window(hwnd: usize) -> Result<usize> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let hung_window_from_ghost_window =
+        HUNG_WINDOW_FROM_GHOST_WINDOW.ok_or(WindowInspectorError::FunctionNotAvailable {
+            function: "HungWindowFromGhostWindow",
+        })?;
+    let hung = unsafe { hung_window_from_ghost_window(HWND(hwnd as *mut c_void)) };
+    Ok(if hung.is_invalid() {
+        hwnd
+    } else {
+        hung.0 as usize
+    })
+}
+
+/// 获取未响应的窗口对应的幽灵窗口，窗口没有幽灵窗口时返回`None`。
+/// 使用未公开的`GhostWindowFromHungWindow`，系统不支持时返回错误。
+pub fn get_ghost_window(hwnd: usize) -> Result<Option<usize>> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let ghost_window_from_hung_window =
+        GHOST_WINDOW_FROM_HUNG_WINDOW.ok_or(WindowInspectorError::FunctionNotAvailable {
+            function: "GhostWindowFromHungWindow",
+        })?;
+    let ghost = unsafe { ghost_window_from_hung_window(HWND(hwnd as *mut c_void)) };
+    Ok((!ghost.is_invalid()).then_some(ghost.0 as usize))
+}
+
+/// 窗口是否是幽灵窗口。
+pub fn is_ghost_window(hwnd: usize) -> Result<bool> {
+    Ok(resolve_ghost_window(hwnd)? != hwnd)
+}
+
+/// 窗口是否被DWM隐藏（cloaked）。被隐藏的窗口`IsWindowVisible`仍为真，但屏幕上看不到，
+/// 例如位于其他虚拟桌面的窗口、挂起的UWP应用窗口。
+pub fn is_window_cloaked(hwnd: usize) -> Result<bool> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let mut cloaked = 0u32;
+    unsafe {
+        DwmGetWindowAttribute(
+            HWND(hwnd as *mut c_void),
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut _ as *mut _,
+            size_of::<u32>() as u32,
+        )
+    }
+    .map_err(|e| WindowInspectorError::DwmGetWindowAttributeFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })?;
+    Ok(cloaked != 0)
+}
+
+/// 窗口在任务栏上是否有按钮，按任务栏的规则判断：
+/// 窗口是可见的顶层窗口且未被隐藏（见[`is_window_cloaked`]）；有`WS_EX_APPWINDOW`时总有按钮；
+/// 否则有`WS_EX_TOOLWINDOW`或有所有者窗口时没有按钮。
+/// 无法得知程序是否通过`ITaskbarList::DeleteTab`等方式手动移除了按钮。
+pub fn has_taskbar_button(hwnd: usize) -> Result<bool> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let style = unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_STYLE) } as u32;
+    if style & WS_CHILD.0 != 0
+        || !unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool()
+        || is_window_cloaked(hwnd)?
+    {
+        return Ok(false);
+    }
+    let ex_style = unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } as u32;
+    if ex_style & WS_EX_APPWINDOW.0 != 0 {
+        return Ok(true);
+    }
+    if ex_style & WS_EX_TOOLWINDOW.0 != 0 {
+        return Ok(false);
+    }
+    let has_owner = unsafe { GetWindow(HWND(hwnd as *mut c_void), GW_OWNER) }
+        .is_ok_and(|owner| !owner.is_invalid());
+    Ok(!has_owner)
+}
@@ -0,0 +1,88 @@
+//! 桌面窗口统计。
+
+use std::collections::HashMap;
+
+use crate::result::Result;
+use crate::snapshot::capture_desktop;
+use crate::snapshot::DesktopSnapshot;
+
+/// 桌面窗口的统计信息，由[`desktop_summary`]、[`summarize`]返回。
+/// 只统计顶层窗口。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DesktopSummary {
+    /// 窗口总数，包括不可见窗口。
+    pub total: usize,
+    /// 可见窗口数。
+    pub visible: usize,
+    /// 不可见窗口数。
+    pub hidden: usize,
+    /// 最小化的窗口数。
+    pub minimized: usize,
+    /// 置顶窗口数。
+    pub top_most: usize,
+    /// 每个进程ID的窗口数。
+    pub per_process: HashMap<u32, usize>,
+    /// 每个显示器的可见窗口数。
+    pub per_monitor: HashMap<usize, usize>,
+}
+
+/// 统计快照中的窗口。
+pub fn summarize(snapshot: &DesktopSnapshot) -> DesktopSummary {
+    let mut summary = DesktopSummary::default();
+    for window in &snapshot.windows {
+        summary.total += 1;
+        if window.visible {
+            summary.visible += 1;
+            *summary.per_monitor.entry(window.monitor).or_default() += 1;
+        } else {
+            summary.hidden += 1;
+        }
+        if window.minimized {
+            summary.minimized += 1;
+        }
+        if window.top_most {
+            summary.top_most += 1;
+        }
+        *summary.per_process.entry(window.process_id).or_default() += 1;
+    }
+    summary
+}
+
+/// 获取桌面快照并统计。适合监控程序定期上报，不需要传输完整的快照。
+pub fn desktop_summary() -> Result<DesktopSummary> {
+    Ok(summarize(&capture_desktop()?))
+}
+
+#[test]
+fn test_summarize() {
+    use std::time::SystemTime;
+
+    use crate::snapshot::WindowSnapshot;
+
+    let window = |process_id, visible, top_most| WindowSnapshot {
+        hwnd: 0,
+        class: String::new(),
+        title: String::new(),
+        process_id,
+        process_path: None,
+        window_xywh: (0, 0, 0, 0),
+        client_xywh: (0, 0, 0, 0),
+        monitor: 1,
+        top_most,
+        visible,
+        minimized: false,
+    };
+    let snapshot = DesktopSnapshot {
+        time: SystemTime::now(),
+        windows: vec![
+            window(1, true, true),
+            window(1, false, false),
+            window(2, true, false),
+        ],
+    };
+    let summary = summarize(&snapshot);
+    assert_eq!((summary.total, summary.visible, summary.hidden), (3, 2, 1));
+    assert_eq!(summary.top_most, 1);
+    assert_eq!(summary.per_process[&1], 2);
+    assert_eq!(summary.per_monitor[&1], 2);
+}
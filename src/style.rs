@@ -0,0 +1,292 @@
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::SetLastError;
+use windows::Win32::Foundation::COLORREF;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::WIN32_ERROR;
+use windows::Win32::UI::WindowsAndMessaging::GetLayeredWindowAttributes;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::SetLayeredWindowAttributes;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::LAYERED_WINDOW_ATTRIBUTES_FLAGS;
+use windows::Win32::UI::WindowsAndMessaging::LWA_ALPHA;
+use windows::Win32::UI::WindowsAndMessaging::SWP_FRAMECHANGED;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_LONG_PTR_INDEX;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
+
+use crate::error::WindowInspectorError;
+use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::result::Result;
+
+/// 读取窗口的样式或扩展样式。
+fn get_window_long(hwnd: usize, index: WINDOW_LONG_PTR_INDEX) -> Result<u32> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    // 样式可能本来就是0，需要通过GetLastError区分失败。
+    unsafe { SetLastError(WIN32_ERROR(0)) };
+    match unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), index) } {
+        0 => match unsafe { GetLastError() } {
+            WIN32_ERROR(0) => Ok(0),
+            error => Err(WindowInspectorError::GetWindowLongWFailed {
+                error: windows::core::Error::from_hresult(error.to_hresult()),
+            }),
+        },
+        n => Ok(n as u32),
+    }
+}
+
+/// 设置窗口的样式或扩展样式，并通知窗口重新计算边框。
+fn set_window_long(hwnd: usize, index: WINDOW_LONG_PTR_INDEX, value: u32) -> Result<()> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    if dry_run(format_args!(
+        "SetWindowLongW {:#X} {:?} to {:#X}",
+        hwnd, index, value
+    )) {
+        return Ok(());
+    }
+    unsafe { SetLastError(WIN32_ERROR(0)) };
+    if unsafe { SetWindowLongW(HWND(hwnd as *mut c_void), index, value as i32) } == 0 {
+        let error = unsafe { GetLastError() };
+        if error != WIN32_ERROR(0) {
+            return Err(WindowInspectorError::SetWindowLongWFailed {
+                hwnd: HWND(hwnd as *mut c_void),
+                error: windows::core::Error::from_hresult(error.to_hresult()),
+            });
+        }
+    }
+    unsafe {
+        SetWindowPos(
+            HWND(hwnd as *mut c_void),
+            None,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+        )
+    }
+    .map_err(|e| WindowInspectorError::SetWindowPosFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+}
+
+/// 获取窗口样式（`GWL_STYLE`），即`WS_*`的组合。
+pub fn get_window_style(hwnd: usize) -> Result<u32> {
+    get_window_long(hwnd, GWL_STYLE)
+}
+
+/// 设置窗口样式（`GWL_STYLE`）。
+pub fn set_window_style(hwnd: usize, style: u32) -> Result<()> {
+    set_window_long(hwnd, GWL_STYLE, style)
+}
+
+/// 获取窗口扩展样式（`GWL_EXSTYLE`），即`WS_EX_*`的组合。
+pub fn get_window_ex_style(hwnd: usize) -> Result<u32> {
+    get_window_long(hwnd, GWL_EXSTYLE)
+}
+
+/// 设置窗口扩展样式（`GWL_EXSTYLE`）。
+pub fn set_window_ex_style(hwnd: usize, ex_style: u32) -> Result<()> {
+    set_window_long(hwnd, GWL_EXSTYLE, ex_style)
+}
+
+/// 在drop时恢复窗口原来的样式或扩展样式。
+/// 由[`set_window_style_scoped`]或[`set_window_ex_style_scoped`]返回。
+/// 只恢复设置时改变了的位，期间其他位的变化（例如最大化、置顶）会保留。
+/// 恢复时窗口已经不存在或恢复失败都会被忽略。
+#[must_use = "guard被drop时会立即恢复原来的样式"]
+#[derive(Debug)]
+pub struct StyleGuard {
+    hwnd: usize,
+    index: WINDOW_LONG_PTR_INDEX,
+    original: u32,
+    /// 设置时改变了的位。
+    mask: u32,
+}
+
+impl StyleGuard {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 修改前的样式。
+    pub fn original(&self) -> u32 {
+        self.original
+    }
+}
+
+/// 把`current`中`mask`的位恢复为`original`中的值。
+fn restore_bits(current: u32, original: u32, mask: u32) -> u32 {
+    current & !mask | original & mask
+}
+
+impl Drop for StyleGuard {
+    fn drop(&mut self) {
+        let Ok(current) = get_window_long(self.hwnd, self.index) else {
+            return;
+        };
+        let restored = restore_bits(current, self.original, self.mask);
+        if restored != current {
+            let _ = set_window_long(self.hwnd, self.index, restored);
+        }
+    }
+}
+
+/// 设置窗口样式，返回的guard被drop时恢复原来的样式。
+pub fn set_window_style_scoped(hwnd: usize, style: u32) -> Result<StyleGuard> {
+    let original = get_window_style(hwnd)?;
+    set_window_style(hwnd, style)?;
+    Ok(StyleGuard {
+        hwnd,
+        index: GWL_STYLE,
+        original,
+        mask: original ^ style,
+    })
+}
+
+/// 设置窗口扩展样式，返回的guard被drop时恢复原来的扩展样式。
+pub fn set_window_ex_style_scoped(hwnd: usize, ex_style: u32) -> Result<StyleGuard> {
+    let original = get_window_ex_style(hwnd)?;
+    set_window_ex_style(hwnd, ex_style)?;
+    Ok(StyleGuard {
+        hwnd,
+        index: GWL_EXSTYLE,
+        original,
+        mask: original ^ ex_style,
+    })
+}
+
+/// 获取窗口不透明度，0为完全透明，255为完全不透明。
+/// 不是分层窗口（没有`WS_EX_LAYERED`）或分层窗口没有设置透明度时返回255。
+pub fn get_window_opacity(hwnd: usize) -> Result<u8> {
+    if get_window_ex_style(hwnd)? & WS_EX_LAYERED.0 == 0 {
+        return Ok(255);
+    }
+    let mut alpha = 255;
+    let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS::default();
+    // 用UpdateLayeredWindow绘制的分层窗口会失败，这类窗口的透明度无法获取，视为不透明。
+    if unsafe {
+        GetLayeredWindowAttributes(
+            HWND(hwnd as *mut c_void),
+            None,
+            Some(&mut alpha),
+            Some(&mut flags),
+        )
+    }
+    .is_err()
+        || !flags.contains(LWA_ALPHA)
+    {
+        return Ok(255);
+    }
+    Ok(alpha)
+}
+
+/// 设置窗口不透明度，0为完全透明，255为完全不透明。
+/// 会给窗口加上`WS_EX_LAYERED`扩展样式。
+pub fn set_window_opacity(hwnd: usize, opacity: u8) -> Result<()> {
+    let ex_style = get_window_ex_style(hwnd)?;
+    if dry_run(format_args!("set opacity of {:#X} to {}", hwnd, opacity)) {
+        return Ok(());
+    }
+    if ex_style & WS_EX_LAYERED.0 == 0 {
+        set_window_ex_style(hwnd, ex_style | WS_EX_LAYERED.0)?;
+    }
+    unsafe {
+        SetLayeredWindowAttributes(HWND(hwnd as *mut c_void), COLORREF(0), opacity, LWA_ALPHA)
+    }
+    .map_err(|e| WindowInspectorError::SetLayeredWindowAttributesFailed {
+        hwnd: HWND(hwnd as *mut c_void),
+        error: e,
+    })
+}
+
+/// 在drop时恢复窗口原来的不透明度。
+/// 由[`set_window_opacity_scoped`]返回。原来不是分层窗口时，恢复时只去掉`WS_EX_LAYERED`，不影响其他扩展样式。
+/// 恢复时窗口已经不存在或恢复失败都会被忽略。
+#[must_use = "guard被drop时会立即恢复原来的不透明度"]
+#[derive(Debug)]
+pub struct OpacityGuard {
+    hwnd: usize,
+    original_ex_style: u32,
+    original_opacity: u8,
+}
+
+impl OpacityGuard {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 修改前的不透明度。
+    pub fn original(&self) -> u8 {
+        self.original_opacity
+    }
+}
+
+impl Drop for OpacityGuard {
+    fn drop(&mut self) {
+        if !is_window_exist(self.hwnd) {
+            return;
+        }
+        if self.original_ex_style & WS_EX_LAYERED.0 == 0 {
+            if let Ok(current) = get_window_ex_style(self.hwnd) {
+                let _ = set_window_ex_style(
+                    self.hwnd,
+                    restore_bits(current, self.original_ex_style, WS_EX_LAYERED.0),
+                );
+            }
+        } else {
+            let _ = set_window_opacity(self.hwnd, self.original_opacity);
+        }
+    }
+}
+
+/// 设置窗口不透明度，返回的guard被drop时恢复原来的不透明度。
+pub fn set_window_opacity_scoped(hwnd: usize, opacity: u8) -> Result<OpacityGuard> {
+    let original_ex_style = get_window_ex_style(hwnd)?;
+    let original_opacity = get_window_opacity(hwnd)?;
+    set_window_opacity(hwnd, opacity)?;
+    Ok(OpacityGuard {
+        hwnd,
+        original_ex_style,
+        original_opacity,
+    })
+}
+
+#[test]
+fn test_restore_bits() {
+    // 设置时去掉了0b0010，期间窗口加上了0b1000。
+    assert_eq!(restore_bits(0b1001, 0b0011, 0b0010), 0b1011);
+}
+
+#[test]
+fn test_opacity_guard() {
+    use crate::test_support::TestWindowBuilder;
+
+    let window = TestWindowBuilder::new().build().unwrap();
+    let hwnd = window.hwnd();
+    {
+        let _guard = set_window_opacity_scoped(hwnd, 128).unwrap();
+        assert_eq!(get_window_opacity(hwnd).unwrap(), 128);
+    }
+    assert_eq!(get_window_opacity(hwnd).unwrap(), 255);
+    assert_eq!(get_window_ex_style(hwnd).unwrap() & WS_EX_LAYERED.0, 0);
+}
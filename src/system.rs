@@ -0,0 +1,90 @@
+//! 窗口站与桌面。
+//!
+//! 其他函数只能看到调用线程所在桌面（通常是`WinSta0\Default`）中的窗口。
+//! 锁屏、UAC提示等位于其他桌面（`Winlogon`、安全桌面），需要用这里的函数枚举。
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::System::StationsAndDesktops::CloseDesktop;
+use windows::Win32::System::StationsAndDesktops::EnumDesktopWindows;
+use windows::Win32::System::StationsAndDesktops::EnumDesktopsW;
+use windows::Win32::System::StationsAndDesktops::GetProcessWindowStation;
+use windows::Win32::System::StationsAndDesktops::OpenDesktopW;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_CONTROL_FLAGS;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_ENUMERATE;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_READOBJECTS;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+use crate::wide::to_wide;
+
+unsafe extern "system" fn enum_desktops_proc(name: PCWSTR, lparam: LPARAM) -> BOOL {
+    let desktops = &mut *(lparam.0 as *mut Vec<String>);
+    desktops.push(String::from_utf16_lossy(name.as_wide()));
+    true.into()
+}
+
+unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam.0 as *mut Vec<usize>);
+    windows.push(hwnd.0 as usize);
+    true.into()
+}
+
+/// 获取当前进程所在窗口站（通常是`WinSta0`）中的所有桌面名，例如`Default`、`Winlogon`。
+/// 窗口站需要`WINSTA_ENUMDESKTOPS`权限，服务进程所在的非交互窗口站中只有服务自己的桌面。
+pub fn enumerate_desktops() -> Result<Vec<String>> {
+    let window_station = unsafe { GetProcessWindowStation() }
+        .map_err(|e| WindowInspectorError::EnumDesktopsWFailed { error: e })?;
+    let mut desktops: Vec<String> = Vec::new();
+    unsafe {
+        EnumDesktopsW(
+            window_station,
+            Some(enum_desktops_proc),
+            LPARAM(&mut desktops as *mut Vec<String> as isize),
+        )
+    }
+    .map_err(|e| WindowInspectorError::EnumDesktopsWFailed { error: e })?;
+    Ok(desktops)
+}
+
+/// 获取桌面`desktop`（[`enumerate_desktops`]返回的名称）中的所有顶层窗口，按Z序从上到下排列。
+/// 返回的句柄只能用于查询，对其他桌面中的窗口进行的操作大多会失败。
+/// 普通程序无权打开`Winlogon`等安全桌面，此时返回错误；需要以SYSTEM身份运行（例如服务）。
+pub fn get_desktop_windows(desktop: &str) -> Result<Vec<usize>> {
+    let name = to_wide(desktop.as_ref())?;
+    let handle = unsafe {
+        OpenDesktopW(
+            PCWSTR(name.as_ptr()),
+            DESKTOP_CONTROL_FLAGS(0),
+            false,
+            DESKTOP_ENUMERATE.0 | DESKTOP_READOBJECTS.0,
+        )
+    }
+    .map_err(|e| WindowInspectorError::OpenDesktopWFailed {
+        desktop: desktop.to_string(),
+        error: e,
+    })?;
+    let mut windows: Vec<usize> = Vec::new();
+    let result = unsafe {
+        EnumDesktopWindows(
+            handle,
+            Some(enum_windows_proc),
+            LPARAM(&mut windows as *mut Vec<usize> as isize),
+        )
+    };
+    let _ = unsafe { CloseDesktop(handle) };
+    result.map_err(|e| WindowInspectorError::EnumDesktopWindowsFailed {
+        desktop: desktop.to_string(),
+        error: e,
+    })?;
+    Ok(windows)
+}
+
+#[test]
+fn test_enumerate_desktops() {
+    let desktops = enumerate_desktops().unwrap();
+    assert!(desktops.iter().any(|d| d.eq_ignore_ascii_case("Default")));
+    assert!(!get_desktop_windows("Default").unwrap().is_empty());
+}
@@ -0,0 +1,295 @@
+//! 创建用于测试的临时窗口。需要`test-utils`特性。
+//!
+//! 窗口在后台线程中创建，并由该线程处理消息，[`TestWindow`]被drop时关闭窗口并结束线程。
+//!
+//! # 示例
+// doctest编译库时没有cfg(test)，未启用test-utils特性时这个模块不存在。
+#![cfg_attr(feature = "test-utils", doc = "```")]
+#![cfg_attr(not(feature = "test-utils"), doc = "```ignore")]
+//! use window_inspector::find::get_hwnd;
+//! use window_inspector::test_support::TestWindowBuilder;
+//!
+//! let window = TestWindowBuilder::new().title("测试窗口").build().unwrap();
+//! assert_eq!(get_hwnd(window.class(), "测试窗口").unwrap(), window.hwnd());
+//! ```
+
+use std::ffi::c_void;
+use std::mem::size_of;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+use std::thread::JoinHandle;
+
+use widestring::U16CString;
+use windows::core::HRESULT;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::ERROR_CLASS_ALREADY_EXISTS;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::DispatchMessageW;
+use windows::Win32::UI::WindowsAndMessaging::GetMessageW;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::TranslateMessage;
+use windows::Win32::UI::WindowsAndMessaging::UnregisterClassW;
+use windows::Win32::UI::WindowsAndMessaging::HWND_MESSAGE;
+use windows::Win32::UI::WindowsAndMessaging::MSG;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DESTROY;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WS_OVERLAPPEDWINDOW;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+use crate::wide::to_wide;
+
+/// 用于生成不重复的窗口类名。
+static NEXT_CLASS_ID: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "system" fn test_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_DESTROY {
+        PostQuitMessage(0);
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// [`TestWindow`]的构建器。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestWindowBuilder {
+    class: Option<String>,
+    title: String,
+    xywh: (i32, i32, u32, u32),
+    visible: bool,
+    message_only: bool,
+}
+
+impl Default for TestWindowBuilder {
+    fn default() -> Self {
+        Self {
+            class: None,
+            title: String::new(),
+            xywh: (100, 100, 400, 300),
+            visible: true,
+            message_only: false,
+        }
+    }
+}
+
+impl TestWindowBuilder {
+    /// 创建默认的构建器：自动生成不重复的类名，标题为空，位于(100, 100)，大小为400x300，可见。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置窗口类名。默认自动生成不重复的类名，并行运行的测试不会互相找到对方的窗口。
+    pub fn class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// 设置窗口标题。
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// 设置窗口位置尺寸。
+    pub fn xywh(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.xywh = (x, y, width, height);
+        self
+    }
+
+    /// 设置窗口是否可见。显示窗口时不会激活它，不影响前台窗口。
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// 创建仅消息窗口（父窗口为`HWND_MESSAGE`）。仅消息窗口不可见，也不会被`FindWindowExW`、`EnumWindows`找到。
+    pub fn message_only(mut self, message_only: bool) -> Self {
+        self.message_only = message_only;
+        self
+    }
+
+    /// 在后台线程中创建窗口，等待创建完成后返回。
+    pub fn build(self) -> Result<TestWindow> {
+        let class = self.class.clone().unwrap_or_else(|| {
+            format!(
+                "window_inspector_test_{}_{}",
+                std::process::id(),
+                NEXT_CLASS_ID.fetch_add(1, Ordering::Relaxed)
+            )
+        });
+        let wide_class = to_wide(class.as_ref())?;
+        let wide_title = to_wide(self.title.as_ref())?;
+        let title = self.title.clone();
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            // HWND不能跨线程传递，使用usize；错误只传递HRESULT。
+            let hwnd = match self.create(&wide_class, &wide_title) {
+                Ok(hwnd) => hwnd,
+                Err(e) => {
+                    let _ = sender.send(Err(e));
+                    return;
+                }
+            };
+            let _ = sender.send(Ok(hwnd.0 as usize));
+            let mut msg = MSG::default();
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+                let _ = unsafe { TranslateMessage(&msg) };
+                unsafe { DispatchMessageW(&msg) };
+            }
+            // 同一个类还有其他窗口时会失败，忽略即可。
+            let _ = unsafe { UnregisterClassW(PCWSTR(wide_class.as_ptr()), None) };
+        });
+        match receiver.recv() {
+            Ok(Ok(hwnd)) => Ok(TestWindow {
+                hwnd,
+                class,
+                title,
+                thread: Some(thread),
+            }),
+            Ok(Err((registering, code))) => {
+                let _ = thread.join();
+                let error = windows::core::Error::from_hresult(code);
+                Err(if registering {
+                    WindowInspectorError::RegisterClassExWFailed { class, error }
+                } else {
+                    WindowInspectorError::CreateWindowExWFailed {
+                        class,
+                        title,
+                        error,
+                    }
+                })
+            }
+            Err(_) => panic!("创建测试窗口的线程意外退出"),
+        }
+    }
+
+    /// 注册窗口类并创建窗口。失败时返回(是否是注册窗口类失败, HRESULT)。
+    fn create(
+        &self,
+        class: &U16CString,
+        title: &U16CString,
+    ) -> std::result::Result<HWND, (bool, HRESULT)> {
+        let instance = unsafe { GetModuleHandleW(None) }.map_err(|e| (true, e.code()))?;
+        let window_class = WNDCLASSEXW {
+            cbSize: size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(test_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: PCWSTR(class.as_ptr()),
+            ..Default::default()
+        };
+        if unsafe { RegisterClassExW(&window_class) } == 0 {
+            let error = windows::core::Error::from_win32();
+            // 指定了相同类名的多个测试窗口共用一个窗口类。
+            if error.code() != HRESULT::from_win32(ERROR_CLASS_ALREADY_EXISTS.0) {
+                return Err((true, error.code()));
+            }
+        }
+        let (x, y, width, height) = self.xywh;
+        let (style, parent) = if self.message_only {
+            (WINDOW_STYLE(0), HWND_MESSAGE)
+        } else {
+            (WS_OVERLAPPEDWINDOW, HWND::default())
+        };
+        let hwnd = unsafe {
+            CreateWindowExW(
+                // 工具窗口不会出现在任务栏上。
+                WS_EX_TOOLWINDOW,
+                PCWSTR(class.as_ptr()),
+                PCWSTR(title.as_ptr()),
+                style,
+                x,
+                y,
+                width as i32,
+                height as i32,
+                parent,
+                None,
+                instance,
+                None,
+            )
+        }
+        .map_err(|e| (false, e.code()))?;
+        if self.visible && !self.message_only {
+            let _ = unsafe { ShowWindow(hwnd, SW_SHOWNOACTIVATE) };
+        }
+        Ok(hwnd)
+    }
+}
+
+/// 用于测试的临时窗口，drop时关闭。
+#[derive(Debug)]
+pub struct TestWindow {
+    hwnd: usize,
+    class: String,
+    title: String,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TestWindow {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 窗口类名。
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    /// 创建时的窗口标题。
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+}
+
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        let _ = unsafe {
+            PostMessageW(
+                HWND(self.hwnd as *mut c_void),
+                WM_CLOSE,
+                WPARAM::default(),
+                LPARAM::default(),
+            )
+        };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[test]
+fn test_message_only_window() {
+    use crate::exist::is_window_exist;
+    use crate::find::get_hwnd;
+
+    let window = TestWindowBuilder::new()
+        .title("message only")
+        .message_only(true)
+        .build()
+        .unwrap();
+    assert!(is_window_exist(window.hwnd()));
+    assert!(get_hwnd(window.class(), "message only").is_err());
+    let hwnd = window.hwnd();
+    drop(window);
+    assert!(!is_window_exist(hwnd));
+}
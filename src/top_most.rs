@@ -1,33 +1,23 @@
 use std::ffi::c_void;
 
-use windows::Win32::Foundation::GetLastError;
 use windows::Win32::Foundation::HWND;
-use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
-use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
-use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
-use windows::Win32::UI::WindowsAndMessaging::HWND_NOTOPMOST;
-use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
-use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
-use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
-use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
 
+use crate::backend::with_current_backend;
+use crate::enumerate::get_top_level_windows;
 use crate::error::WindowInspectorError;
 use crate::exist::is_window_exist;
+use crate::exist::with_validation;
+use crate::mode::dry_run;
 use crate::result::Result;
 
 /// 获取窗口置顶状态。
 pub fn get_window_top_most(hwnd: usize) -> Result<bool> {
-    if !is_window_exist(hwnd) {
-        return Err(WindowInspectorError::WindowNotExist {
-            hwnd: HWND(hwnd as *mut c_void),
-        });
-    }
-    match unsafe { GetWindowLongW(HWND(hwnd as *mut c_void), GWL_EXSTYLE) } {
-        0 => Err(WindowInspectorError::GetWindowLongWFailed {
-            error_code: unsafe { GetLastError() }.0,
-        }),
-        n => Ok((n as u32 & WS_EX_TOPMOST.0) != 0),
-    }
+    with_validation(hwnd, get_window_top_most_unchecked)
+}
+
+/// 获取窗口置顶状态，不检查窗口是否存在。
+pub(crate) fn get_window_top_most_unchecked(hwnd: usize) -> Result<bool> {
+    with_current_backend(|backend| backend.is_top_most(hwnd))
 }
 
 /// 设置窗口置顶状态。
@@ -37,27 +27,13 @@ fn set_window_top_most_status(hwnd: usize, is_top_most: bool) -> Result<()> {
             hwnd: HWND(hwnd as *mut c_void),
         });
     }
-    unsafe {
-        if let Err(e) = SetWindowPos(
-            HWND(hwnd as *mut c_void),
-            if is_top_most {
-                HWND_TOPMOST
-            } else {
-                HWND_NOTOPMOST
-            },
-            0,
-            0,
-            0,
-            0,
-            SWP_NOMOVE | SWP_NOSIZE,
-        ) {
-            return Err(WindowInspectorError::SetWindowPosFailed {
-                hwnd: HWND(hwnd as *mut c_void),
-                error_message: format!("{:?}", e),
-            });
-        }
+    if dry_run(format_args!(
+        "SetWindowPos {:#X} top most: {}",
+        hwnd, is_top_most
+    )) {
+        return Ok(());
     }
-    Ok(())
+    with_current_backend(|backend| backend.set_top_most(hwnd, is_top_most))
 }
 
 /// 设置窗口置顶。
@@ -70,6 +46,42 @@ pub fn cancel_window_top_most(hwnd: usize) -> Result<()> {
     set_window_top_most_status(hwnd, false)
 }
 
+/// 在drop时恢复窗口原来的置顶状态。
+/// 由[`set_window_top_most_scoped`]返回。恢复时窗口已经不存在或恢复失败都会被忽略。
+#[must_use = "guard被drop时会立即恢复原来的置顶状态"]
+#[derive(Debug)]
+pub struct TopMostGuard {
+    hwnd: usize,
+    original: bool,
+}
+
+impl TopMostGuard {
+    /// 窗口句柄。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 修改前是否置顶。
+    pub fn original(&self) -> bool {
+        self.original
+    }
+}
+
+impl Drop for TopMostGuard {
+    fn drop(&mut self) {
+        if is_window_exist(self.hwnd) {
+            let _ = set_window_top_most_status(self.hwnd, self.original);
+        }
+    }
+}
+
+/// 设置窗口置顶，返回的guard被drop时恢复原来的置顶状态。
+pub fn set_window_top_most_scoped(hwnd: usize) -> Result<TopMostGuard> {
+    let original = get_window_top_most(hwnd)?;
+    set_window_top_most(hwnd)?;
+    Ok(TopMostGuard { hwnd, original })
+}
+
 /// 切换窗口置顶状态。
 pub fn toggle_window_top_most(hwnd: usize) -> Result<()> {
     let is_top_most = get_window_top_most(hwnd)?;
@@ -79,3 +91,13 @@ pub fn toggle_window_top_most(hwnd: usize) -> Result<()> {
         set_window_top_most(hwnd)
     }
 }
+
+/// 获取所有置顶（`WS_EX_TOPMOST`）的顶层窗口，按Z序从上到下排列，包括不可见窗口。
+/// 可以用来排查“看不见的置顶窗口挡住了点击”之类的问题。
+pub fn get_all_top_most_windows() -> Result<Vec<usize>> {
+    Ok(get_top_level_windows()?
+        .into_iter()
+        // 枚举后关闭的窗口会获取失败，直接跳过。
+        .filter(|&hwnd| get_window_top_most_unchecked(hwnd).unwrap_or(false))
+        .collect())
+}
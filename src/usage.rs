@@ -0,0 +1,28 @@
+use std::mem::size_of;
+use std::time::Duration;
+
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::GetLastInputInfo;
+use windows::Win32::UI::Input::KeyboardAndMouse::LASTINPUTINFO;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+
+/// 获取用户空闲时长，即距离最后一次键盘或鼠标输入的时长。
+/// 是[`GetLastInputInfo`]的封装，只统计当前会话的输入。
+///
+/// [`GetLastInputInfo`]: https://microsoft.github.io/windows-docs-rs/doc/windows/Win32/UI/Input/KeyboardAndMouse/fn.GetLastInputInfo.html
+pub fn get_idle_duration() -> Result<Duration> {
+    let mut last_input_info = LASTINPUTINFO {
+        cbSize: size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut last_input_info) }.as_bool() {
+        return Err(WindowInspectorError::GetLastInputInfoFailed {
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    // 两者都是系统启动后的毫秒数，约49.7天回绕一次，使用wrapping_sub处理回绕。
+    let idle_millis = unsafe { GetTickCount() }.wrapping_sub(last_input_info.dwTime);
+    Ok(Duration::from_millis(idle_millis as u64))
+}
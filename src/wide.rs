@@ -0,0 +1,28 @@
+use std::ffi::OsStr;
+
+use widestring::U16CStr;
+use widestring::U16CString;
+use windows::core::PCWSTR;
+
+use crate::error::WindowInspectorError;
+use crate::result::Result;
+
+/// 将字符串编码为以0结尾的UTF-16字符串。
+/// 非UTF-8的`OsStr`（例如包含未配对代理项的窗口标题）会原样保留。
+/// # 错误
+/// 字符串中间含有`\0`时返回[`WindowInspectorError::StringContainsNul`]。
+pub(crate) fn to_wide(s: &OsStr) -> Result<U16CString> {
+    U16CString::from_os_str(s).map_err(|_| WindowInspectorError::StringContainsNul {
+        string: s.to_string_lossy().into_owned(),
+    })
+}
+
+/// 转换为[`PCWSTR`]，空字符串转换为空指针。
+/// 返回的指针借用`s`，不能比`s`活得更久。
+pub(crate) fn to_pcwstr_or_null(s: &U16CStr) -> PCWSTR {
+    if s.is_empty() {
+        PCWSTR::null()
+    } else {
+        PCWSTR(s.as_ptr())
+    }
+}
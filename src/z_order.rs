@@ -0,0 +1,366 @@
+//! 窗口Z序。
+
+use std::ffi::c_void;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+
+use lazy_static::lazy_static;
+use windows::core::s;
+use windows::core::w;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::LibraryLoader::GetProcAddress;
+use windows::Win32::UI::WindowsAndMessaging::GetAncestor;
+use windows::Win32::UI::WindowsAndMessaging::GetWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::GA_ROOT;
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+use windows::Win32::UI::WindowsAndMessaging::GW_HWNDPREV;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOP;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+
+use crate::enumerate::get_top_level_windows;
+use crate::error::WindowInspectorError;
+use crate::events::win_events;
+use crate::events::EventFilter;
+use crate::events::EventKinds;
+use crate::events::HookGuard;
+use crate::exist::is_window_exist;
+use crate::mode::dry_run;
+use crate::result::Result;
+
+/// `GetWindowBand`的类型。
+type GetWindowBandFn = unsafe extern "system" fn(HWND, *mut u32) -> BOOL;
+
+lazy_static! {
+    static ref GET_WINDOW_BAND: Option<GetWindowBandFn> = {
+        let module = unsafe { GetModuleHandleW(w!("user32.dll")) }.ok()?;
+        let function = unsafe { GetProcAddress(module, s!("GetWindowBand")) }?;
+        Some(unsafe {
+            std::mem::transmute::<unsafe extern "system" fn() -> isize, GetWindowBandFn>(function)
+        })
+    };
+    static ref ACTIVATION_TRACKING: Mutex<ActivationTracking> =
+        Mutex::new(ActivationTracking::default());
+}
+
+/// 前台窗口变化的记录，见[`start_activation_tracking`]。
+#[derive(Default)]
+struct ActivationTracking {
+    /// 存活的[`ActivationTracker`]数量。
+    trackers: usize,
+    /// 按最近激活排列的顶层窗口。没有在记录时为空。
+    order: Vec<usize>,
+    /// 前台窗口事件的订阅和处理事件的线程，最后一个[`ActivationTracker`]被drop时停止。
+    hook: Option<(HookGuard, JoinHandle<()>)>,
+}
+
+/// 窗口所在的Z序层级（band，未公开的`ZBID_*`）。
+/// 普通窗口和置顶窗口都在[`WindowBand::Desktop`]中，置顶只是同一层级内的排序。
+/// 开始菜单、通知中心、锁屏等系统界面位于更高的层级，因此`HWND_TOPMOST`无法盖住它们。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowBand {
+    Default,
+    /// 普通窗口、置顶窗口。
+    Desktop,
+    /// 带`uiAccess`的程序（如屏幕键盘、放大镜）。
+    UiAccess,
+    ImmersiveIhm,
+    ImmersiveNotification,
+    ImmersiveAppChrome,
+    ImmersiveMogo,
+    ImmersiveEdgy,
+    ImmersiveInactiveMobody,
+    ImmersiveInactiveDock,
+    ImmersiveActiveMobody,
+    ImmersiveActiveDock,
+    ImmersiveBackground,
+    ImmersiveSearch,
+    GenuineWindows,
+    ImmersiveRestricted,
+    SystemTools,
+    Lock,
+    AboveLockUx,
+    /// 未知的层级。
+    Other(u32),
+}
+
+impl From<u32> for WindowBand {
+    fn from(band: u32) -> Self {
+        match band {
+            0 => WindowBand::Default,
+            1 => WindowBand::Desktop,
+            2 => WindowBand::UiAccess,
+            3 => WindowBand::ImmersiveIhm,
+            4 => WindowBand::ImmersiveNotification,
+            5 => WindowBand::ImmersiveAppChrome,
+            6 => WindowBand::ImmersiveMogo,
+            7 => WindowBand::ImmersiveEdgy,
+            8 => WindowBand::ImmersiveInactiveMobody,
+            9 => WindowBand::ImmersiveInactiveDock,
+            10 => WindowBand::ImmersiveActiveMobody,
+            11 => WindowBand::ImmersiveActiveDock,
+            12 => WindowBand::ImmersiveBackground,
+            13 => WindowBand::ImmersiveSearch,
+            14 => WindowBand::GenuineWindows,
+            15 => WindowBand::ImmersiveRestricted,
+            16 => WindowBand::SystemTools,
+            17 => WindowBand::Lock,
+            18 => WindowBand::AboveLockUx,
+            band => WindowBand::Other(band),
+        }
+    }
+}
+
+impl WindowBand {
+    /// 是否位于普通窗口、置顶窗口之上的系统层级。
+    pub fn is_above_top_most(self) -> bool {
+        !matches!(self, WindowBand::Default | WindowBand::Desktop)
+    }
+}
+
+/// 获取窗口所在的Z序层级。
+/// 使用未公开的`GetWindowBand`，系统不支持时返回错误。
+pub fn get_window_band(hwnd: usize) -> Result<WindowBand> {
+    if !is_window_exist(hwnd) {
+        return Err(WindowInspectorError::WindowNotExist {
+            hwnd: HWND(hwnd as *mut c_void),
+        });
+    }
+    let get_window_band = GET_WINDOW_BAND.ok_or(WindowInspectorError::FunctionNotAvailable {
+        function: "GetWindowBand",
+    })?;
+    let mut band = 0;
+    if !unsafe { get_window_band(HWND(hwnd as *mut c_void), &mut band) }.as_bool() {
+        return Err(WindowInspectorError::GetWindowBandFailed {
+            hwnd: HWND(hwnd as *mut c_void),
+            error: windows::core::Error::from_win32(),
+        });
+    }
+    Ok(band.into())
+}
+
+fn is_top_most(hwnd: HWND) -> bool {
+    let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+    ex_style & WS_EX_TOPMOST.0 != 0
+}
+
+/// 计算把`hwnd`放到`other`正上方时`SetWindowPos`的`hWndInsertAfter`参数。已经在正上方时返回`None`。
+/// 插入到置顶窗口之后会使窗口变为置顶，所以`other`不是置顶窗口而上方是置顶窗口时使用`HWND_TOP`。
+pub(crate) fn insert_after_for_above(hwnd: HWND, other: HWND) -> Option<HWND> {
+    match unsafe { GetWindow(other, GW_HWNDPREV) } {
+        Ok(prev) if prev == hwnd => None,
+        Ok(prev) if is_top_most(other) || !is_top_most(prev) => Some(prev),
+        _ => Some(HWND_TOP),
+    }
+}
+
+/// 把`hwnd`放到`other`正上方。
+fn place_above(hwnd: usize, other: usize) {
+    let hwnd = HWND(hwnd as *mut c_void);
+    if let Some(insert_after) = insert_after_for_above(hwnd, HWND(other as *mut c_void)) {
+        let _ = unsafe {
+            SetWindowPos(
+                hwnd,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        };
+    }
+}
+
+/// 保持一个窗口在另一个窗口正上方，drop时停止。由[`keep_above`]返回。
+#[must_use = "guard被drop时会立即停止"]
+#[derive(Debug)]
+pub struct KeepAboveGuard {
+    hwnd: usize,
+    other: usize,
+    hook: Option<HookGuard>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl KeepAboveGuard {
+    /// 保持在上方的窗口。
+    pub fn hwnd(&self) -> usize {
+        self.hwnd
+    }
+
+    /// 位于下方的窗口。
+    pub fn other(&self) -> usize {
+        self.other
+    }
+}
+
+impl Drop for KeepAboveGuard {
+    fn drop(&mut self) {
+        // 卸载钩子后事件接收端断开，后台线程随之结束。
+        if let Some(hook) = self.hook.take() {
+            hook.shutdown();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 保持`hwnd`在Z序中位于`other_hwnd`正上方，而不是置顶，因此不会遮挡其他程序的窗口。
+/// `other_hwnd`被激活、还原或显示时（这些情况下它会移到`hwnd`上方）立即把`hwnd`放回它的上方。
+/// 任一窗口关闭后自动停止。
+pub fn keep_above(hwnd: usize, other_hwnd: usize) -> Result<KeepAboveGuard> {
+    for handle in [hwnd, other_hwnd] {
+        if !is_window_exist(handle) {
+            return Err(WindowInspectorError::WindowNotExist {
+                hwnd: HWND(handle as *mut c_void),
+            });
+        }
+    }
+    if dry_run(format_args!("keep {:#X} above {:#X}", hwnd, other_hwnd)) {
+        return Ok(KeepAboveGuard {
+            hwnd,
+            other: other_hwnd,
+            hook: None,
+            thread: None,
+        });
+    }
+    let events = win_events(&EventFilter {
+        hwnd: Some(other_hwnd),
+        event_kinds: EventKinds::FOREGROUND | EventKinds::RESTORE | EventKinds::SHOW,
+        ..Default::default()
+    })?;
+    place_above(hwnd, other_hwnd);
+    let (receiver, hook) = events.into_parts();
+    let thread = thread::spawn(move || {
+        for _ in receiver.iter() {
+            if !is_window_exist(hwnd) || !is_window_exist(other_hwnd) {
+                break;
+            }
+            place_above(hwnd, other_hwnd);
+        }
+    });
+    Ok(KeepAboveGuard {
+        hwnd,
+        other: other_hwnd,
+        hook: Some(hook),
+        thread: Some(thread),
+    })
+}
+
+fn is_visible(hwnd: usize) -> bool {
+    unsafe { IsWindowVisible(HWND(hwnd as *mut c_void)) }.as_bool()
+}
+
+/// 记录前台窗口变化，由[`start_activation_tracking`]返回。所有guard都被drop后停止记录并清空激活顺序。
+#[must_use = "guard被drop时会立即停止记录"]
+#[derive(Debug)]
+pub struct ActivationTracker {
+    _private: (),
+}
+
+impl Drop for ActivationTracker {
+    fn drop(&mut self) {
+        let hook = {
+            let mut tracking = ACTIVATION_TRACKING.lock().unwrap();
+            tracking.trackers -= 1;
+            if tracking.trackers > 0 {
+                return;
+            }
+            tracking.order.clear();
+            tracking.hook.take()
+        };
+        // 后台线程处理事件时需要加锁，释放锁后再等待它结束。
+        if let Some((hook, thread)) = hook {
+            hook.shutdown();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 开始在后台记录前台窗口的变化，返回的guard被drop时停止。
+/// 同时存在多个guard时共用一个订阅，最后一个被drop时才停止。
+pub fn start_activation_tracking() -> Result<ActivationTracker> {
+    let mut tracking = ACTIVATION_TRACKING.lock().unwrap();
+    if tracking.hook.is_none() {
+        let events = win_events(&EventFilter {
+            event_kinds: EventKinds::FOREGROUND,
+            ..Default::default()
+        })?;
+        // 开始记录前的顺序未知，用Z序近似。
+        tracking.order = get_top_level_windows()?
+            .into_iter()
+            .filter(|&hwnd| is_visible(hwnd))
+            .collect();
+        let (receiver, hook) = events.into_parts();
+        let thread = thread::spawn(move || {
+            for event in receiver.iter() {
+                // 前台事件可能来自子窗口，记录其顶层窗口。
+                let root = unsafe { GetAncestor(HWND(event.hwnd as *mut c_void), GA_ROOT) };
+                let hwnd = if root.is_invalid() {
+                    event.hwnd
+                } else {
+                    root.0 as usize
+                };
+                let order = &mut ACTIVATION_TRACKING.lock().unwrap().order;
+                order.retain(|&other| other != hwnd && is_window_exist(other));
+                order.insert(0, hwnd);
+            }
+        });
+        tracking.hook = Some((hook, thread));
+    }
+    tracking.trackers += 1;
+    Ok(ActivationTracker { _private: () })
+}
+
+/// 获取所有可见的顶层窗口，按最近激活的顺序排列（类似Alt+Tab的顺序），第一个是当前或最近的前台窗口。
+/// 激活顺序只在[`start_activation_tracking`]返回的guard存活期间记录。
+/// 没有在记录时激活记录为空，返回的就是Z序；开始记录前的激活顺序无法得知，同样用Z序代替。
+pub fn get_activation_order() -> Result<Vec<usize>> {
+    let mut order: Vec<usize> = ACTIVATION_TRACKING
+        .lock()
+        .unwrap()
+        .order
+        .iter()
+        .copied()
+        .filter(|&hwnd| is_window_exist(hwnd) && is_visible(hwnd))
+        .collect();
+    // 从未激活过的窗口（例如开始记录后新建但没有激活的窗口）按Z序放在最后。
+    let others: Vec<usize> = get_top_level_windows()?
+        .into_iter()
+        .filter(|hwnd| is_visible(*hwnd) && !order.contains(hwnd))
+        .collect();
+    order.extend(others);
+    Ok(order)
+}
+
+#[test]
+fn test_keep_above() {
+    use crate::test_support::TestWindowBuilder;
+
+    let below = TestWindowBuilder::new().build().unwrap();
+    let above = TestWindowBuilder::new().build().unwrap();
+    let _guard = keep_above(above.hwnd(), below.hwnd()).unwrap();
+    let prev = unsafe { GetWindow(HWND(below.hwnd() as *mut c_void), GW_HWNDPREV) }.unwrap();
+    assert_eq!(prev.0 as usize, above.hwnd());
+}
+
+#[test]
+fn test_activation_tracker() {
+    let first = start_activation_tracking().unwrap();
+    let second = start_activation_tracking().unwrap();
+    drop(first);
+    assert!(ACTIVATION_TRACKING.lock().unwrap().hook.is_some());
+    drop(second);
+    let tracking = ACTIVATION_TRACKING.lock().unwrap();
+    assert!(tracking.hook.is_none());
+    assert!(tracking.order.is_empty());
+}